@@ -0,0 +1,11 @@
+// the two commented out lines in main.rs that are supposed to break the build,
+// let used as an expression and a ; on a function's last line, each have a
+// copy in tests/compile_fail/. this checks rustc still rejects them with the
+// same error. if rustc changes its mind, the .stderr file won't match
+
+// regenerate the .stderr files with TRYBUILD=overwrite cargo test
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
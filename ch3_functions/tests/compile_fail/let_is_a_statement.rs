@@ -0,0 +1,4 @@
+// main.rs: let y = 6 is a statement, so there's nothing to bind to x
+fn main() {
+    let x = (let y = 6);
+}
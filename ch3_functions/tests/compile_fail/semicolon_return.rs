@@ -0,0 +1,6 @@
+// main.rs: the ; turns x + 1 into a statement, so _plus_one returns ()
+fn _plus_one(x: i32) -> i32 {
+    x + 1;
+}
+
+fn main() {}
@@ -98,5 +98,8 @@ fn main() {
 
     // access as normal. what happens with invalid access?
     // let _element = a[10];
-    // compiles fine, but runtime errors before the memory is accessed
+    // with an index only known at runtime this compiles fine, but panics
+    // before the memory is accessed. a constant index like 10 is caught early
+    // by the unconditional_panic lint when building (cargo check misses it, so
+    // it isn't in tests/compile_fail)
 }
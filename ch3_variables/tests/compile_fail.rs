@@ -0,0 +1,12 @@
+// the three commented out lines in main.rs that are supposed to break the
+// build, assigning to x without mut, assigning to a const and giving a mut
+// variable a new type, each have a copy in tests/compile_fail/. this checks
+// rustc still rejects them with the same error. if rustc changes its mind, the
+// .stderr file won't match. a[10] isn't here, see the comment next to it
+
+// regenerate the .stderr files with TRYBUILD=overwrite cargo test
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
@@ -0,0 +1,6 @@
+// main.rs: IMPORTANT_NUMBER = 3, consts can't be assigned to
+const IMPORTANT_NUMBER: u32 = 1;
+
+fn main() {
+    IMPORTANT_NUMBER = 3;
+}
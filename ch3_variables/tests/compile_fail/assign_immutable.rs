@@ -0,0 +1,7 @@
+// main.rs: let x = 5; then x = 6 without mut
+fn main() {
+    let x = 5;
+    println!("{}", x);
+    x = 6;
+    println!("{}", x);
+}
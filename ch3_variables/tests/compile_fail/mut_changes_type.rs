@@ -0,0 +1,5 @@
+// main.rs: mut doesn't let a variable change type, only shadowing does
+fn main() {
+    let mut _z = 5;
+    _z = "i'm also a string now!";
+}
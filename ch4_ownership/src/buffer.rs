@@ -0,0 +1,262 @@
+// a gap buffer: the text lives in one Vec<u8> with a hole (the gap) where the
+// cursor is. inserting at the cursor just fills in the gap, and moving the
+// cursor shuffles bytes across the gap. this is what a lot of editors do
+
+//     "hello w" [ . . . . gap . . . . ] "orld"
+//      ^ front half                      ^ back half
+
+// the borrow rules from references() show up everywhere here: slice() and
+// chars() hand out views borrowing &self, and insert()/delete() need
+// &mut self, so you can't edit the text while someone is still reading it
+use std::fmt;
+use std::ops::Range;
+use std::str;
+
+const MIN_GAP: usize = 16;
+
+pub struct GapBuffer {
+    buf: Vec<u8>,
+    gap_start: usize,
+    gap_end: usize,
+    undo: Vec<Edit>,
+}
+
+// enough info to reverse an edit. deletes have to keep the text they removed
+// since the buffer doesn't have it anymore
+enum Edit {
+    Insert { at: usize, len: usize },
+    Delete { at: usize, text: String },
+}
+
+/// A borrowed view into part of a [`GapBuffer`]. The range might straddle the
+/// gap, so it's up to two `&str`s back to back.
+#[derive(Debug, Clone, Copy)]
+pub struct Slice<'a> {
+    front: &'a str,
+    back: &'a str,
+}
+
+impl GapBuffer {
+    pub fn new() -> GapBuffer {
+        GapBuffer::from("")
+    }
+
+    /// Length of the text in bytes, not counting the gap.
+    pub fn len(&self) -> usize {
+        self.buf.len() - self.gap_len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Inserts `text` at byte offset `at`.
+    ///
+    /// Panics if `at` is out of bounds or not on a char boundary, like
+    /// `String::insert_str`.
+    pub fn insert(&mut self, at: usize, text: &str) {
+        self.assert_boundary(at);
+        self.raw_insert(at, text);
+        self.undo.push(Edit::Insert {
+            at,
+            len: text.len(),
+        });
+    }
+
+    /// Removes the bytes in `range` and gives them back.
+    ///
+    /// Panics if either end is out of bounds or not on a char boundary.
+    pub fn delete(&mut self, range: Range<usize>) -> String {
+        self.assert_range(&range);
+        let at = range.start;
+        let text = self.raw_delete(range);
+        self.undo.push(Edit::Delete {
+            at,
+            text: text.clone(),
+        });
+        text
+    }
+
+    /// Reverses the most recent insert or delete. Returns `false` if there
+    /// was nothing left to undo.
+    pub fn undo(&mut self) -> bool {
+        match self.undo.pop() {
+            Some(Edit::Insert { at, len }) => {
+                self.raw_delete(at..at + len);
+                true
+            }
+            Some(Edit::Delete { at, text }) => {
+                self.raw_insert(at, &text);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// A view of the bytes in `range`, borrowing the buffer.
+    pub fn slice(&self, range: Range<usize>) -> Slice<'_> {
+        self.assert_range(&range);
+
+        // the part before the gap, then the part after it. either can be empty
+        let front_end = range.end.min(self.gap_start);
+        let back_start = range.start.max(self.gap_start);
+        let front = if range.start < front_end {
+            self.text_at(range.start..front_end)
+        } else {
+            ""
+        };
+        let back = if back_start < range.end {
+            let gap = self.gap_len();
+            self.text_at(back_start + gap..range.end + gap)
+        } else {
+            ""
+        };
+
+        Slice { front, back }
+    }
+
+    /// Iterates over the chars of the whole buffer without copying it.
+    pub fn chars(&self) -> impl Iterator<Item = char> + '_ {
+        let all = self.slice(0..self.len());
+        all.front.chars().chain(all.back.chars())
+    }
+
+    fn gap_len(&self) -> usize {
+        self.gap_end - self.gap_start
+    }
+
+    // physical bytes to text. both halves only ever get split on char
+    // boundaries, so this can't fail
+    fn text_at(&self, physical: Range<usize>) -> &str {
+        str::from_utf8(&self.buf[physical]).expect("gap sits on a char boundary")
+    }
+
+    fn is_char_boundary(&self, at: usize) -> bool {
+        if at == 0 || at == self.len() {
+            return true;
+        }
+        let physical = if at < self.gap_start {
+            at
+        } else {
+            at + self.gap_len()
+        };
+        // utf-8 continuation bytes look like 0b10xx_xxxx
+        self.buf[physical] & 0b1100_0000 != 0b1000_0000
+    }
+
+    fn assert_boundary(&self, at: usize) {
+        assert!(at <= self.len(), "{} is past the end of the buffer", at);
+        assert!(self.is_char_boundary(at), "{} is not a char boundary", at);
+    }
+
+    fn assert_range(&self, range: &Range<usize>) {
+        assert!(range.start <= range.end, "range starts after it ends");
+        self.assert_boundary(range.start);
+        self.assert_boundary(range.end);
+    }
+
+    // slide the gap so it starts at logical offset `at`
+    fn move_gap(&mut self, at: usize) {
+        if at < self.gap_start {
+            // bytes between at and the gap hop over to the other side
+            let n = self.gap_start - at;
+            self.buf.copy_within(at..self.gap_start, self.gap_end - n);
+            self.gap_start -= n;
+            self.gap_end -= n;
+        } else if at > self.gap_start {
+            let n = at - self.gap_start;
+            self.buf
+                .copy_within(self.gap_end..self.gap_end + n, self.gap_start);
+            self.gap_start += n;
+            self.gap_end += n;
+        }
+    }
+
+    // make sure the gap has room for at least `needed` bytes
+    fn reserve(&mut self, needed: usize) {
+        if self.gap_len() >= needed {
+            return;
+        }
+        let new_gap = (needed + self.len()).max(MIN_GAP);
+        let back = self.buf.split_off(self.gap_end);
+        self.buf.truncate(self.gap_start);
+        self.buf.resize(self.gap_start + new_gap, 0);
+        self.gap_end = self.buf.len();
+        self.buf.extend_from_slice(&back);
+    }
+
+    fn raw_insert(&mut self, at: usize, text: &str) {
+        self.move_gap(at);
+        self.reserve(text.len());
+        let end = self.gap_start + text.len();
+        self.buf[self.gap_start..end].copy_from_slice(text.as_bytes());
+        self.gap_start = end;
+    }
+
+    fn raw_delete(&mut self, range: Range<usize>) -> String {
+        let removed = self.slice(range.clone()).to_string();
+        self.move_gap(range.start);
+        // deleting is just widening the gap over the old bytes
+        self.gap_end += range.end - range.start;
+        removed
+    }
+}
+
+impl Default for GapBuffer {
+    fn default() -> GapBuffer {
+        GapBuffer::new()
+    }
+}
+
+impl From<&str> for GapBuffer {
+    fn from(text: &str) -> GapBuffer {
+        let mut buf = Vec::with_capacity(text.len() + MIN_GAP);
+        buf.extend_from_slice(text.as_bytes());
+        let gap_start = buf.len();
+        buf.resize(gap_start + MIN_GAP, 0);
+        GapBuffer {
+            gap_end: buf.len(),
+            buf,
+            gap_start,
+            undo: Vec::new(),
+        }
+    }
+}
+
+impl fmt::Display for GapBuffer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.slice(0..self.len()).fmt(f)
+    }
+}
+
+impl<'a> Slice<'a> {
+    pub fn len(&self) -> usize {
+        self.front.len() + self.back.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The two halves of the view, either of which may be empty.
+    pub fn as_parts(&self) -> (&'a str, &'a str) {
+        (self.front, self.back)
+    }
+
+    pub fn chars(&self) -> impl Iterator<Item = char> + 'a {
+        self.front.chars().chain(self.back.chars())
+    }
+}
+
+impl fmt::Display for Slice<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.front, self.back)
+    }
+}
+
+// so we can assert_eq!(buf.slice(..), "text") without building a String
+impl PartialEq<&str> for Slice<'_> {
+    fn eq(&self, other: &&str) -> bool {
+        other.len() == self.len() && other.starts_with(self.front) && other.ends_with(self.back)
+    }
+}
@@ -0,0 +1,9 @@
+// the reusable bits of the ownership chapter live here, main.rs is still the
+// walkthrough that shows them off
+
+// gap buffer with undo, see src/buffer.rs
+pub mod buffer;
+// zero-copy tokens for config lines, see src/tokenizer.rs
+pub mod tokenizer;
+// unicode aware first_word and friends, see src/words.rs
+pub mod words;
@@ -4,6 +4,7 @@ fn main() {
     ownership_and_functions();
     references();
     slices();
+    tokenizer();
 }
 
 /*
@@ -235,6 +236,38 @@ fn references() {
         }
     }
 
+    // a bigger version of change(): a gap buffer (src/buffer.rs) that edits
+    // through &mut self and hands out views through &self
+    {
+        use ch4_ownership::buffer::GapBuffer;
+
+        let mut buf = GapBuffer::from("i am");
+        buf.insert(4, " tired");
+        buf.insert(0, "honestly, ");
+        let removed = buf.delete(10..15); // "i am "
+        println!("[references] buffer is '{}', deleted '{}'", buf, removed);
+
+        // slice borrows buf, so it's just a view, no copying
+        let view = buf.slice(0..8);
+        assert_eq!(view, "honestly");
+        println!("[references] view of the buffer: '{}'", view);
+
+        // while that view (or an iterator) is alive, buf is borrowed, so this
+        // won't compile: can't borrow buf as mutable while it's borrowed
+        {
+            let mut chars = buf.chars();
+            // buf.insert(0, "oh, ");
+            assert_eq!(chars.next(), Some('h'));
+        }
+
+        // chars went out of scope (an iterator might do something when it's
+        // dropped, so it holds the borrow until then), now we can edit again
+        buf.undo(); // put "i am " back
+        buf.undo(); // take "honestly, " out
+        assert_eq!(buf.to_string(), "i am tired");
+        println!("[references] buffer after two undos: '{}'", buf);
+    }
+
     // important note tho: you can't have two mutable references at the same
     // time, prevents data races
     {
@@ -296,19 +329,37 @@ fn slices() {
 
     // slices hold a start point and a length
     {
-        fn first_word(input: &str) -> &str {
-            for (i, &item) in input.as_bytes().iter().enumerate() {
-                if item == b' ' {
-                    return &input[..i]; // from 0 to i, not including i
-                }
-            }
-
-            &input // if no space, then the whole thing is the first word
-        }
+        // the book version scans bytes for b' ', which falls over on tabs,
+        // newlines, non-breaking spaces and leading whitespace. the real one
+        // lives in src/words.rs and walks grapheme clusters instead, but it
+        // still hands back a slice of the input
+        use ch4_ownership::words::{first_word, last_word, nth_word, words};
+
         let /*mut*/ s = String::from("the first word of this string is the!");
         let first = first_word(&s);
         // s.clear(); // can't happen, we're borrowing immutably in first
-        println!("[slices] first word of '{}' is '{}'", s, first);
+        println!("[slices] first word of '{}' is '{:?}'", s, first);
+
+        // none of these work with the byte version
+        let tricky = "\t  caf\u{e9}\u{a0}au\nlait \u{301}x  ";
+        assert_eq!(first_word(tricky), Some("caf\u{e9}"));
+        assert_eq!(nth_word(tricky, 1), Some("au"));
+        // " \u{301}" is a space wearing an accent, one grapheme that isn't
+        // blank, so it glues "lait" and "x" into one word
+        assert_eq!(last_word(tricky), Some("lait \u{301}x"));
+        assert_eq!(first_word(" \t\n"), None);
+
+        // every word is a real slice of the input, not a copy: its pointer
+        // lands somewhere inside the original string
+        for w in words(tricky) {
+            let offset = w.as_ptr() as usize - tricky.as_ptr() as usize;
+            assert_eq!(&tricky[offset..offset + w.len()], w);
+        }
+        println!(
+            "[slices] words of {:?} are {:?}",
+            tricky,
+            words(tricky).collect::<Vec<_>>()
+        );
     }
 
     // benefit of this is we return a slice/reference directly, so we're holding
@@ -324,3 +375,35 @@ fn slices() {
         println!("[slices] slice array to first 3 elements: {:?}", &a[..3]);
     }
 }
+
+fn tokenizer() {
+    // slices all the way down: the tokens borrow from the line, so the line
+    // has to outlive them (try dropping it before the loop, it won't compile,
+    // tests/compile_fail/tokens_outlive_line.rs makes sure)
+    use ch4_ownership::tokenizer::{tokenize, TokenKind};
+
+    let line = String::from("name = \"my server\" # port is 8080, timeout 0.5");
+    for token in tokenize(&line) {
+        let token = token.expect("quotes are balanced");
+        println!(
+            "[tokenizer] {:?} {:?} at {:?}",
+            token.kind, token.text, token.span
+        );
+        // the span always points back at the same text (plus quotes)
+        assert!(line[token.span.clone()].contains(token.text));
+    }
+
+    let bad = "key = \"oops";
+    assert_eq!(
+        tokenize(bad).last().unwrap().unwrap_err().to_string(),
+        "unterminated quote starting at byte 6"
+    );
+
+    // tests/tokenizer.rs counts allocations to check tokenize really makes
+    // none, next to split + to_string which makes one per word
+    let words = tokenize(&line)
+        .filter_map(Result::ok)
+        .filter(|t| t.kind == TokenKind::Word)
+        .count();
+    println!("[tokenizer] {} words, and not one String made", words);
+}
@@ -0,0 +1,139 @@
+// the slices demo grew up: a tokenizer that never allocates. every token is a
+// &str pointing back into the line it came from, plus the byte span it covers,
+// so the caller can report errors or re-slice the original if they want to
+
+// good enough for config style lines like
+//     name = "my server" # comment
+//     port = 8080
+use std::fmt;
+use std::ops::Range;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TokenKind {
+    /// Starts with a letter or `_`, then letters, digits, `_` or `-`.
+    Word,
+    /// ASCII digits with an optional fractional part, like `8080` or `0.5`.
+    Number,
+    /// Any other single non-whitespace character.
+    Punct,
+    /// Text between matching `"` or `'` quotes. Escapes are left as is.
+    Quoted,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub kind: TokenKind,
+    // for Quoted this is the inside of the quotes, the span still covers them
+    pub text: &'a str,
+    pub span: Range<usize>,
+}
+
+/// A quote was opened at byte `start` and never closed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct UnterminatedQuote {
+    pub start: usize,
+}
+
+impl fmt::Display for UnterminatedQuote {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unterminated quote starting at byte {}", self.start)
+    }
+}
+
+impl std::error::Error for UnterminatedQuote {}
+
+/// Iterator over the tokens of a string, see [`tokenize`].
+pub struct Tokenizer<'a> {
+    input: &'a str,
+    pos: usize, // byte offset of the next thing to look at
+}
+
+/// Splits `input` into tokens, skipping the whitespace between them. An
+/// unclosed quote is an error, and the last item.
+pub fn tokenize(input: &str) -> Tokenizer<'_> {
+    Tokenizer { input, pos: 0 }
+}
+
+impl<'a> Tokenizer<'a> {
+    // the rest of the input we haven't looked at yet
+    fn rest(&self) -> &'a str {
+        &self.input[self.pos..]
+    }
+
+    // byte length of the longest prefix of rest() where every char passes
+    fn prefix_len(&self, pred: impl Fn(char) -> bool) -> usize {
+        self.rest()
+            .char_indices()
+            .find(|&(_, c)| !pred(c))
+            .map_or(self.rest().len(), |(i, _)| i)
+    }
+
+    fn token(&mut self, kind: TokenKind, len: usize) -> Token<'a> {
+        let span = self.pos..self.pos + len;
+        self.pos += len;
+        Token {
+            kind,
+            text: &self.input[span.clone()],
+            span,
+        }
+    }
+
+    fn quoted(&mut self, quote: char) -> Result<Token<'a>, UnterminatedQuote> {
+        let start = self.pos;
+        let mut escaped = false;
+
+        // skip the opening quote, then look for an unescaped closing one
+        for (i, c) in self.rest().char_indices().skip(1) {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == quote {
+                let end = start + i + c.len_utf8();
+                self.pos = end;
+                return Ok(Token {
+                    kind: TokenKind::Quoted,
+                    text: &self.input[start + 1..start + i],
+                    span: start..end,
+                });
+            }
+        }
+
+        // nothing sensible to hand out after this, so stop for good
+        self.pos = self.input.len();
+        Err(UnterminatedQuote { start })
+    }
+
+    fn number(&mut self) -> Token<'a> {
+        let mut len = self.prefix_len(|c| c.is_ascii_digit());
+        let after = &self.rest()[len..];
+
+        // only eat the '.' if a digit follows, so "1." is Number then Punct
+        if after.starts_with('.') && after[1..].starts_with(|c: char| c.is_ascii_digit()) {
+            len += 1 + after[1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len() - 1);
+        }
+
+        self.token(TokenKind::Number, len)
+    }
+}
+
+impl<'a> Iterator for Tokenizer<'a> {
+    type Item = Result<Token<'a>, UnterminatedQuote>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.pos += self.prefix_len(char::is_whitespace);
+        let c = self.rest().chars().next()?;
+
+        Some(match c {
+            '"' | '\'' => self.quoted(c),
+            '0'..='9' => Ok(self.number()),
+            c if c.is_alphabetic() || c == '_' => {
+                let len = self.prefix_len(|c| c.is_alphanumeric() || c == '_' || c == '-');
+                Ok(self.token(TokenKind::Word, len))
+            }
+            c => Ok(self.token(TokenKind::Punct, c.len_utf8())),
+        })
+    }
+}
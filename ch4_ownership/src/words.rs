@@ -0,0 +1,84 @@
+// the first_word from the slices demo only knows about b' ', so tabs, newlines,
+// non-breaking spaces and leading spaces all confuse it. this does the same
+// job but walks grapheme clusters instead of bytes, so every slice we hand
+// back starts and ends on a boundary a human would agree with
+
+// still no allocation: everything returned is a &str borrowed from the input,
+// same deal as the original first_word
+use unicode_segmentation::{GraphemeIndices, UnicodeSegmentation};
+
+/// Iterator over the whitespace separated words of a string, see [`words`].
+pub struct Words<'a> {
+    input: &'a str,
+    graphemes: GraphemeIndices<'a>,
+}
+
+/// Splits `input` on Unicode whitespace, skipping empty words.
+pub fn words(input: &str) -> Words<'_> {
+    Words {
+        input,
+        graphemes: input.grapheme_indices(true),
+    }
+}
+
+/// The first word of `input`, or `None` if it's all whitespace.
+pub fn first_word(input: &str) -> Option<&str> {
+    words(input).next()
+}
+
+/// The `n`th word of `input` (starting from 0).
+pub fn nth_word(input: &str, n: usize) -> Option<&str> {
+    words(input).nth(n)
+}
+
+/// The last word of `input`, found by walking backwards from the end.
+pub fn last_word(input: &str) -> Option<&str> {
+    words(input).next_back()
+}
+
+// a grapheme like " \u{301}" (space + combining accent) is one cluster that
+// starts with a space. it isn't really blank, so only count a grapheme as
+// whitespace if every char in it is
+fn is_blank(grapheme: &str) -> bool {
+    grapheme.chars().all(char::is_whitespace)
+}
+
+impl<'a> Iterator for Words<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        // skip leading whitespace, the first non-blank grapheme starts a word
+        let (start, first) = self.graphemes.find(|(_, g)| !is_blank(g))?;
+        let mut end = start + first.len();
+
+        // keep going until whitespace or we run out. the whitespace grapheme
+        // gets eaten too, which is fine since it isn't part of any word
+        for (i, g) in &mut self.graphemes {
+            if is_blank(g) {
+                break;
+            }
+            end = i + g.len();
+        }
+
+        Some(&self.input[start..end])
+    }
+}
+
+// same thing but from the back. front and back share the one grapheme
+// iterator, so they can never hand out overlapping words
+impl<'a> DoubleEndedIterator for Words<'a> {
+    fn next_back(&mut self) -> Option<&'a str> {
+        let (last, g) = self.graphemes.rfind(|(_, g)| !is_blank(g))?;
+        let end = last + g.len();
+        let mut start = last;
+
+        while let Some((i, g)) = self.graphemes.next_back() {
+            if is_blank(g) {
+                break;
+            }
+            start = i;
+        }
+
+        Some(&self.input[start..end])
+    }
+}
@@ -0,0 +1,114 @@
+// GapBuffer against a plain String doing the same edits. the text is full of
+// multi-byte chars so the gap keeps getting moved across them
+use ch4_ownership::buffer::GapBuffer;
+
+const TEXT: &str = "héllo wörld, 🦀 says\thi";
+
+fn boundaries(s: &str) -> Vec<usize> {
+    s.char_indices()
+        .map(|(i, _)| i)
+        .chain(Some(s.len()))
+        .collect()
+}
+
+#[test]
+fn insert_at_every_boundary() {
+    for at in boundaries(TEXT) {
+        let mut buf = GapBuffer::from(TEXT);
+        let mut expected = String::from(TEXT);
+
+        // in the middle first so the gap has to come back to the start after
+        buf.insert(at, "→ü");
+        expected.insert_str(at, "→ü");
+        assert_eq!(buf.to_string(), expected);
+
+        buf.insert(0, "ß");
+        expected.insert(0, 'ß');
+        assert_eq!(buf.to_string(), expected);
+        assert_eq!(buf.len(), expected.len());
+    }
+}
+
+#[test]
+fn delete_between_every_pair_of_boundaries() {
+    let all = boundaries(TEXT);
+    for &start in &all {
+        for &end in all.iter().filter(|&&end| end >= start) {
+            let mut buf = GapBuffer::from(TEXT);
+            // park the gap somewhere else first
+            buf.insert(TEXT.len(), "");
+            buf.insert(0, "");
+
+            let mut expected = String::from(TEXT);
+            let removed: String = expected.drain(start..end).collect();
+            assert_eq!(buf.delete(start..end), removed);
+            assert_eq!(buf.to_string(), expected);
+        }
+    }
+}
+
+#[test]
+fn slices_straddle_the_gap() {
+    let mut buf = GapBuffer::from(TEXT);
+    let at = TEXT.find('🦀').unwrap();
+    buf.insert(at, "big ");
+    let expected = buf.to_string();
+
+    // the gap is right after "big ", so this range has text on both sides
+    let view = buf.slice(at - 2..at + 8);
+    let (front, back) = view.as_parts();
+    assert!(!front.is_empty() && !back.is_empty());
+    assert_eq!(view, &expected[at - 2..at + 8]);
+    assert_eq!(view.chars().collect::<String>(), &expected[at - 2..at + 8]);
+    assert!(buf.chars().eq(expected.chars()));
+}
+
+#[test]
+fn grows_past_the_first_gap() {
+    let mut buf = GapBuffer::new();
+    let mut expected = String::new();
+    for i in 0..50 {
+        let at = boundaries(&expected)[i % (expected.chars().count() + 1)];
+        buf.insert(at, "ünï ");
+        expected.insert_str(at, "ünï ");
+    }
+    assert_eq!(buf.to_string(), expected);
+}
+
+#[test]
+fn undo_walks_back_through_every_edit() {
+    let mut buf = GapBuffer::from(TEXT);
+    let mut history = vec![buf.to_string()];
+
+    buf.insert(6, "ñew ");
+    history.push(buf.to_string());
+    buf.delete(0..6);
+    history.push(buf.to_string());
+    let crab = buf.to_string().find('🦀').unwrap();
+    buf.delete(crab..crab + '🦀'.len_utf8());
+    history.push(buf.to_string());
+    buf.insert(buf.len(), " 👋");
+    history.push(buf.to_string());
+
+    while let Some(state) = history.pop() {
+        assert_eq!(buf.to_string(), state);
+        assert_eq!(buf.undo(), !history.is_empty());
+    }
+    assert_eq!(buf.to_string(), TEXT);
+    assert!(!buf.undo());
+}
+
+#[test]
+#[should_panic(expected = "not a char boundary")]
+fn insert_inside_a_char_panics() {
+    let mut buf = GapBuffer::from(TEXT);
+    buf.insert(2, "x"); // the middle of é
+}
+
+#[test]
+#[should_panic(expected = "not a char boundary")]
+fn delete_inside_a_char_panics() {
+    let mut buf = GapBuffer::from(TEXT);
+    buf.insert(0, "🦀");
+    buf.delete(0..2);
+}
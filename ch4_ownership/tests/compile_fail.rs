@@ -0,0 +1,12 @@
+// every commented out line in main.rs that's supposed to break the build has a
+// copy in tests/compile_fail/, named after what it gets wrong, alongside ones
+// for the library: editing a GapBuffer while a borrow of it is alive, and
+// dropping a line while its tokens are. this checks rustc still rejects each
+// with the same error. if rustc changes its mind, the .stderr file won't match
+
+// regenerate the .stderr files with TRYBUILD=overwrite cargo test
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
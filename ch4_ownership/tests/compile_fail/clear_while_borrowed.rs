@@ -0,0 +1,9 @@
+// slices: first borrows s, so s can't be cleared until we're done with first
+use ch4_ownership::words::first_word;
+
+fn main() {
+    let mut s = String::from("the first word of this string is the!");
+    let first = first_word(&s);
+    s.clear();
+    println!("{:?}", first);
+}
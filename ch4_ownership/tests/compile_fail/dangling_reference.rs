@@ -0,0 +1,10 @@
+// references: s is dropped at the end of dangle, so there's nothing to point at
+fn dangle() -> &String {
+    let s = String::from("not long for this world");
+
+    &s
+}
+
+fn main() {
+    dangle();
+}
@@ -0,0 +1,9 @@
+// references: chars() borrows the buffer, so no inserting until it's gone
+use ch4_ownership::buffer::GapBuffer;
+
+fn main() {
+    let mut buf = GapBuffer::from("honestly, tired");
+    let mut chars = buf.chars();
+    buf.insert(0, "oh, ");
+    println!("{:?}", chars.next());
+}
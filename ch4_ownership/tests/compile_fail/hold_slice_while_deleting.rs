@@ -0,0 +1,9 @@
+// references: a slice of the buffer is a borrow too, same deal as chars()
+use ch4_ownership::buffer::GapBuffer;
+
+fn main() {
+    let mut buf = GapBuffer::from("honestly, tired");
+    let view = buf.slice(0..8);
+    buf.delete(0..10);
+    println!("{}", view);
+}
@@ -0,0 +1,10 @@
+// references: can't modify through a plain & reference
+fn calculate_len(input: &String) -> usize {
+    input.push_str(" string");
+    input.len()
+}
+
+fn main() {
+    let s1 = String::from("stringy");
+    println!("{}", calculate_len(&s1));
+}
@@ -0,0 +1,11 @@
+// ownership_and_functions: s2 was moved into modify_string, only s3 is valid
+fn modify_string(mut old_str: String) -> String {
+    old_str.push_str(" string string");
+    old_str
+}
+
+fn main() {
+    let s2 = String::from("stringy");
+    let s3 = modify_string(s2);
+    println!("{} {}", s2, s3);
+}
@@ -0,0 +1,10 @@
+// ownership_and_functions: s was moved into takes_ownership
+fn takes_ownership(a_string: String) {
+    println!("{}", a_string);
+}
+
+fn main() {
+    let s = String::from("red shirt");
+    takes_ownership(s);
+    println!("{}", s);
+}
@@ -0,0 +1,8 @@
+// references: using r1 after r2 stretches the mutable borrow over the
+// immutable one
+fn main() {
+    let mut s = String::from("one at a time");
+    let r1 = &mut s;
+    let r2 = &s;
+    println!("{} {}", r1, r2);
+}
@@ -0,0 +1,11 @@
+// tokenizer: tokens borrow the line, so the line has to outlive them
+use ch4_ownership::tokenizer::tokenize;
+
+fn main() {
+    let line = String::from("port = 8080");
+    let tokens = tokenize(&line);
+    drop(line);
+    for token in tokens {
+        println!("{:?}", token);
+    }
+}
@@ -0,0 +1,9 @@
+// references: only one mutable reference at a time
+fn main() {
+    let mut s = String::from("one at a time");
+
+    let r1 = &mut s;
+    let r2 = &mut s;
+
+    println!("{} {}", r1, r2);
+}
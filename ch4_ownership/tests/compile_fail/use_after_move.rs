@@ -0,0 +1,8 @@
+// move_clone_copy: s1 was moved into s2, so s1 can't be used anymore
+fn main() {
+    let s1 = String::from("i am string");
+    let s2 = s1;
+
+    println!("{}", s2);
+    println!("{}", s1);
+}
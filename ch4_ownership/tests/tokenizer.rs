@@ -0,0 +1,179 @@
+// the tokenizer's output, and the claim that it never allocates. that one
+// needs a global allocator that counts, which is fine here since every file
+// in tests/ is its own binary with its own allocator
+use ch4_ownership::tokenizer::{tokenize, Token, TokenKind, UnterminatedQuote};
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+
+// counts every allocation, then hands off to the normal system allocator.
+// tests run on several threads at once, so each thread keeps its own count
+// and one test's allocations don't show up in another's
+struct CountingAlloc;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        // try_with, since a thread that's shutting down can still allocate
+        let _ = ALLOCATIONS.try_with(|n| n.set(n.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAlloc = CountingAlloc;
+
+// how many allocations f makes on this thread
+fn count_allocations(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+fn kinds_and_text(input: &str) -> Vec<(TokenKind, &str)> {
+    tokenize(input)
+        .map(|t| {
+            let t = t.expect("no unterminated quotes");
+            (t.kind, t.text)
+        })
+        .collect()
+}
+
+const LINES: [&str; 4] = [
+    "name = \"my server\"",
+    "port = 8080",
+    "ratio = 0.75 # comment",
+    "tags = [alpha, beta-2, 'gamma']",
+];
+
+#[test]
+fn tokenizing_allocates_nothing() {
+    let zero_copy = count_allocations(|| {
+        for _ in 0..1000 {
+            for line in LINES.iter() {
+                let words = tokenize(line)
+                    .filter_map(Result::ok)
+                    .filter(|t| t.kind == TokenKind::Word)
+                    .count();
+                assert!(words > 0);
+            }
+        }
+    });
+    assert_eq!(zero_copy, 0);
+
+    // and the counter does work: the usual split + to_string makes a String
+    // per word, plus the Vec
+    let baseline = count_allocations(|| {
+        for _ in 0..1000 {
+            for line in LINES.iter() {
+                let owned: Vec<String> = line.split_whitespace().map(str::to_string).collect();
+                assert!(!owned.is_empty());
+            }
+        }
+    });
+    assert!(baseline >= 4000, "only {} allocations", baseline);
+}
+
+#[test]
+fn config_line() {
+    use self::TokenKind::*;
+    assert_eq!(
+        kinds_and_text("name = \"my server\" # port 8080"),
+        [
+            (Word, "name"),
+            (Punct, "="),
+            (Quoted, "my server"),
+            (Punct, "#"),
+            (Word, "port"),
+            (Number, "8080"),
+        ]
+    );
+}
+
+#[test]
+fn words() {
+    use self::TokenKind::*;
+    assert_eq!(
+        kinds_and_text("_private beta-2 caf\u{e9} 日本語"),
+        [
+            (Word, "_private"),
+            (Word, "beta-2"),
+            (Word, "caf\u{e9}"),
+            (Word, "日本語"),
+        ]
+    );
+    // a word can't start with a - or a digit
+    assert_eq!(
+        kinds_and_text("-x 2x"),
+        [(Punct, "-"), (Word, "x"), (Number, "2"), (Word, "x")]
+    );
+}
+
+#[test]
+fn numbers() {
+    use self::TokenKind::*;
+    assert_eq!(kinds_and_text("0.75"), [(Number, "0.75")]);
+    // the . only belongs to the number if a digit follows it
+    assert_eq!(kinds_and_text("1."), [(Number, "1"), (Punct, ".")]);
+    assert_eq!(
+        kinds_and_text("1.x"),
+        [(Number, "1"), (Punct, "."), (Word, "x")]
+    );
+    assert_eq!(
+        kinds_and_text("1.2.3"),
+        [(Number, "1.2"), (Punct, "."), (Number, "3")]
+    );
+    assert_eq!(kinds_and_text(".5"), [(Punct, "."), (Number, "5")]);
+}
+
+#[test]
+fn quotes() {
+    use self::TokenKind::*;
+    assert_eq!(
+        kinds_and_text(r#"'single' "dou'ble" "esc\"aped" """#),
+        [
+            (Quoted, "single"),
+            (Quoted, "dou'ble"),
+            (Quoted, r#"esc\"aped"#),
+            (Quoted, ""),
+        ]
+    );
+
+    let tokens: Vec<_> = tokenize(r#"a = "oops"#).collect();
+    assert_eq!(tokens.len(), 3);
+    assert_eq!(tokens[2], Err(UnterminatedQuote { start: 4 }));
+    // an escaped closing quote doesn't close it either
+    assert_eq!(
+        tokenize(r#"'it\'"#).last(),
+        Some(Err(UnterminatedQuote { start: 0 }))
+    );
+}
+
+#[test]
+fn spans_point_back_at_the_input() {
+    let input = " \u{a0}név = \"日本\"\t# 3.14 'x'\n";
+    for token in tokenize(input) {
+        let Token { kind, text, span } = token.unwrap();
+        let covered = &input[span.clone()];
+        if kind == TokenKind::Quoted {
+            assert_eq!(&covered[1..covered.len() - 1], text);
+        } else {
+            assert_eq!(covered, text);
+        }
+        // and text is borrowed from input, not a copy
+        let offset = text.as_ptr() as usize - input.as_ptr() as usize;
+        assert!(span.start <= offset && offset + text.len() <= span.end);
+    }
+}
+
+#[test]
+fn nothing_to_tokenize() {
+    assert_eq!(tokenize("").count(), 0);
+    assert_eq!(tokenize(" \t\n\u{a0}").count(), 0);
+}
@@ -0,0 +1,147 @@
+// properties of src/words.rs over random strings from a seeded rng, so
+// failures repeat. the strings are glued together from pieces picked to be
+// awkward: multi-byte chars, every kind of whitespace, combining accents and
+// emoji that are several chars but one grapheme
+use ch4_ownership::words::{first_word, last_word, nth_word, words};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ROUNDS: usize = 2000;
+
+const WORDY: &[&str] = &[
+    "a",
+    "Z",
+    "42",
+    "caf\u{e9}",
+    "e\u{301}",
+    "日本",
+    "🦀",
+    "👩\u{200d}👩\u{200d}👧",
+    "-",
+    "!",
+];
+const BLANK: &[&str] = &[" ", "\t", "\n", "\r\n", "\u{a0}", "\u{3000}", "\u{2009}"];
+// a space wearing an accent is one grapheme, and not a blank one
+const GLUE: &[&str] = &[" \u{301}", "\u{301}"];
+
+fn random_text(rng: &mut StdRng) -> String {
+    let len = rng.gen_range(0..16);
+    (0..len)
+        .map(|_| match rng.gen_range(0..10) {
+            0..=4 => WORDY[rng.gen_range(0..WORDY.len())],
+            5..=8 => BLANK[rng.gen_range(0..BLANK.len())],
+            _ => GLUE[rng.gen_range(0..GLUE.len())],
+        })
+        .collect()
+}
+
+// where `word` sits in `input`, checking it really is a slice of it: inside
+// its bytes, on char boundaries at both ends, and the same as slicing there
+fn offset_in(input: &str, word: &str) -> usize {
+    let start = input.as_ptr() as usize;
+    let at = word.as_ptr() as usize;
+    assert!(
+        at >= start && at + word.len() <= start + input.len(),
+        "{:?} isn't inside {:?}",
+        word,
+        input
+    );
+
+    let offset = at - start;
+    assert!(input.is_char_boundary(offset));
+    assert!(input.is_char_boundary(offset + word.len()));
+    let sliced = &input[offset..offset + word.len()];
+    assert_eq!(sliced, word);
+    assert_eq!(sliced.as_ptr(), word.as_ptr());
+    offset
+}
+
+#[test]
+fn every_word_is_a_slice_of_the_input() {
+    let mut rng = StdRng::seed_from_u64(26);
+    for _ in 0..ROUNDS {
+        let input = random_text(&mut rng);
+
+        let forward: Vec<&str> = words(&input).collect();
+        let mut backward: Vec<&str> = words(&input).rev().collect();
+        let mut end = 0;
+        for &word in &forward {
+            assert!(!word.is_empty());
+            // in order, and never overlapping
+            let offset = offset_in(&input, word);
+            assert!(offset >= end, "{:?} overlaps in {:?}", word, input);
+            end = offset + word.len();
+        }
+        for &word in &backward {
+            offset_in(&input, word);
+        }
+        backward.reverse();
+        assert_eq!(forward, backward, "{:?}", input);
+
+        for word in first_word(&input).into_iter().chain(last_word(&input)) {
+            offset_in(&input, word);
+        }
+        assert_eq!(first_word(&input), forward.first().copied());
+        assert_eq!(last_word(&input), forward.last().copied());
+        for n in 0..=forward.len() {
+            assert_eq!(nth_word(&input, n), forward.get(n).copied());
+        }
+    }
+}
+
+#[test]
+fn both_ends_meet_in_the_middle() {
+    let mut rng = StdRng::seed_from_u64(2026);
+    for _ in 0..ROUNDS {
+        let input = random_text(&mut rng);
+        let all: Vec<&str> = words(&input).collect();
+
+        // take from whichever end at random, the words still come out once each
+        let mut iter = words(&input);
+        let (mut front, mut back) = (Vec::new(), Vec::new());
+        loop {
+            let word = if rng.gen() {
+                iter.next().map(|w| front.push(w))
+            } else {
+                iter.next_back().map(|w| back.push(w))
+            };
+            if word.is_none() {
+                break;
+            }
+        }
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, all, "{:?}", input);
+    }
+}
+
+#[test]
+fn same_as_split_whitespace_without_combining_marks() {
+    // with nothing gluing a space to what's next, graphemes don't change
+    // where the words break
+    let mut rng = StdRng::seed_from_u64(7);
+    for _ in 0..ROUNDS {
+        let input = random_text(&mut rng).replace('\u{301}', "");
+        assert!(words(&input).eq(input.split_whitespace()), "{:?}", input);
+    }
+}
+
+#[test]
+fn whitespace_only_has_no_words() {
+    for input in &["", " ", "\t\n\r\n", "\u{a0}\u{3000} \u{2009}"] {
+        assert_eq!(words(input).count(), 0);
+        assert_eq!(first_word(input), None);
+        assert_eq!(last_word(input), None);
+        assert_eq!(nth_word(input, 0), None);
+    }
+}
+
+#[test]
+fn accents_stay_with_their_letters() {
+    let input = "  e\u{301}t\u{e9} \u{301}x\u{a0}🦀\n";
+    assert_eq!(
+        words(input).collect::<Vec<_>>(),
+        ["e\u{301}t\u{e9} \u{301}x", "🦀"]
+    );
+    assert_eq!(first_word("\u{301}abc def"), Some("\u{301}abc"));
+}
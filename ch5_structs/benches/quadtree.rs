@@ -0,0 +1,104 @@
+// the quadtree against just checking every rectangle, with 100k of them.
+// run with
+//
+//     cargo bench --bench quadtree
+//
+// no benchmarking crate, just Instant, so treat the numbers as rough
+use ch5_structs::geometry::quadtree::{distance_squared, QuadTree};
+use ch5_structs::geometry::{Point, Rectangle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const COUNT: usize = 100_000;
+const QUERIES: usize = 10_000;
+// the naive scan is slow enough that it only gets a sample
+const NAIVE_QUERIES: usize = 100;
+const SIZE: i32 = 10_000;
+
+fn random_point(rng: &mut StdRng) -> Point {
+    Point::new(rng.gen_range(0..SIZE), rng.gen_range(0..SIZE))
+}
+
+fn per_query(total: Duration, n: usize) -> String {
+    format!("{:.1?}", total / n as u32)
+}
+
+// the same three questions both ways, as indexes into rects so the answers
+// can be compared
+fn ask_tree(tree: &QuadTree<usize>, p: &Point) -> Vec<usize> {
+    let to_index = |id| *tree.get(id).unwrap().1;
+    let region = Rectangle::at(*p, 200, 200);
+    let mut found: Vec<usize> = tree.query_point(p).into_iter().map(to_index).collect();
+    found.extend(tree.query_region(&region).into_iter().map(to_index));
+    found.extend(tree.nearest(p).map(to_index));
+    found
+}
+
+fn ask_naive(rects: &[Rectangle], p: &Point) -> Vec<usize> {
+    let region = Rectangle::at(*p, 200, 200);
+    let mut found: Vec<usize> = (0..rects.len())
+        .filter(|&i| rects[i].contains_point(p))
+        .collect();
+    found.extend((0..rects.len()).filter(|&i| {
+        let r = &rects[i];
+        i64::from(r.x) <= region.right()
+            && i64::from(region.x) <= r.right()
+            && i64::from(r.y) <= region.bottom()
+            && i64::from(region.y) <= r.bottom()
+    }));
+    found.extend((0..rects.len()).min_by_key(|&i| distance_squared(&rects[i], p)));
+    found
+}
+
+fn main() {
+    // seeded, so every run uses the same rectangles
+    let mut rng = StdRng::seed_from_u64(5);
+    let rects: Vec<Rectangle> = (0..COUNT)
+        .map(|_| {
+            let corner = random_point(&mut rng);
+            Rectangle::at(corner, rng.gen_range(1..50), rng.gen_range(1..50))
+        })
+        .collect();
+    let points: Vec<Point> = (0..QUERIES).map(|_| random_point(&mut rng)).collect();
+
+    let start = Instant::now();
+    let mut tree = QuadTree::new(Rectangle::square(SIZE as u32));
+    for (i, rect) in rects.iter().enumerate() {
+        tree.insert(*rect, i);
+    }
+    println!("built {} rectangles in {:.1?}", tree.len(), start.elapsed());
+
+    let start = Instant::now();
+    let answers: Vec<Vec<usize>> = points
+        .iter()
+        .map(|p| ask_tree(&tree, black_box(p)))
+        .collect();
+    let fast = start.elapsed();
+    println!(
+        "quadtree: {} point + region + nearest queries in {:.1?}, {} each",
+        QUERIES,
+        fast,
+        per_query(fast, QUERIES)
+    );
+
+    let sample = &points[..NAIVE_QUERIES];
+    let start = Instant::now();
+    let expected: Vec<Vec<usize>> = sample
+        .iter()
+        .map(|p| ask_naive(&rects, black_box(p)))
+        .collect();
+    let slow = start.elapsed();
+    println!(
+        "naive:    {} point + region + nearest queries in {:.1?}, {} each",
+        NAIVE_QUERIES,
+        slow,
+        per_query(slow, NAIVE_QUERIES)
+    );
+
+    // the tree had better agree with the slow way
+    for ((p, got), want) in sample.iter().zip(&answers).zip(&expected) {
+        assert_eq!(got, want, "{:?}", p);
+    }
+}
@@ -0,0 +1,319 @@
+// passwords and logins for the Users in a UserStore. passwords are never kept
+// around, only a salted PBKDF2-SHA256 hash of them, and a good login hands
+// back a session token that expires
+
+// time comes from a Clock instead of SystemTime::now() directly, so tests
+// (and the demo in main.rs) can use a ManualClock and fast forward through
+// lockouts and expiry without actually waiting
+use crate::store::{StoreError, UserStore};
+use crate::user::{User, UserError};
+use rand::Rng;
+use sha2::Sha256;
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::rc::Rc;
+use std::time::{Duration, SystemTime};
+
+const SALT_LEN: usize = 16;
+const HASH_LEN: usize = 32;
+const TOKEN_LEN: usize = 32;
+
+pub trait Clock {
+    fn now(&self) -> SystemTime;
+}
+
+/// The real time.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime {
+        SystemTime::now()
+    }
+}
+
+/// A clock that only moves when told to. Clones share the same time, so keep
+/// one around to call `advance` on after handing another to an
+/// [`Authenticator`].
+#[derive(Debug, Clone)]
+pub struct ManualClock {
+    now: Rc<Cell<SystemTime>>,
+}
+
+impl ManualClock {
+    pub fn new(start: SystemTime) -> ManualClock {
+        ManualClock {
+            now: Rc::new(Cell::new(start)),
+        }
+    }
+
+    pub fn advance(&self, by: Duration) {
+        self.now.set(self.now.get() + by);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> SystemTime {
+        self.now.get()
+    }
+}
+
+/// A salted password hash. The iteration count is stored alongside so it can
+/// be raised later without breaking old hashes.
+#[derive(Clone, PartialEq, Eq)]
+pub struct PasswordHash {
+    salt: [u8; SALT_LEN],
+    iterations: u32,
+    hash: [u8; HASH_LEN],
+}
+
+impl PasswordHash {
+    pub fn new(password: &str, iterations: u32) -> PasswordHash {
+        let salt = rand::thread_rng().gen();
+        PasswordHash {
+            salt,
+            iterations,
+            hash: derive(password, &salt, iterations),
+        }
+    }
+
+    pub fn verify(&self, password: &str) -> bool {
+        let attempt = derive(password, &self.salt, self.iterations);
+        // look at every byte no matter what, so how long this takes doesn't
+        // leak how much of the hash matched
+        attempt
+            .iter()
+            .zip(self.hash.iter())
+            .fold(0, |diff, (a, b)| diff | (a ^ b))
+            == 0
+    }
+}
+
+// so a PasswordHash can't end up in a log by accident
+impl fmt::Debug for PasswordHash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "PasswordHash {{ iterations: {}, .. }}", self.iterations)
+    }
+}
+
+fn derive(password: &str, salt: &[u8], iterations: u32) -> [u8; HASH_LEN] {
+    let mut out = [0; HASH_LEN];
+    pbkdf2::pbkdf2_hmac::<Sha256>(password.as_bytes(), salt, iterations, &mut out);
+    out
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub token: String,
+    pub username: String,
+    pub expires: SystemTime,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AuthConfig {
+    /// Wrong passwords in a row before the account gets locked.
+    pub max_failures: u32,
+    pub lockout: Duration,
+    pub session_ttl: Duration,
+    pub iterations: u32,
+}
+
+impl Default for AuthConfig {
+    fn default() -> AuthConfig {
+        AuthConfig {
+            max_failures: 5,
+            lockout: Duration::from_secs(15 * 60),
+            session_ttl: Duration::from_secs(60 * 60),
+            iterations: 100_000,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthError {
+    // wrong password, unknown user, or a user without a password. all the
+    // same to the caller so they can't use it to find out who exists
+    InvalidCredentials,
+    Locked { until: SystemTime },
+    Inactive,
+    InvalidSession,
+    SessionExpired,
+    Store(StoreError),
+}
+
+// per user login state that doesn't belong on User itself
+#[derive(Debug)]
+struct Credential {
+    hash: PasswordHash,
+    failures: u32,
+    locked_until: Option<SystemTime>,
+}
+
+pub struct Authenticator<C: Clock> {
+    store: UserStore,
+    clock: C,
+    config: AuthConfig,
+    credentials: HashMap<String, Credential>,
+    sessions: HashMap<String, Session>,
+}
+
+impl<C: Clock> Authenticator<C> {
+    pub fn new(store: UserStore, clock: C) -> Authenticator<C> {
+        Authenticator::with_config(store, clock, AuthConfig::default())
+    }
+
+    pub fn with_config(store: UserStore, clock: C, config: AuthConfig) -> Authenticator<C> {
+        Authenticator {
+            store,
+            clock,
+            config,
+            credentials: HashMap::new(),
+            sessions: HashMap::new(),
+        }
+    }
+
+    pub fn store(&self) -> &UserStore {
+        &self.store
+    }
+
+    // there's no store_mut(): the password and sessions are keyed by
+    // username, so taking a user out behind our back and putting a new one
+    // in under the same name would hand them the old user's login. changes
+    // go through the methods below instead
+
+    pub fn add_user(&mut self, user: User) -> Result<(), AuthError> {
+        self.store.insert(user).map_err(AuthError::Store)
+    }
+
+    /// Runs `edit` on the user like [`UserStore::update`]. Deactivating
+    /// someone ends their sessions the next time they're validated.
+    pub fn update_user<F>(&mut self, username: &str, edit: F) -> Result<&User, AuthError>
+    where
+        F: FnOnce(&mut User) -> Result<(), UserError>,
+    {
+        self.store.update(username, edit).map_err(AuthError::Store)
+    }
+
+    /// Takes the user out of the store, along with their password and any
+    /// sessions, so someone added later under the same name starts fresh.
+    pub fn remove_user(&mut self, username: &str) -> Result<User, AuthError> {
+        let user = self.store.remove(username).map_err(AuthError::Store)?;
+        self.credentials.remove(username);
+        self.sessions
+            .retain(|_, session| session.username != username);
+        Ok(user)
+    }
+
+    /// Sets (or replaces) a user's password. Also clears any lockout.
+    pub fn set_password(&mut self, username: &str, password: &str) -> Result<(), AuthError> {
+        if self.store.get(username).is_none() {
+            return Err(AuthError::Store(StoreError::NotFound(username.to_string())));
+        }
+        let credential = Credential {
+            hash: PasswordHash::new(password, self.config.iterations),
+            failures: 0,
+            locked_until: None,
+        };
+        self.credentials.insert(username.to_string(), credential);
+        Ok(())
+    }
+
+    /// Checks the password, bumps the user's sign in count and starts a
+    /// session. Too many wrong passwords in a row locks the account for
+    /// `config.lockout`, even against the right password. A deactivated user
+    /// only finds out they are after getting the password right.
+    pub fn login(&mut self, username: &str, password: &str) -> Result<Session, AuthError> {
+        let now = self.clock.now();
+        let credential = match (self.store.get(username), self.credentials.get_mut(username)) {
+            (Some(_), Some(credential)) => credential,
+            _ => {
+                // hash anyway, so a name that doesn't exist takes as long to
+                // turn down as a wrong password
+                derive(password, &[0; SALT_LEN], self.config.iterations);
+                return Err(AuthError::InvalidCredentials);
+            }
+        };
+
+        match credential.locked_until {
+            Some(until) if now < until => return Err(AuthError::Locked { until }),
+            Some(_) => credential.locked_until = None, // lockout is over
+            None => {}
+        }
+
+        if !credential.hash.verify(password) {
+            credential.failures += 1;
+            if credential.failures >= self.config.max_failures {
+                let until = now + self.config.lockout;
+                credential.failures = 0;
+                credential.locked_until = Some(until);
+                return Err(AuthError::Locked { until });
+            }
+            return Err(AuthError::InvalidCredentials);
+        }
+        credential.failures = 0;
+
+        self.store
+            .update(username, |user| user.sign_in().map(|_| ()))
+            .map_err(|e| match e {
+                StoreError::User(UserError::Inactive) => AuthError::Inactive,
+                e => AuthError::Store(e),
+            })?;
+
+        let session = Session {
+            token: new_token(),
+            username: username.to_string(),
+            expires: now + self.config.session_ttl,
+        };
+        self.sessions.insert(session.token.clone(), session.clone());
+        Ok(session)
+    }
+
+    /// Who a session token belongs to, if it's still good. Sessions end early
+    /// if their user is deactivated or taken out of the store.
+    pub fn validate(&mut self, token: &str) -> Result<&str, AuthError> {
+        let now = self.clock.now();
+        let session = self.sessions.get(token).ok_or(AuthError::InvalidSession)?;
+        let ended = if now >= session.expires {
+            Some(AuthError::SessionExpired)
+        } else {
+            match self.store.get(&session.username) {
+                Some(user) if user.is_active() => None,
+                Some(_) => Some(AuthError::Inactive),
+                None => Some(AuthError::InvalidSession),
+            }
+        };
+        if let Some(e) = ended {
+            self.sessions.remove(token);
+            return Err(e);
+        }
+        Ok(&self.sessions[token].username)
+    }
+
+    /// Ends a session. Returns `false` if there wasn't one.
+    pub fn logout(&mut self, token: &str) -> bool {
+        self.sessions.remove(token).is_some()
+    }
+}
+
+// 32 random bytes, hex encoded
+fn new_token() -> String {
+    let bytes: [u8; TOKEN_LEN] = rand::thread_rng().gen();
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+impl fmt::Display for AuthError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AuthError::InvalidCredentials => write!(f, "wrong username or password"),
+            AuthError::Locked { .. } => write!(f, "too many failed logins, account is locked"),
+            AuthError::Inactive => UserError::Inactive.fmt(f),
+            AuthError::InvalidSession => write!(f, "no such session"),
+            AuthError::SessionExpired => write!(f, "session has expired"),
+            AuthError::Store(e) => e.fmt(f),
+        }
+    }
+}
+
+impl Error for AuthError {}
@@ -0,0 +1,478 @@
+// the Color(i32, i32, i32) tuple struct from tuple_structs() in main.rs, made
+// into something you could actually use. channels are u8, so a colour with a
+// negative or 300 red can't exist at all. plain ints from somewhere else go
+// through TryFrom, which says which channel was wrong
+
+// HSL and HSV are other ways of writing the same colours that are easier to
+// reason about (same hue, lighter...). they're validated too, and converting
+// to them and back gives the colour you started with
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+mod names;
+
+/// An sRGB colour with straight (not premultiplied) alpha. Alpha 255 is
+/// opaque, 0 is fully transparent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Color {
+    pub r: u8,
+    pub g: u8,
+    pub b: u8,
+    pub a: u8,
+}
+
+/// Hue in degrees `0.0..360.0`, saturation and lightness in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsl {
+    hue: f64,
+    saturation: f64,
+    lightness: f64,
+}
+
+/// Hue in degrees `0.0..360.0`, saturation and value in `0.0..=1.0`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hsv {
+    hue: f64,
+    saturation: f64,
+    value: f64,
+}
+
+/// How readable text in one colour is on another, by the WCAG 2 contrast
+/// thresholds. Large text is 18pt, or 14pt bold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Contrast {
+    /// Below 3:1, not enough for anything.
+    Fail,
+    /// At least 3:1, AA for large text only.
+    AaLarge,
+    /// At least 4.5:1, AA for all text and AAA for large text.
+    Aa,
+    /// At least 7:1, AAA for all text.
+    Aaa,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColorError {
+    /// An RGB(A) channel outside `0..=255`.
+    Channel { channel: char, value: i32 },
+    /// Saturation, lightness or value outside `0.0..=1.0`, or anything NaN
+    /// or infinite.
+    Component(&'static str),
+    /// Not `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`.
+    Hex(String),
+    /// Neither a hex colour nor a CSS colour name.
+    Unknown(String),
+}
+
+impl Color {
+    pub const BLACK: Color = Color::rgb(0, 0, 0);
+    pub const WHITE: Color = Color::rgb(255, 255, 255);
+    pub const TRANSPARENT: Color = Color::rgba(0, 0, 0, 0);
+
+    /// An opaque colour.
+    pub const fn rgb(r: u8, g: u8, b: u8) -> Color {
+        Color::rgba(r, g, b, 255)
+    }
+
+    pub const fn rgba(r: u8, g: u8, b: u8, a: u8) -> Color {
+        Color { r, g, b, a }
+    }
+
+    pub fn with_alpha(&self, a: u8) -> Color {
+        Color { a, ..*self }
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.a == 255
+    }
+
+    /// Parses `#rgb`, `#rgba`, `#rrggbb` or `#rrggbbaa`, in either case. The
+    /// `#` is optional.
+    pub fn from_hex(hex: &str) -> Result<Color, ColorError> {
+        let err = || ColorError::Hex(hex.to_string());
+        let digits = hex.strip_prefix('#').unwrap_or(hex);
+        // from_str_radix would take a leading +, so check for digits first
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return Err(err());
+        }
+        let nibble = |i: usize| u8::from_str_radix(&digits[i..=i], 16).unwrap();
+        let byte = |i: usize| u8::from_str_radix(&digits[i..i + 2], 16).unwrap();
+
+        match digits.len() {
+            // #abc is short for #aabbcc, and 0xa * 17 == 0xaa
+            3 | 4 => {
+                let a = if digits.len() == 4 {
+                    nibble(3) * 17
+                } else {
+                    255
+                };
+                Ok(Color::rgba(
+                    nibble(0) * 17,
+                    nibble(1) * 17,
+                    nibble(2) * 17,
+                    a,
+                ))
+            }
+            6 | 8 => {
+                let a = if digits.len() == 8 { byte(6) } else { 255 };
+                Ok(Color::rgba(byte(0), byte(2), byte(4), a))
+            }
+            _ => Err(err()),
+        }
+    }
+
+    /// `#rrggbb` in lowercase, or `#rrggbbaa` if it isn't opaque.
+    pub fn to_hex(&self) -> String {
+        if self.is_opaque() {
+            format!("#{:02x}{:02x}{:02x}", self.r, self.g, self.b)
+        } else {
+            format!("#{:02x}{:02x}{:02x}{:02x}", self.r, self.g, self.b, self.a)
+        }
+    }
+
+    /// Looks up a CSS colour name like `"rebeccapurple"`, ignoring case.
+    pub fn named(name: &str) -> Option<Color> {
+        let name = name.to_ascii_lowercase();
+        let i = names::NAMES
+            .binary_search_by_key(&name.as_str(), |&(n, _)| n)
+            .ok()?;
+        let [r, g, b] = names::NAMES[i].1;
+        Some(Color::rgb(r, g, b))
+    }
+
+    /// The CSS name for this exact colour, if it has one. Only opaque colours
+    /// have names.
+    pub fn name(&self) -> Option<&'static str> {
+        if !self.is_opaque() {
+            return None;
+        }
+        names::NAMES
+            .iter()
+            .find(|(_, rgb)| *rgb == [self.r, self.g, self.b])
+            .map(|&(name, _)| name)
+    }
+
+    /// Every CSS colour name, alphabetically.
+    pub fn names() -> impl Iterator<Item = &'static str> {
+        names::NAMES.iter().map(|&(name, _)| name)
+    }
+
+    /// Ignores alpha.
+    pub fn to_hsl(&self) -> Hsl {
+        let (hue, max, min) = self.hue_max_min();
+        let lightness = (max + min) / 2.0;
+        let saturation = if max == min {
+            0.0
+        } else {
+            // can land a hair over 1 when one channel is 0 or 255, which
+            // Hsl::new would turn down
+            ((max - min) / (1.0 - (2.0 * lightness - 1.0).abs())).min(1.0)
+        };
+        Hsl {
+            hue,
+            saturation,
+            lightness,
+        }
+    }
+
+    /// An opaque colour. Channels get rounded to the nearest whole number.
+    pub fn from_hsl(hsl: Hsl) -> Color {
+        let chroma = (1.0 - (2.0 * hsl.lightness - 1.0).abs()) * hsl.saturation;
+        from_hue(hsl.hue, chroma, hsl.lightness - chroma / 2.0)
+    }
+
+    /// Ignores alpha.
+    pub fn to_hsv(&self) -> Hsv {
+        let (hue, max, min) = self.hue_max_min();
+        let saturation = if max == 0.0 { 0.0 } else { (max - min) / max };
+        Hsv {
+            hue,
+            saturation,
+            value: max,
+        }
+    }
+
+    /// An opaque colour. Channels get rounded to the nearest whole number.
+    pub fn from_hsv(hsv: Hsv) -> Color {
+        let chroma = hsv.value * hsv.saturation;
+        from_hue(hsv.hue, chroma, hsv.value - chroma)
+    }
+
+    // the part HSL and HSV share: the hue, and the biggest and smallest
+    // channel from 0 to 1
+    fn hue_max_min(&self) -> (f64, f64, f64) {
+        let (r, g, b) = (unit(self.r), unit(self.g), unit(self.b));
+        let max = r.max(g).max(b);
+        let min = r.min(g).min(b);
+        let d = max - min;
+
+        // which sixth of the colour wheel it's in depends on which channel
+        // is biggest. grey has no hue, call it 0
+        let sextant = if d == 0.0 {
+            0.0
+        } else if max == r {
+            ((g - b) / d).rem_euclid(6.0)
+        } else if max == g {
+            (b - r) / d + 2.0
+        } else {
+            (r - g) / d + 4.0
+        };
+        (sextant * 60.0, max, min)
+    }
+
+    /// Draws this colour on top of `background` (the usual "source over"
+    /// compositing). An opaque colour just covers it up.
+    pub fn over(&self, background: Color) -> Color {
+        // nothing to draw. this also means a is never 0 below
+        if self.a == 0 {
+            return background;
+        }
+        let (fa, ba) = (unit(self.a), unit(background.a));
+        let a = fa + ba * (1.0 - fa);
+        let channel = |f: u8, b: u8| {
+            let c = (unit(f) * fa + unit(b) * ba * (1.0 - fa)) / a;
+            to_byte(c)
+        };
+        Color::rgba(
+            channel(self.r, background.r),
+            channel(self.g, background.g),
+            channel(self.b, background.b),
+            to_byte(a),
+        )
+    }
+
+    /// Part way from this colour to `other`, alpha included. `t` of 0 is this
+    /// colour, 1 is `other`, and anything outside that gets clamped.
+    pub fn mix(&self, other: Color, t: f64) -> Color {
+        let t = if t.is_nan() { 0.0 } else { t.clamp(0.0, 1.0) };
+        let lerp = |a: u8, b: u8| (f64::from(a) + (f64::from(b) - f64::from(a)) * t).round() as u8;
+        Color::rgba(
+            lerp(self.r, other.r),
+            lerp(self.g, other.g),
+            lerp(self.b, other.b),
+            lerp(self.a, other.a),
+        )
+    }
+
+    /// How bright the colour looks, from 0 (black) to 1 (white), as WCAG
+    /// defines it. Ignores alpha.
+    pub fn relative_luminance(&self) -> f64 {
+        // undo the sRGB gamma curve first, the eye's sensitivity is in
+        // linear light
+        let linear = |c: u8| {
+            let c = unit(c);
+            if c <= 0.04045 {
+                c / 12.92
+            } else {
+                ((c + 0.055) / 1.055).powf(2.4)
+            }
+        };
+        0.2126 * linear(self.r) + 0.7152 * linear(self.g) + 0.0722 * linear(self.b)
+    }
+
+    /// From 1 (the same) to 21 (black on white). The order doesn't matter.
+    /// Ignores alpha, so put see-through colours [`Color::over`] whatever
+    /// they'll be shown on first.
+    pub fn contrast_ratio(&self, other: Color) -> f64 {
+        let (a, b) = (self.relative_luminance(), other.relative_luminance());
+        (a.max(b) + 0.05) / (a.min(b) + 0.05)
+    }
+
+    pub fn contrast(&self, other: Color) -> Contrast {
+        match self.contrast_ratio(other) {
+            r if r >= 7.0 => Contrast::Aaa,
+            r if r >= 4.5 => Contrast::Aa,
+            r if r >= 3.0 => Contrast::AaLarge,
+            _ => Contrast::Fail,
+        }
+    }
+}
+
+// 0..=255 to 0.0..=1.0 and back
+fn unit(c: u8) -> f64 {
+    f64::from(c) / 255.0
+}
+
+fn to_byte(c: f64) -> u8 {
+    (c * 255.0).round() as u8
+}
+
+// chroma is how far the biggest channel is from the smallest, m gets added to
+// every channel to bring the lightness (or value) up
+fn from_hue(hue: f64, chroma: f64, m: f64) -> Color {
+    let sextant = hue / 60.0;
+    let x = chroma * (1.0 - (sextant % 2.0 - 1.0).abs());
+    let (r, g, b) = match sextant as u32 {
+        0 => (chroma, x, 0.0),
+        1 => (x, chroma, 0.0),
+        2 => (0.0, chroma, x),
+        3 => (0.0, x, chroma),
+        4 => (x, 0.0, chroma),
+        _ => (chroma, 0.0, x),
+    };
+    Color::rgb(to_byte(r + m), to_byte(g + m), to_byte(b + m))
+}
+
+// any hue is fine, it just goes round the wheel. the others have to be in
+// range
+fn hue(h: f64) -> Result<f64, ColorError> {
+    if !h.is_finite() {
+        return Err(ColorError::Component("hue"));
+    }
+    // rem_euclid can round up to exactly 360 for tiny negative hues
+    let h = h.rem_euclid(360.0);
+    Ok(if h == 360.0 { 0.0 } else { h })
+}
+
+fn fraction(value: f64, name: &'static str) -> Result<f64, ColorError> {
+    if (0.0..=1.0).contains(&value) {
+        Ok(value)
+    } else {
+        Err(ColorError::Component(name))
+    }
+}
+
+impl Hsl {
+    /// Hue in degrees, wrapped around into `0.0..360.0`.
+    pub fn new(hue: f64, saturation: f64, lightness: f64) -> Result<Hsl, ColorError> {
+        Ok(Hsl {
+            hue: self::hue(hue)?,
+            saturation: fraction(saturation, "saturation")?,
+            lightness: fraction(lightness, "lightness")?,
+        })
+    }
+
+    pub fn hue(&self) -> f64 {
+        self.hue
+    }
+
+    pub fn saturation(&self) -> f64 {
+        self.saturation
+    }
+
+    pub fn lightness(&self) -> f64 {
+        self.lightness
+    }
+}
+
+impl Hsv {
+    /// Hue in degrees, wrapped around into `0.0..360.0`.
+    pub fn new(hue: f64, saturation: f64, value: f64) -> Result<Hsv, ColorError> {
+        Ok(Hsv {
+            hue: self::hue(hue)?,
+            saturation: fraction(saturation, "saturation")?,
+            value: fraction(value, "value")?,
+        })
+    }
+
+    pub fn hue(&self) -> f64 {
+        self.hue
+    }
+
+    pub fn saturation(&self) -> f64 {
+        self.saturation
+    }
+
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+}
+
+impl From<Hsl> for Color {
+    fn from(hsl: Hsl) -> Color {
+        Color::from_hsl(hsl)
+    }
+}
+
+impl From<Hsv> for Color {
+    fn from(hsv: Hsv) -> Color {
+        Color::from_hsv(hsv)
+    }
+}
+
+// for ints that might not fit, like the fields of the old tuple struct
+impl TryFrom<(i32, i32, i32)> for Color {
+    type Error = ColorError;
+
+    fn try_from((r, g, b): (i32, i32, i32)) -> Result<Color, ColorError> {
+        Color::try_from((r, g, b, 255))
+    }
+}
+
+impl TryFrom<(i32, i32, i32, i32)> for Color {
+    type Error = ColorError;
+
+    fn try_from((r, g, b, a): (i32, i32, i32, i32)) -> Result<Color, ColorError> {
+        let channel = |channel: char, value: i32| {
+            u8::try_from(value).map_err(|_| ColorError::Channel { channel, value })
+        };
+        Ok(Color::rgba(
+            channel('r', r)?,
+            channel('g', g)?,
+            channel('b', b)?,
+            channel('a', a)?,
+        ))
+    }
+}
+
+// a hex colour or a CSS name, like a stylesheet would take
+impl FromStr for Color {
+    type Err = ColorError;
+
+    fn from_str(s: &str) -> Result<Color, ColorError> {
+        let s = s.trim();
+        if s.starts_with('#') {
+            Color::from_hex(s)
+        } else {
+            Color::named(s).ok_or_else(|| ColorError::Unknown(s.to_string()))
+        }
+    }
+}
+
+impl fmt::Display for Color {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_hex())
+    }
+}
+
+// the way CSS writes them, rounded to whole degrees and percents
+impl fmt::Display for Hsl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hsl({:.0}, {:.0}%, {:.0}%)",
+            self.hue,
+            self.saturation * 100.0,
+            self.lightness * 100.0
+        )
+    }
+}
+
+impl fmt::Display for Hsv {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "hsv({:.0}, {:.0}%, {:.0}%)",
+            self.hue,
+            self.saturation * 100.0,
+            self.value * 100.0
+        )
+    }
+}
+
+impl fmt::Display for ColorError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColorError::Channel { channel, value } => {
+                write!(f, "{} channel {} is outside 0 to 255", channel, value)
+            }
+            ColorError::Component(name) => write!(f, "{} is out of range", name),
+            ColorError::Hex(hex) => write!(f, "{:?} isn't a hex colour", hex),
+            ColorError::Unknown(s) => write!(f, "{:?} isn't a colour", s),
+        }
+    }
+}
+
+impl Error for ColorError {}
@@ -0,0 +1,33 @@
+// 2d shapes, grown out of the Rectangle from rectangles() in main.rs
+
+// everything sits on an integer grid: x goes right, y goes down (like a
+// screen), and shapes are closed, so a point on the edge counts as inside.
+// areas of curvy or slanted things come out as f64 since they don't land on
+// whole numbers
+mod circle; // same trick as ch7: the contents live in geometry/circle.rs
+mod dimension;
+pub mod packing;
+mod point;
+mod polygon;
+pub mod quadtree;
+mod rectangle;
+pub mod render;
+mod scene;
+mod shape;
+
+pub use self::circle::Circle;
+pub use self::dimension::{ConvertError, Dimension, Number};
+pub use self::point::Point;
+pub use self::polygon::Polygon;
+pub use self::rectangle::Rectangle;
+pub use self::scene::Scene;
+pub use self::shape::Shape;
+
+// scaling happens in f64, then snaps back to the grid
+fn scale_coord(value: i32, factor: f64) -> i32 {
+    (f64::from(value) * factor).round() as i32
+}
+
+fn scale_len(value: u32, factor: f64) -> u32 {
+    (f64::from(value) * factor.abs()).round() as u32
+}
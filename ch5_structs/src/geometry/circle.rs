@@ -0,0 +1,95 @@
+use super::{scale_len, Point, Polygon, Rectangle};
+use std::convert::TryFrom;
+use std::f64::consts::PI;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Circle {
+    pub center: Point,
+    pub radius: u32,
+}
+
+impl Circle {
+    pub fn new(center: Point, radius: u32) -> Circle {
+        Circle { center, radius }
+    }
+
+    pub fn area(&self) -> f64 {
+        PI * f64::from(self.radius).powi(2)
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        2.0 * PI * f64::from(self.radius)
+    }
+
+    /// The square around the circle.
+    ///
+    /// # Panics
+    ///
+    /// If that doesn't fit in a [`Rectangle`], see
+    /// [`Circle::checked_bounding_box`].
+    pub fn bounding_box(&self) -> Rectangle {
+        self.checked_bounding_box()
+            .expect("circle's bounding box doesn't fit in a Rectangle")
+    }
+
+    /// The square around the circle, or `None` if it doesn't fit in a
+    /// [`Rectangle`]: when the diameter is more than `u32::MAX`, or the left
+    /// or top edge is before `i32::MIN`.
+    pub fn checked_bounding_box(&self) -> Option<Rectangle> {
+        let r = i64::from(self.radius);
+        let left = i32::try_from(i64::from(self.center.x) - r).ok()?;
+        let top = i32::try_from(i64::from(self.center.y) - r).ok()?;
+        let diameter = self.radius.checked_mul(2)?;
+        Some(Rectangle::at(Point::new(left, top), diameter, diameter))
+    }
+
+    // all integer math, so points exactly on the edge always count. i128,
+    // since two points can be 2^32 apart both ways and then the squares
+    // don't fit an i64
+    pub fn contains_point(&self, p: &Point) -> bool {
+        self.covers(i64::from(p.x), i64::from(p.y))
+    }
+
+    // the corners as i64, a rectangle's right and bottom edges can be past
+    // i32::MAX where a Point can't go
+    pub fn contains_rect(&self, rect: &Rectangle) -> bool {
+        let (left, top) = (i64::from(rect.x), i64::from(rect.y));
+        let (right, bottom) = (rect.right(), rect.bottom());
+        [(left, top), (right, top), (right, bottom), (left, bottom)]
+            .iter()
+            .all(|&(x, y)| self.covers(x, y))
+    }
+
+    fn covers(&self, x: i64, y: i64) -> bool {
+        self.distance_squared(x, y) <= i128::from(self.radius).pow(2)
+    }
+
+    fn distance_squared(&self, x: i64, y: i64) -> i128 {
+        let dx = i128::from(x - i64::from(self.center.x));
+        let dy = i128::from(y - i64::from(self.center.y));
+        dx * dx + dy * dy
+    }
+
+    pub fn contains_circle(&self, other: &Circle) -> bool {
+        // the other circle's far edge is its center distance plus its radius
+        // away, compare squares to stay out of floats:
+        // d + r2 <= r1  <=>  d^2 <= (r1 - r2)^2, when r1 >= r2
+        other.radius <= self.radius
+            && self.distance_squared(other.center.x.into(), other.center.y.into())
+                <= i128::from(self.radius - other.radius).pow(2)
+    }
+
+    // circles are convex, so this is just every vertex
+    pub fn contains_polygon(&self, polygon: &Polygon) -> bool {
+        polygon.vertices().iter().all(|p| self.contains_point(p))
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Circle {
+        Circle::new(self.center.translate(dx, dy), self.radius)
+    }
+
+    /// Scales about `(0, 0)`, so the center moves too.
+    pub fn scale(&self, factor: f64) -> Circle {
+        Circle::new(self.center.scale(factor), scale_len(self.radius, factor))
+    }
+}
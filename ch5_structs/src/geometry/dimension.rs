@@ -0,0 +1,135 @@
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::ops::{Add, Mul};
+
+// the number types a Rectangle's width and height can be. width * height in
+// the same type overflows easily (two u32s of 70_000 already do), so every
+// Dimension also names a Wide type that's big enough to hold any area or
+// perimeter, plus checked math for when you want to stay in the same type.
+// the one exception is f64, which has nothing wider to go to
+
+/// A number that can be a width or height.
+pub trait Dimension: Copy + PartialOrd + fmt::Debug {
+    /// Wide enough that area and perimeter can't overflow, except for `f64`:
+    /// that's its own `Wide`, so a big enough area or perimeter comes out
+    /// infinite.
+    type Wide: Copy + Add<Output = Self::Wide> + Mul<Output = Self::Wide> + From<Self>;
+
+    /// `None` on overflow (or an infinite result, for floats).
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+
+    fn to_number(self) -> Number;
+    fn from_number(number: Number) -> Result<Self, ConvertError>;
+}
+
+/// Any dimension, as something every other dimension can try to convert
+/// from. Every supported integer fits in an `i128` and every float in an
+/// `f64`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Number {
+    Int(i128),
+    Float(f64),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertError {
+    /// Too big or too small (or negative, for unsigned types).
+    OutOfRange,
+    /// A float with a fractional part going to an integer type. Round first
+    /// if that's what you want.
+    Fractional,
+    /// NaN or infinity.
+    NotFinite,
+    /// An integer too big for the float type to hold exactly.
+    Inexact,
+}
+
+macro_rules! int_dimension {
+    ($($t:ty => $wide:ty),*) => {$(
+        impl Dimension for $t {
+            type Wide = $wide;
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                <$t>::checked_mul(self, other)
+            }
+
+            fn to_number(self) -> Number {
+                Number::Int(i128::from(self))
+            }
+
+            fn from_number(number: Number) -> Result<$t, ConvertError> {
+                let int = match number {
+                    Number::Int(i) => i,
+                    Number::Float(f) if !f.is_finite() => return Err(ConvertError::NotFinite),
+                    Number::Float(f) if f.fract() != 0.0 => return Err(ConvertError::Fractional),
+                    // whole number now. `as` saturates anything past i128,
+                    // which try_from below then rejects
+                    Number::Float(f) => f as i128,
+                };
+                <$t>::try_from(int).map_err(|_| ConvertError::OutOfRange)
+            }
+        }
+    )*};
+}
+
+macro_rules! float_dimension {
+    ($($t:ty),*) => {$(
+        impl Dimension for $t {
+            type Wide = f64;
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                Some(self + other).filter(|sum| sum.is_finite())
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                Some(self * other).filter(|product| product.is_finite())
+            }
+
+            fn to_number(self) -> Number {
+                Number::Float(f64::from(self))
+            }
+
+            fn from_number(number: Number) -> Result<$t, ConvertError> {
+                let converted = match number {
+                    // straight to $t, and it has to come back the same: f32
+                    // holds every integer up to 2^24 exactly, f64 up to 2^53,
+                    // past that only some
+                    Number::Int(i) if (i as $t) as i128 != i => return Err(ConvertError::Inexact),
+                    Number::Int(i) => return Ok(i as $t),
+                    Number::Float(f) if !f.is_finite() => return Err(ConvertError::NotFinite),
+                    // f64 -> f32 rounds, which is what anyone asking for an
+                    // f32 expects. only running out of range is an error
+                    Number::Float(f) => f as $t,
+                };
+                if converted.is_finite() {
+                    Ok(converted)
+                } else {
+                    Err(ConvertError::OutOfRange)
+                }
+            }
+        }
+    )*};
+}
+
+int_dimension!(u8 => u16, u16 => u32, u32 => u64, u64 => u128, i16 => i32, i32 => i64, i64 => i128);
+float_dimension!(f32, f64);
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            ConvertError::OutOfRange => "value is out of range for the new type",
+            ConvertError::Fractional => "value has a fractional part",
+            ConvertError::NotFinite => "value is NaN or infinite",
+            ConvertError::Inexact => "value is too big to convert exactly",
+        };
+        write!(f, "{}", reason)
+    }
+}
+
+impl Error for ConvertError {}
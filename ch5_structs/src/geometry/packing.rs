@@ -0,0 +1,411 @@
+use super::{Point, Rectangle};
+use std::error::Error;
+use std::fmt;
+use std::fmt::Write;
+
+// can_hold says whether one rectangle fits in another. packing asks the
+// bigger question: given a pile of rectangles and a bin size, where does each
+// one go so we use as few bins as possible? think sprite sheets or sheets of
+// labels. finding the best answer is NP-hard, so these are the usual greedy
+// heuristics, biggest rectangles first
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Algorithm {
+    /// Keeps a list of free rectangles. Each placement cuts the free
+    /// rectangle it went into in two, like a guillotine.
+    Guillotine,
+    /// Tracks the top edge of everything placed so far (the skyline) and
+    /// drops each rectangle as low as it will go. Fast, but never fills the
+    /// gaps left under overhangs.
+    Skyline,
+}
+
+/// Packs rectangles into bins of one size. Only the sizes of the input
+/// rectangles matter, not where they are.
+#[derive(Debug, Clone, Copy)]
+pub struct Packer {
+    bin: Rectangle,
+    algorithm: Algorithm,
+    rotation: bool,
+}
+
+/// Where one input rectangle ended up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Placement {
+    /// Position in the list given to [`Packer::pack`].
+    pub index: usize,
+    pub bin: usize,
+    /// Position inside the bin, with width and height swapped if rotated.
+    pub rect: Rectangle,
+    pub rotated: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packing {
+    pub bin_size: Rectangle,
+    pub bins: usize,
+    /// In the order they were placed, biggest first.
+    pub placements: Vec<Placement>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PackError {
+    /// This rectangle is bigger than a bin, so it can't go anywhere.
+    TooBig { index: usize },
+    /// The bin is wider or taller than `i32::MAX`, so a placement could end
+    /// up further right or down than a [`Rectangle`] can sit.
+    BinTooBig,
+}
+
+impl Packer {
+    pub fn new(bin_width: u32, bin_height: u32, algorithm: Algorithm) -> Packer {
+        Packer {
+            bin: Rectangle::new(bin_width, bin_height),
+            algorithm,
+            rotation: false,
+        }
+    }
+
+    /// Allow turning rectangles 90 degrees when that fits better.
+    pub fn allow_rotation(mut self, rotation: bool) -> Packer {
+        self.rotation = rotation;
+        self
+    }
+
+    pub fn pack(&self, items: &[Rectangle]) -> Result<Packing, PackError> {
+        // every placement's top left is inside the bin, which starts at the
+        // origin. keeping the bin on the i32 grid keeps them on it too, and
+        // keeps the u32 sums below from overflowing
+        let max = i32::MAX as u32;
+        if self.bin.width > max || self.bin.height > max {
+            return Err(PackError::BinTooBig);
+        }
+
+        // check everything fits on its own before doing any real work
+        for (index, item) in items.iter().enumerate() {
+            let turned = Rectangle::new(item.height, item.width);
+            if !(self.bin.can_hold_inclusive(item)
+                || self.rotation && self.bin.can_hold_inclusive(&turned))
+            {
+                return Err(PackError::TooBig { index });
+            }
+        }
+
+        // biggest first: small things are easy to fit into leftover gaps,
+        // big things aren't. sort_by_key is stable, so ties stay in order
+        let mut order: Vec<usize> = (0..items.len()).collect();
+        order.sort_by_key(|&i| {
+            let item = &items[i];
+            std::cmp::Reverse((item.area(), item.width.max(item.height)))
+        });
+
+        let mut bins: Vec<Bin> = Vec::new();
+        let mut placements = Vec::with_capacity(items.len());
+
+        for index in order {
+            let (w, h) = (items[index].width, items[index].height);
+
+            // first bin with room wins, otherwise start a new one
+            let found = bins
+                .iter()
+                .enumerate()
+                .find_map(|(b, bin)| bin.find(w, h, self.rotation).map(|c| (b, c)));
+            let (bin, candidate) = match found {
+                Some(found) => found,
+                None => {
+                    bins.push(Bin::new(self.bin, self.algorithm));
+                    let candidate = bins[bins.len() - 1]
+                        .find(w, h, self.rotation)
+                        .expect("checked it fits an empty bin above");
+                    (bins.len() - 1, candidate)
+                }
+            };
+
+            bins[bin].commit(&candidate);
+            placements.push(Placement {
+                index,
+                bin,
+                rect: candidate.rect,
+                rotated: candidate.rotated,
+            });
+        }
+
+        Ok(Packing {
+            bin_size: self.bin,
+            bins: bins.len(),
+            placements,
+        })
+    }
+}
+
+impl Packing {
+    /// Area of all the bins used minus the area that got covered. A u128,
+    /// since a few of the biggest bins add up to more than a u64.
+    pub fn wasted_area(&self) -> u128 {
+        let used: u128 = self
+            .placements
+            .iter()
+            .map(|p| u128::from(p.rect.area()))
+            .sum();
+        u128::from(self.bin_size.area()) * self.bins as u128 - used
+    }
+
+    pub fn in_bin(&self, bin: usize) -> impl Iterator<Item = &Placement> {
+        self.placements.iter().filter(move |p| p.bin == bin)
+    }
+
+    /// Draws every bin side by side as an SVG image, each placement labelled
+    /// with its index. The same packing always gives the same text.
+    pub fn to_svg(&self) -> String {
+        // in i64, since a lot of wide bins side by side goes way past u32
+        const GAP: i64 = 10;
+        let (bw, bh) = (
+            i64::from(self.bin_size.width),
+            i64::from(self.bin_size.height),
+        );
+        let bins = self.bins as i64;
+        let width = bins * bw + (bins + 1) * GAP;
+        let height = bh + 2 * GAP;
+
+        // writing to a String can't fail, hence all the unwraps
+        let mut svg = String::new();
+        writeln!(
+            svg,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">"#,
+            w = width,
+            h = height
+        )
+        .unwrap();
+
+        for bin in 0..bins {
+            let left = GAP + bin * (bw + GAP);
+            writeln!(
+                svg,
+                r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="white" stroke="black"/>"#,
+                left, GAP, bw, bh
+            )
+            .unwrap();
+
+            for p in self.in_bin(bin as usize) {
+                let x = left + i64::from(p.rect.x);
+                let y = GAP + i64::from(p.rect.y);
+                // spread the colours around the wheel so neighbours differ
+                let hue = (p.index * 47) % 360;
+                writeln!(
+                    svg,
+                    r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="hsl({}, 60%, 75%)" stroke="black"/>"#,
+                    x, y, p.rect.width, p.rect.height, hue
+                )
+                .unwrap();
+                writeln!(
+                    svg,
+                    r#"  <text x="{}" y="{}" font-size="10" text-anchor="middle" dominant-baseline="middle">{}{}</text>"#,
+                    x + i64::from(p.rect.width) / 2,
+                    y + i64::from(p.rect.height) / 2,
+                    p.index,
+                    if p.rotated { "r" } else { "" }
+                )
+                .unwrap();
+            }
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+}
+
+// where a rectangle could go in one bin, and how good a spot it is (lower is
+// better)
+#[derive(Debug)]
+struct Candidate {
+    rect: Rectangle,
+    rotated: bool,
+    score: (u64, u64),
+    // Guillotine only: which free rectangle it goes into
+    free: usize,
+}
+
+// a piece of the skyline: from x to x + width, everything below y is taken
+#[derive(Debug, Clone, Copy)]
+struct Segment {
+    x: u32,
+    y: u32,
+    width: u32,
+}
+
+#[derive(Debug)]
+enum Bin {
+    Guillotine {
+        free: Vec<Rectangle>,
+    },
+    Skyline {
+        size: Rectangle,
+        skyline: Vec<Segment>,
+    },
+}
+
+impl Bin {
+    fn new(size: Rectangle, algorithm: Algorithm) -> Bin {
+        match algorithm {
+            Algorithm::Guillotine => Bin::Guillotine { free: vec![size] },
+            Algorithm::Skyline => Bin::Skyline {
+                size,
+                skyline: vec![Segment {
+                    x: 0,
+                    y: 0,
+                    width: size.width,
+                }],
+            },
+        }
+    }
+
+    fn find(&self, w: u32, h: u32, rotation: bool) -> Option<Candidate> {
+        // the sizes to try, turned sideways too if allowed (and it matters)
+        let mut sizes = vec![(w, h, false)];
+        if rotation && w != h {
+            sizes.push((h, w, true));
+        }
+
+        let candidates = sizes.into_iter().flat_map(|(w, h, rotated)| {
+            let item = Rectangle::new(w, h);
+            let found: Vec<Candidate> = match self {
+                // best area fit: the free rectangle with the least left over
+                Bin::Guillotine { free } => free
+                    .iter()
+                    .enumerate()
+                    .filter(|(_, f)| f.can_hold_inclusive(&item))
+                    .map(|(i, f)| Candidate {
+                        rect: Rectangle::at(f.top_left(), w, h),
+                        rotated,
+                        score: (f.area() - item.area(), 0),
+                        free: i,
+                    })
+                    .collect(),
+                // bottom left: lowest top edge, then furthest left
+                Bin::Skyline { size, skyline } => (0..skyline.len())
+                    .filter_map(|i| skyline_fit(size, skyline, i, w, h))
+                    .map(|(x, y)| Candidate {
+                        rect: Rectangle::at(Point::new(x as i32, y as i32), w, h),
+                        rotated,
+                        score: (u64::from(y + h), u64::from(x)),
+                        free: 0,
+                    })
+                    .collect(),
+            };
+            found
+        });
+
+        // min_by_key keeps the first of equal scores, so unrotated wins ties
+        candidates.min_by_key(|c| c.score)
+    }
+
+    fn commit(&mut self, candidate: &Candidate) {
+        let placed = candidate.rect;
+        match self {
+            // a rectangle with no area takes no room. splitting around it
+            // could throw away a free rectangle with no area of its own, and
+            // a zero width segment would be a post later rectangles land on
+            _ if placed.area() == 0 => {}
+            Bin::Guillotine { free } => {
+                let f = free.swap_remove(candidate.free);
+                let right_w = f.width - placed.width;
+                let below_h = f.height - placed.height;
+                // inside the bin, which pack() keeps on the i32 grid
+                let (right, bottom) = (placed.right() as i32, placed.bottom() as i32);
+
+                // split along the shorter leftover side, which tends to leave
+                // one big useful piece instead of two skinny ones
+                let (right, below) = if right_w < below_h {
+                    (
+                        Rectangle::at(Point::new(right, f.y), right_w, placed.height),
+                        Rectangle::at(Point::new(f.x, bottom), f.width, below_h),
+                    )
+                } else {
+                    (
+                        Rectangle::at(Point::new(right, f.y), right_w, f.height),
+                        Rectangle::at(Point::new(f.x, bottom), placed.width, below_h),
+                    )
+                };
+                free.extend([right, below].iter().filter(|r| r.area() > 0));
+            }
+            Bin::Skyline { skyline, .. } => {
+                let (x, w) = (placed.x as u32, placed.width);
+                let mut next = Vec::with_capacity(skyline.len() + 2);
+
+                // keep whatever sticks out either side of the new rectangle
+                for s in skyline.iter() {
+                    let end = s.x + s.width;
+                    if end <= x || s.x >= x + w {
+                        next.push(*s);
+                        continue;
+                    }
+                    if s.x < x {
+                        next.push(Segment {
+                            width: x - s.x,
+                            ..*s
+                        });
+                    }
+                    if end > x + w {
+                        next.push(Segment {
+                            x: x + w,
+                            width: end - (x + w),
+                            y: s.y,
+                        });
+                    }
+                }
+                next.push(Segment {
+                    x,
+                    y: placed.bottom() as u32,
+                    width: w,
+                });
+                next.sort_by_key(|s| s.x);
+
+                // neighbours at the same height are really one segment
+                skyline.clear();
+                for s in next {
+                    match skyline.last_mut() {
+                        Some(last) if last.y == s.y => last.width += s.width,
+                        _ => skyline.push(s),
+                    }
+                }
+            }
+        }
+    }
+}
+
+// where a w x h rectangle lands if its left edge lines up with segment i:
+// it rests on the highest segment underneath it
+fn skyline_fit(
+    size: &Rectangle,
+    skyline: &[Segment],
+    i: usize,
+    w: u32,
+    h: u32,
+) -> Option<(u32, u32)> {
+    let x = skyline[i].x;
+    if x + w > size.width {
+        return None;
+    }
+    // it rests on the tallest segment under it. one with no width still
+    // stands on segment i, rather than on nothing
+    let y = skyline[i..]
+        .iter()
+        .take_while(|s| s.x < x + w.max(1))
+        .map(|s| s.y)
+        .max()?;
+    if y + h > size.height {
+        return None;
+    }
+    Some((x, y))
+}
+
+impl fmt::Display for PackError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PackError::TooBig { index } => write!(f, "rectangle {} is bigger than a bin", index),
+            PackError::BinTooBig => write!(f, "bins can't be wider or taller than i32::MAX"),
+        }
+    }
+}
+
+impl Error for PackError {}
@@ -0,0 +1,38 @@
+use super::{scale_coord, Rectangle};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point {
+    pub x: i32,
+    pub y: i32,
+}
+
+impl Point {
+    pub fn new(x: i32, y: i32) -> Point {
+        Point { x, y }
+    }
+
+    /// Squared distance to `other`. Exact, unlike [`Point::distance`].
+    pub fn distance_squared(&self, other: &Point) -> i64 {
+        let dx = i64::from(self.x) - i64::from(other.x);
+        let dy = i64::from(self.y) - i64::from(other.y);
+        dx * dx + dy * dy
+    }
+
+    pub fn distance(&self, other: &Point) -> f64 {
+        (self.distance_squared(other) as f64).sqrt()
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Point {
+        Point::new(self.x + dx, self.y + dy)
+    }
+
+    /// Scales about `(0, 0)`.
+    pub fn scale(&self, factor: f64) -> Point {
+        Point::new(scale_coord(self.x, factor), scale_coord(self.y, factor))
+    }
+
+    // a point is as area-less as it gets
+    pub fn bounding_box(&self) -> Rectangle {
+        Rectangle::at(*self, 0, 0)
+    }
+}
@@ -0,0 +1,190 @@
+use super::{Circle, Point, Rectangle};
+
+/// A simple (not self intersecting) polygon. The last vertex connects back to
+/// the first.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Polygon {
+    vertices: Vec<Point>,
+}
+
+impl Polygon {
+    pub fn new(vertices: Vec<Point>) -> Polygon {
+        Polygon { vertices }
+    }
+
+    pub fn vertices(&self) -> &[Point] {
+        &self.vertices
+    }
+
+    // every edge as (start, end), including the one that closes the loop
+    fn edges(&self) -> impl Iterator<Item = (Point, Point)> + '_ {
+        let next = self.vertices.iter().cycle().skip(1);
+        self.vertices.iter().copied().zip(next.copied())
+    }
+
+    /// Shoelace formula. Twice the area is always a whole number, the area
+    /// itself can end in .5.
+    pub fn area(&self) -> f64 {
+        let twice: i64 = self
+            .edges()
+            .map(|(a, b)| i64::from(a.x) * i64::from(b.y) - i64::from(b.x) * i64::from(a.y))
+            .sum();
+        twice.abs() as f64 / 2.0
+    }
+
+    pub fn perimeter(&self) -> f64 {
+        self.edges().map(|(a, b)| a.distance(&b)).sum()
+    }
+
+    /// The box around every vertex. An empty polygon gets an empty box at
+    /// the origin.
+    pub fn bounding_box(&self) -> Rectangle {
+        Rectangle::bounding(&self.vertices).unwrap_or_default()
+    }
+
+    pub fn contains_point(&self, p: &Point) -> bool {
+        self.contains_doubled(2 * i128::from(p.x), 2 * i128::from(p.y))
+    }
+
+    // contains_point with everything at twice the size, so it can be asked
+    // about a point halfway between two grid points too
+    fn contains_doubled(&self, px: i128, py: i128) -> bool {
+        let doubled = |p: Point| (2 * i128::from(p.x), 2 * i128::from(p.y));
+        let on_edge = self.edges().any(|(a, b)| {
+            let ((ax, ay), (bx, by)) = (doubled(a), doubled(b));
+            (px - ax) * (by - ay) == (py - ay) * (bx - ax)
+                && ax.min(bx) <= px
+                && px <= ax.max(bx)
+                && ay.min(by) <= py
+                && py <= ay.max(by)
+        });
+        if on_edge {
+            return true;
+        }
+
+        // shoot a ray to the right and count how many edges it crosses, odd
+        // means we started inside. the half open a.y > p.y != b.y > p.y
+        // check makes sure a vertex right on the ray only counts once
+        let mut inside = false;
+        for (a, b) in self.edges() {
+            let ((ax, ay), (bx, by)) = (doubled(a), doubled(b));
+            if (ay > py) != (by > py) {
+                // x where the edge crosses y = py is ax + (py - ay)(bx - ax)/(by - ay).
+                // multiply through by (by - ay) instead of dividing, flipping
+                // the comparison if that's negative
+                let lhs = (px - ax) * (by - ay);
+                let rhs = (py - ay) * (bx - ax);
+                if (by > ay && lhs < rhs) || (by < ay && lhs > rhs) {
+                    inside = !inside;
+                }
+            }
+        }
+        inside
+    }
+
+    /// Every point of `other` is inside this one, edges included. Works for
+    /// polygons that aren't convex too.
+    pub fn contains_polygon(&self, other: &Polygon) -> bool {
+        // an edge that properly crosses one of ours goes from inside to
+        // outside right there
+        if self
+            .edges()
+            .any(|(a, b)| other.edges().any(|(c, d)| segments_cross(a, b, c, d)))
+        {
+            return false;
+        }
+
+        // the rest can still leave through a notch without crossing anything,
+        // by touching our vertices or running along our edges. so cut each of
+        // other's edges at every one of our vertices on it: no piece meets our
+        // boundary except at its ends or all the way along, so it's all in or
+        // all out, and its midpoint says which. with the whole outline of
+        // other inside, so is everything it surrounds, since ours has no holes
+        other.vertices.iter().all(|p| self.contains_point(p))
+            && other.edges().all(|(c, d)| {
+                let mut cuts: Vec<Point> = self
+                    .vertices
+                    .iter()
+                    .copied()
+                    .filter(|v| on_segment(v, c, d))
+                    .chain([c, d].iter().copied())
+                    .collect();
+                // they're all on one line, so this orders them along it
+                cuts.sort_by_key(|v| {
+                    (i64::from(v.x) - i64::from(c.x)).abs()
+                        + (i64::from(v.y) - i64::from(c.y)).abs()
+                });
+                cuts.windows(2).all(|w| {
+                    let x = i128::from(w[0].x) + i128::from(w[1].x);
+                    let y = i128::from(w[0].y) + i128::from(w[1].y);
+                    self.contains_doubled(x, y)
+                })
+            })
+    }
+
+    pub fn contains_rect(&self, rect: &Rectangle) -> bool {
+        self.contains_polygon(&rect.to_polygon())
+    }
+
+    /// The center is inside and no edge comes closer than the radius.
+    pub fn contains_circle(&self, circle: &Circle) -> bool {
+        let r = f64::from(circle.radius);
+        self.contains_point(&circle.center)
+            && self
+                .edges()
+                .all(|(a, b)| distance_to_segment(&circle.center, a, b) >= r)
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Polygon {
+        Polygon::new(self.vertices.iter().map(|p| p.translate(dx, dy)).collect())
+    }
+
+    /// Scales about `(0, 0)`.
+    pub fn scale(&self, factor: f64) -> Polygon {
+        Polygon::new(self.vertices.iter().map(|p| p.scale(factor)).collect())
+    }
+}
+
+// > 0 if a, b, c turn left (counter clockwise), < 0 for right, 0 if they're
+// in a line
+fn cross(a: Point, b: Point, c: Point) -> i64 {
+    let (ab, ac) = (offset(a, b), offset(a, c));
+    ab.0 * ac.1 - ab.1 * ac.0
+}
+
+// b - a, widened first so it can't overflow
+fn offset(a: Point, b: Point) -> (i64, i64) {
+    (
+        i64::from(b.x) - i64::from(a.x),
+        i64::from(b.y) - i64::from(a.y),
+    )
+}
+
+fn on_segment(p: &Point, a: Point, b: Point) -> bool {
+    cross(a, b, *p) == 0
+        && a.x.min(b.x) <= p.x
+        && p.x <= a.x.max(b.x)
+        && a.y.min(b.y) <= p.y
+        && p.y <= a.y.max(b.y)
+}
+
+// a proper crossing, where each segment has an end strictly on either side of
+// the other. touching or overlapping along an edge doesn't count
+fn segments_cross(a: Point, b: Point, c: Point, d: Point) -> bool {
+    let (d1, d2) = (cross(a, b, c), cross(a, b, d));
+    let (d3, d4) = (cross(c, d, a), cross(c, d, b));
+    d1.signum() * d2.signum() < 0 && d3.signum() * d4.signum() < 0
+}
+
+fn distance_to_segment(p: &Point, a: Point, b: Point) -> f64 {
+    let len_squared = a.distance_squared(&b);
+    if len_squared == 0 {
+        return p.distance(&a);
+    }
+    // how far along a -> b the closest point is, clamped to the segment
+    let (ab, ap) = (offset(a, b), offset(a, *p));
+    let t = ((ap.0 * ab.0 + ap.1 * ab.1) as f64 / len_squared as f64).clamp(0.0, 1.0);
+    let dx = ap.0 as f64 - t * ab.0 as f64;
+    let dy = ap.1 as f64 - t * ab.1 as f64;
+    dx.hypot(dy)
+}
@@ -0,0 +1,271 @@
+use super::{Point, Rectangle};
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+// asking every rectangle "do you contain this point?" is fine for a handful,
+// but with 100k of them and lots of questions it's O(n) per question. a
+// quadtree chops the area into quarters, then quarters of quarters, and so
+// on, so a question only has to look at the few boxes near where it's asking
+
+// each rectangle lives in the smallest box that holds all of it. ones that
+// straddle a dividing line stay up in the parent, and ones outside the tree's
+// bounds altogether stay at the root, which is always right, just slower
+
+const MAX_ITEMS: usize = 8; // split a box once it holds more than this
+const MAX_DEPTH: usize = 12; // unless it's already this deep
+
+/// Handed out by [`QuadTree::insert`], used to get or remove the item later.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ItemId(usize);
+
+#[derive(Debug)]
+pub struct QuadTree<T> {
+    root: Node,
+    // every item ever inserted, by id. removed ones become None, and ids are
+    // never reused so a stale id can't find someone else's item
+    items: Vec<Option<(Rectangle, T)>>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Node {
+    bounds: Rectangle,
+    depth: usize,
+    items: Vec<ItemId>,
+    children: Option<Box<[Node; 4]>>,
+}
+
+impl<T> QuadTree<T> {
+    /// A tree covering `bounds`. Items can go outside it, they're just slow.
+    pub fn new(bounds: Rectangle) -> QuadTree<T> {
+        QuadTree {
+            root: Node::new(bounds, 0),
+            items: Vec::new(),
+            len: 0,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert(&mut self, rect: Rectangle, value: T) -> ItemId {
+        let id = ItemId(self.items.len());
+        self.items.push(Some((rect, value)));
+        self.len += 1;
+        self.root.insert(id, &self.items);
+        id
+    }
+
+    pub fn get(&self, id: ItemId) -> Option<(&Rectangle, &T)> {
+        let (rect, value) = self.items.get(id.0)?.as_ref()?;
+        Some((rect, value))
+    }
+
+    pub fn remove(&mut self, id: ItemId) -> Option<(Rectangle, T)> {
+        let rect = self.items.get(id.0)?.as_ref()?.0;
+
+        // walk down the same way insert did to find the box it's in
+        let mut node = &mut self.root;
+        loop {
+            let child = node
+                .children
+                .as_mut()
+                .and_then(|children| children.iter_mut().find(|c| c.bounds.contains_rect(&rect)));
+            match child {
+                Some(child) => node = child,
+                None => break,
+            }
+        }
+        node.items.retain(|&item| item != id);
+
+        self.len -= 1;
+        self.items[id.0].take()
+    }
+
+    fn rect(&self, id: ItemId) -> &Rectangle {
+        &self.items[id.0]
+            .as_ref()
+            .expect("nodes only hold live ids")
+            .0
+    }
+
+    /// Every item containing `p`, sorted by id.
+    pub fn query_point(&self, p: &Point) -> Vec<ItemId> {
+        let mut found = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            found.extend(
+                node.items
+                    .iter()
+                    .filter(|&&id| self.rect(id).contains_point(p)),
+            );
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().filter(|c| c.bounds.contains_point(p)));
+            }
+        }
+        found.sort();
+        found
+    }
+
+    /// Every item overlapping `region`, sorted by id. Touching counts.
+    pub fn query_region(&self, region: &Rectangle) -> Vec<ItemId> {
+        self.search(region, |rect| touches(rect, region))
+    }
+
+    /// Every item entirely inside `region`, sorted by id.
+    pub fn query_contained(&self, region: &Rectangle) -> Vec<ItemId> {
+        self.search(region, |rect| region.contains_rect(rect))
+    }
+
+    // visit the boxes that touch region, keep the items that pass the check
+    fn search(&self, region: &Rectangle, keep: impl Fn(&Rectangle) -> bool) -> Vec<ItemId> {
+        let mut found = Vec::new();
+        let mut stack = vec![&self.root];
+        while let Some(node) = stack.pop() {
+            found.extend(node.items.iter().filter(|&&id| keep(self.rect(id))));
+            if let Some(children) = &node.children {
+                stack.extend(children.iter().filter(|c| touches(&c.bounds, region)));
+            }
+        }
+        found.sort();
+        found
+    }
+
+    /// The item closest to `p` (0 if `p` is inside it). Ties go to the
+    /// lowest id.
+    pub fn nearest(&self, p: &Point) -> Option<ItemId> {
+        // best first: always open the box that could hold the closest thing
+        // next. once the closest box left is further than the best item so
+        // far, nothing left can beat it
+        let mut best: Option<(i64, ItemId)> = None;
+        let mut queue = BinaryHeap::new();
+        // the root's distance is 0 no matter what, it might hold outsiders
+        queue.push(Reverse(ByDistance(0, &self.root)));
+
+        while let Some(Reverse(ByDistance(dist, node))) = queue.pop() {
+            if best.is_some_and(|(d, _)| dist > d) {
+                break;
+            }
+            for &id in &node.items {
+                let candidate = (distance_squared(self.rect(id), p), id);
+                if best.is_none_or(|b| candidate < b) {
+                    best = Some(candidate);
+                }
+            }
+            if let Some(children) = &node.children {
+                for child in children.iter() {
+                    queue.push(Reverse(ByDistance(
+                        distance_squared(&child.bounds, p),
+                        child,
+                    )));
+                }
+            }
+        }
+
+        best.map(|(_, id)| id)
+    }
+}
+
+impl Node {
+    fn new(bounds: Rectangle, depth: usize) -> Node {
+        Node {
+            bounds,
+            depth,
+            items: Vec::new(),
+            children: None,
+        }
+    }
+
+    fn insert<T>(&mut self, id: ItemId, items: &[Option<(Rectangle, T)>]) {
+        let rect = &items[id.0].as_ref().expect("just inserted").0;
+
+        if let Some(children) = &mut self.children {
+            if let Some(child) = children.iter_mut().find(|c| c.bounds.contains_rect(rect)) {
+                return child.insert(id, items);
+            }
+        }
+        self.items.push(id);
+
+        if self.children.is_none() && self.items.len() > MAX_ITEMS && self.depth < MAX_DEPTH {
+            self.split(items);
+        }
+    }
+
+    fn split<T>(&mut self, items: &[Option<(Rectangle, T)>]) {
+        let b = self.bounds;
+        // halfway across, in i64 since wide enough bounds put that past
+        // i32::MAX. nothing can have its top left out there, so the right
+        // and bottom children start at i32::MAX at the furthest
+        let half = |start: i32, len: u32| {
+            let mid = (i64::from(start) + i64::from(len / 2)).min(i64::from(i32::MAX));
+            (mid as i32, (mid - i64::from(start)) as u32)
+        };
+        let ((mid_x, left_w), (mid_y, top_h)) = (half(b.x, b.width), half(b.y, b.height));
+        let mid = Point::new(mid_x, mid_y);
+        let (right_w, bottom_h) = (b.width - left_w, b.height - top_h);
+        let depth = self.depth + 1;
+
+        self.children = Some(Box::new([
+            Node::new(Rectangle::at(b.top_left(), left_w, top_h), depth),
+            Node::new(Rectangle::at(Point::new(mid.x, b.y), right_w, top_h), depth),
+            Node::new(
+                Rectangle::at(Point::new(b.x, mid.y), left_w, bottom_h),
+                depth,
+            ),
+            Node::new(Rectangle::at(mid, right_w, bottom_h), depth),
+        ]));
+
+        // push everything down again, whatever doesn't fit a child comes
+        // straight back here
+        for id in std::mem::take(&mut self.items) {
+            self.insert(id, items);
+        }
+    }
+}
+
+// heap entry ordered only by distance, nodes themselves don't compare
+struct ByDistance<'a>(i64, &'a Node);
+
+impl PartialEq for ByDistance<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+impl Eq for ByDistance<'_> {}
+
+impl PartialOrd for ByDistance<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for ByDistance<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.cmp(&other.0)
+    }
+}
+
+// overlap with edges included, so zero sized rectangles still get found
+fn touches(a: &Rectangle, b: &Rectangle) -> bool {
+    i64::from(a.x) <= b.right()
+        && i64::from(b.x) <= a.right()
+        && i64::from(a.y) <= b.bottom()
+        && i64::from(b.y) <= a.bottom()
+}
+
+/// Squared distance from `p` to the nearest point of `rect`, 0 if inside.
+pub fn distance_squared(rect: &Rectangle, p: &Point) -> i64 {
+    let gap = |lo: i32, hi: i64, v: i32| {
+        let (lo, v) = (i64::from(lo), i64::from(v));
+        (lo - v).max(0).max(v - hi)
+    };
+    let dx = gap(rect.x, rect.right(), p.x);
+    let dy = gap(rect.y, rect.bottom(), p.y);
+    dx * dx + dy * dy
+}
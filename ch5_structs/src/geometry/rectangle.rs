@@ -0,0 +1,269 @@
+use super::{scale_coord, scale_len, Circle, ConvertError, Dimension, Point, Polygon};
+use std::convert::TryFrom;
+
+// the same Rectangle as the one in main.rs used to be, plus a position so it
+// can actually sit somewhere and overlap things. (x, y) is the top left
+
+// the width and height can be any Dimension: u32 like the book by default,
+// u64 for huge things, f64 for fractional sizes. the position stays on the
+// integer grid. sizes are expected to be >= 0
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)] // Debug so it prints
+pub struct Rectangle<T = u32> {
+    pub x: i32,
+    pub y: i32,
+    pub height: T, // named for clarity: otherwise which is height and width?
+    pub width: T,
+}
+
+// methods are defined within the context of a struct, enum or trait obj
+// first parameter is always &self (or self, &mut self)
+
+// this impl block works for every kind of Rectangle, the one further down is
+// only for Rectangle<u32>, which the rest of geometry is built on
+impl<T: Dimension> Rectangle<T> {
+    /// A rectangle with its top left corner at the origin.
+    pub fn new(width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            x: 0,
+            y: 0,
+            width,
+            height,
+        }
+    }
+
+    pub fn at(top_left: Point, width: T, height: T) -> Rectangle<T> {
+        Rectangle {
+            x: top_left.x,
+            y: top_left.y,
+            width,
+            height,
+        }
+    }
+
+    // associated functions! (not methods)
+    // functions that don't take self, not methods! Use ::, like
+    // String::from. unique from any instance
+    pub fn square(size: T) -> Rectangle<T> {
+        Rectangle::new(size, size)
+    }
+
+    /// Width times height, in a type wide enough that it can't overflow
+    /// (u64 for a `Rectangle<u32>`). An `f64` rectangle has nothing wider,
+    /// so a huge one's area is infinite.
+    pub fn area(&self) -> T::Wide {
+        // &self is shorthand for self : &Rectangle in impl block
+        // &mut self is fine is we want to edit
+        // self would also be fine, but would prevent the initial instance
+        // from being used again since we take ownership
+        T::Wide::from(self.width) * T::Wide::from(self.height)
+    }
+
+    /// The area in `T` itself, or `None` if it doesn't fit.
+    pub fn checked_area(&self) -> Option<T> {
+        self.width.checked_mul(self.height)
+    }
+
+    pub fn perimeter(&self) -> T::Wide {
+        let half = T::Wide::from(self.width) + T::Wide::from(self.height);
+        half + half
+    }
+
+    pub fn checked_perimeter(&self) -> Option<T> {
+        let half = self.width.checked_add(self.height)?;
+        half.checked_add(half)
+    }
+
+    pub fn top_left(&self) -> Point {
+        Point::new(self.x, self.y)
+    }
+
+    // other params: just list them after self
+
+    /// Whether `other` would fit strictly inside this rectangle, ignoring
+    /// where either one is. Same size doesn't count, see
+    /// [`Rectangle::can_hold_inclusive`].
+    pub fn can_hold(&self, other: &Rectangle<T>) -> bool {
+        self.width > other.width && self.height > other.height
+    }
+
+    /// Like [`Rectangle::can_hold`], but a rectangle the same size fits too.
+    pub fn can_hold_inclusive(&self, other: &Rectangle<T>) -> bool {
+        self.width >= other.width && self.height >= other.height
+    }
+
+    /// The same rectangle with its width and height run through `f`, like
+    /// `rect.map_dims(f64::from)` or `rect.map_dims(f64::round)`.
+    pub fn map_dims<U, F: Fn(T) -> U>(&self, f: F) -> Rectangle<U> {
+        Rectangle {
+            x: self.x,
+            y: self.y,
+            width: f(self.width),
+            height: f(self.height),
+        }
+    }
+
+    /// Converts the width and height to another number type, failing instead
+    /// of wrapping or truncating. An integer only goes to a float that holds
+    /// it exactly. The one conversion that rounds is `f64` to `f32`, to the
+    /// nearest `f32` like `as` does.
+    pub fn try_convert<U: Dimension>(&self) -> Result<Rectangle<U>, ConvertError> {
+        Ok(Rectangle {
+            x: self.x,
+            y: self.y,
+            width: U::from_number(self.width.to_number())?,
+            height: U::from_number(self.height.to_number())?,
+        })
+    }
+}
+
+// everything that places the rectangle on the grid: edges, corners, overlap.
+// these need whole number sizes, so they're only here for Rectangle<u32>
+impl Rectangle {
+    /// The smallest rectangle covering all of `points`, or `None` if there
+    /// aren't any.
+    pub fn bounding(points: &[Point]) -> Option<Rectangle> {
+        let first = points.first()?;
+        let (mut min, mut max) = (*first, *first);
+        for p in points {
+            min = Point::new(min.x.min(p.x), min.y.min(p.y));
+            max = Point::new(max.x.max(p.x), max.y.max(p.y));
+        }
+        // a u32 always holds the distance between two i32s
+        Some(Rectangle::from_corners(min, max.x.into(), max.y.into()))
+    }
+
+    // min is top left, (right, bottom) the bottom right. the edges are i64
+    // like right() and bottom() return, the size has to fit back in a u32
+    fn from_corners(min: Point, right: i64, bottom: i64) -> Rectangle {
+        let len = |from: i32, to: i64| {
+            u32::try_from(to - i64::from(from)).expect("rectangle is wider or taller than u32::MAX")
+        };
+        Rectangle::at(min, len(min.x, right), len(min.y, bottom))
+    }
+
+    /// The x of the right edge. An i64, since a big enough width takes it
+    /// past `i32::MAX`.
+    pub fn right(&self) -> i64 {
+        i64::from(self.x) + i64::from(self.width)
+    }
+
+    /// The y of the bottom edge, an i64 like [`right`](Rectangle::right).
+    pub fn bottom(&self) -> i64 {
+        i64::from(self.y) + i64::from(self.height)
+    }
+
+    /// Corners clockwise from the top left.
+    ///
+    /// # Panics
+    ///
+    /// If the right or bottom edge is past `i32::MAX`, where no `Point` can
+    /// go.
+    pub fn corners(&self) -> [Point; 4] {
+        let edge = |e: i64| i32::try_from(e).expect("rectangle edge is past i32::MAX");
+        let (right, bottom) = (edge(self.right()), edge(self.bottom()));
+        [
+            Point::new(self.x, self.y),
+            Point::new(right, self.y),
+            Point::new(right, bottom),
+            Point::new(self.x, bottom),
+        ]
+    }
+
+    pub fn bounding_box(&self) -> Rectangle {
+        *self
+    }
+
+    pub fn contains_point(&self, p: &Point) -> bool {
+        self.x <= p.x
+            && i64::from(p.x) <= self.right()
+            && self.y <= p.y
+            && i64::from(p.y) <= self.bottom()
+    }
+
+    /// Whether `other` is inside this rectangle where they actually are.
+    /// Sharing an edge is fine.
+    pub fn contains_rect(&self, other: &Rectangle) -> bool {
+        self.contains_point(&other.top_left())
+            && other.right() <= self.right()
+            && other.bottom() <= self.bottom()
+    }
+
+    pub fn contains_circle(&self, circle: &Circle) -> bool {
+        // a circle too big for a Rectangle can't be inside one either
+        circle
+            .checked_bounding_box()
+            .is_some_and(|b| self.contains_rect(&b))
+    }
+
+    // a rectangle is convex, so every corner being inside means the whole
+    // polygon is
+    pub fn contains_polygon(&self, polygon: &Polygon) -> bool {
+        polygon.vertices().iter().all(|p| self.contains_point(p))
+    }
+
+    /// The overlap of the two rectangles, or `None` if they don't overlap.
+    /// Only touching along an edge or corner doesn't count.
+    pub fn intersection(&self, other: &Rectangle) -> Option<Rectangle> {
+        let min = Point::new(self.x.max(other.x), self.y.max(other.y));
+        let (right, bottom) = (
+            self.right().min(other.right()),
+            self.bottom().min(other.bottom()),
+        );
+        if i64::from(min.x) < right && i64::from(min.y) < bottom {
+            // no bigger than either of them, so the size always fits
+            Some(Rectangle::from_corners(min, right, bottom))
+        } else {
+            None
+        }
+    }
+
+    pub fn intersects(&self, other: &Rectangle) -> bool {
+        self.intersection(other).is_some()
+    }
+
+    /// The smallest rectangle covering both.
+    ///
+    /// # Panics
+    ///
+    /// If that's wider or taller than `u32::MAX`, which two rectangles far
+    /// enough apart can be.
+    pub fn union(&self, other: &Rectangle) -> Rectangle {
+        Rectangle::from_corners(
+            Point::new(self.x.min(other.x), self.y.min(other.y)),
+            self.right().max(other.right()),
+            self.bottom().max(other.bottom()),
+        )
+    }
+
+    pub fn translate(&self, dx: i32, dy: i32) -> Rectangle {
+        Rectangle {
+            x: self.x + dx,
+            y: self.y + dy,
+            ..*self
+        }
+    }
+
+    /// Scales position and size about `(0, 0)`. A negative factor mirrors
+    /// the rectangle, which keeps its size positive.
+    pub fn scale(&self, factor: f64) -> Rectangle {
+        let corner = Point::new(scale_coord(self.x, factor), scale_coord(self.y, factor));
+        let (width, height) = (
+            scale_len(self.width, factor),
+            scale_len(self.height, factor),
+        );
+        if factor < 0.0 {
+            // the old top left is now the bottom right
+            Rectangle::at(
+                corner.translate(-(width as i32), -(height as i32)),
+                width,
+                height,
+            )
+        } else {
+            Rectangle::at(corner, width, height)
+        }
+    }
+
+    pub fn to_polygon(&self) -> Polygon {
+        Polygon::new(self.corners().to_vec())
+    }
+}
@@ -0,0 +1,362 @@
+use super::{Point, Rectangle, Scene};
+use std::convert::TryFrom;
+use std::fmt::Write;
+
+// {:#?} tells you the numbers but not what they look like. these draw
+// rectangles (or a whole Scene) as text for the terminal or as an SVG image.
+// everything is labelled A, B, C... in the order given, with a legend
+// underneath (ASCII) or the area written inside (SVG)
+
+// nothing here depends on hash order or the clock, so the same input always
+// gives byte for byte the same output. tests/render.rs checks them against
+// files in tests/golden
+
+// a character is about twice as tall as it is wide, so one row covers two
+// units of height for every one unit of width a column covers
+const ROW_ASPECT: f64 = 2.0;
+const SVG_MARGIN: i64 = 10;
+
+/// Draws the outlines of `rects` as ASCII boxes, scaled to fit in `cols` by
+/// `rows` characters, followed by a legend. Later rectangles are drawn over
+/// earlier ones, and `+` marks where edges cross.
+pub fn ascii(rects: &[Rectangle], cols: usize, rows: usize) -> String {
+    let bounds = match rects.iter().copied().reduce(|all, r| all.union(&r)) {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+    let grid = Grid::fit(&bounds, cols, rows);
+    let mut cells = grid.blank();
+
+    let corners: Vec<_> = rects
+        .iter()
+        .map(|rect| {
+            let (c0, r0) = grid.cell(rect.x.into(), rect.y.into());
+            let (c1, r1) = grid.cell(rect.right(), rect.bottom());
+            (c0, r0, c1, r1)
+        })
+        .collect();
+
+    for &(c0, r0, c1, r1) in &corners {
+        for &r in &[r0, r1] {
+            for cell in &mut cells[r][c0..=c1] {
+                draw(cell, '-');
+            }
+        }
+        for row in &mut cells[r0..=r1] {
+            draw(&mut row[c0], '|');
+            draw(&mut row[c1], '|');
+        }
+    }
+
+    // labels go in once all the edges are down, in the empty spot nearest
+    // the bottom right corner. rectangles lined up at the same top left (like
+    // can_hold comparisons) still get a spot each that way. no spot at this
+    // scale means no label, the legend still has it
+    for (i, &(c0, r0, c1, r1)) in corners.iter().enumerate() {
+        let spot = (r0 + 1..r1)
+            .rev()
+            .flat_map(|r| (c0 + 1..c1).rev().map(move |c| (r, c)))
+            .find(|&(r, c)| cells[r][c] == ' ');
+        if let Some((r, c)) = spot {
+            cells[r][c] = label(i);
+        }
+    }
+
+    let mut out = join(&cells);
+    out.push('\n');
+    for (i, rect) in rects.iter().enumerate() {
+        writeln!(
+            out,
+            "{}  {}x{} at ({}, {}), area {}",
+            label(i),
+            rect.width,
+            rect.height,
+            rect.x,
+            rect.y,
+            rect.area()
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// Fills in every shape in `scene` with its label, scaled to fit in `cols` by
+/// `rows` characters, followed by a legend. Each character shows the topmost
+/// shape at that spot.
+pub fn scene_ascii(scene: &Scene, cols: usize, rows: usize) -> String {
+    let bounds = match scene.bounding_box() {
+        Some(bounds) => bounds,
+        None => return String::new(),
+    };
+    let grid = Grid::fit(&bounds, cols, rows);
+    let mut cells = grid.blank();
+
+    // ask each cell which shape it lands on, like shape_at does but keeping
+    // the index for the label. later shapes are on top, so the last hit wins
+    for (r, row) in cells.iter_mut().enumerate() {
+        for (c, cell) in row.iter_mut().enumerate() {
+            let p = match grid.point(c, r) {
+                Some(p) => p,
+                None => continue,
+            };
+            let hit = scene
+                .iter()
+                .enumerate()
+                .filter(|(_, s)| s.contains(&p))
+                .last();
+            if let Some((i, _)) = hit {
+                *cell = label(i);
+            }
+        }
+    }
+
+    let mut out = join(&cells);
+    out.push('\n');
+    for (i, shape) in scene.iter().enumerate() {
+        let b = shape.bounding_box();
+        writeln!(
+            out,
+            "{}  area {:.2}, bounds {}x{} at ({}, {})",
+            label(i),
+            shape.area(),
+            b.width,
+            b.height,
+            b.x,
+            b.y
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// An SVG image of `rects`, each one labelled with its letter and area.
+/// `scale` is pixels per unit.
+pub fn svg(rects: &[Rectangle], scale: u32) -> String {
+    let bounds = rects
+        .iter()
+        .copied()
+        .reduce(|all, r| all.union(&r))
+        .unwrap_or_default();
+    let frame = Frame::new(&bounds, scale);
+
+    // writing to a String can't fail, hence all the unwraps
+    let mut svg = frame.open();
+    let mut labels = String::new();
+    for (i, rect) in rects.iter().enumerate() {
+        let (x, y) = frame.pixel(&rect.top_left());
+        let (w, h) = (
+            i64::from(rect.width) * frame.scale,
+            i64::from(rect.height) * frame.scale,
+        );
+        writeln!(
+            svg,
+            r#"  <rect x="{}" y="{}" width="{}" height="{}" fill="{}" fill-opacity="0.6" stroke="black"/>"#,
+            x,
+            y,
+            w,
+            h,
+            fill(i)
+        )
+        .unwrap();
+        // bottom right again, for the same reason as in ascii
+        text(
+            &mut labels,
+            x + w - 4,
+            y + h - 10,
+            "end",
+            &format!("{} {}", label(i), rect.area()),
+        );
+    }
+    // labels last so no rectangle covers one up
+    svg.push_str(&labels);
+    svg.push_str("</svg>\n");
+    svg
+}
+
+/// An SVG image of every shape in `scene`, bottom to top, each one labelled
+/// with its letter and area. `scale` is pixels per unit.
+pub fn scene_svg(scene: &Scene, scale: u32) -> String {
+    let bounds = scene.bounding_box().unwrap_or_default();
+    let frame = Frame::new(&bounds, scale);
+
+    let mut svg = frame.open();
+    // the shapes draw themselves in their own units, so move and scale the
+    // whole group instead of every coordinate. non-scaling-stroke keeps the
+    // outlines one pixel wide however big the scale is
+    writeln!(
+        svg,
+        r#"  <g transform="translate({} {}) scale({})" stroke="black">"#,
+        SVG_MARGIN - i64::from(bounds.x) * frame.scale,
+        SVG_MARGIN - i64::from(bounds.y) * frame.scale,
+        scale
+    )
+    .unwrap();
+    for (i, shape) in scene.iter().enumerate() {
+        let element = shape.to_svg();
+        // every element is a single self closing tag, so the fill can go
+        // right before the "/>"
+        let styled = element.trim_end_matches("/>");
+        writeln!(
+            svg,
+            r#"    {} fill="{}" fill-opacity="0.6" vector-effect="non-scaling-stroke"/>"#,
+            styled,
+            fill(i)
+        )
+        .unwrap();
+    }
+    svg.push_str("  </g>\n");
+
+    // labels go on top of everything, at the middle of each bounding box
+    for (i, shape) in scene.iter().enumerate() {
+        let b = shape.bounding_box();
+        let (x, y) = frame.pixel(&b.top_left());
+        text(
+            &mut svg,
+            x + i64::from(b.width) * frame.scale / 2,
+            y + i64::from(b.height) * frame.scale / 2,
+            "middle",
+            &format!("{} {:.2}", label(i), shape.area()),
+        );
+    }
+    svg.push_str("</svg>\n");
+    svg
+}
+
+// A to Z, then a to z, then it gives up and uses #
+fn label(i: usize) -> char {
+    match i {
+        0..=25 => (b'A' + i as u8) as char,
+        26..=51 => (b'a' + (i - 26) as u8) as char,
+        _ => '#',
+    }
+}
+
+// spread the colours around the wheel so neighbours differ, like packing does
+fn fill(i: usize) -> String {
+    format!("hsl({}, 60%, 75%)", (i * 47) % 360)
+}
+
+// a line crossing one going the other way makes a +. that's how corners
+// happen too: the top and bottom edges go in first, then the sides cross them
+fn draw(cell: &mut char, line: char) {
+    *cell = match (*cell, line) {
+        ('-', '|') | ('|', '-') | ('+', _) => '+',
+        _ => line,
+    };
+}
+
+fn join(cells: &[Vec<char>]) -> String {
+    let mut out = String::new();
+    for row in cells {
+        let line: String = row.iter().collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+// anchor is where x is along the text: "start", "middle" or "end"
+fn text(svg: &mut String, x: i64, y: i64, anchor: &str, content: &str) {
+    writeln!(
+        svg,
+        r#"  <text x="{}" y="{}" font-size="12" text-anchor="{}" dominant-baseline="middle">{}</text>"#,
+        x, y, anchor, content
+    )
+    .unwrap();
+}
+
+// how world units map onto characters
+struct Grid {
+    origin: Point,
+    // columns per unit of width. rows per unit of height is this over
+    // ROW_ASPECT
+    scale: f64,
+    cols: usize,
+    rows: usize,
+}
+
+impl Grid {
+    // as big as fits in cols x rows without stretching either way
+    fn fit(bounds: &Rectangle, cols: usize, rows: usize) -> Grid {
+        let (cols, rows) = (cols.max(1), rows.max(1));
+        let across = (cols - 1) as f64 / f64::from(bounds.width);
+        let down = (rows - 1) as f64 * ROW_ASPECT / f64::from(bounds.height);
+        // a zero width or height divides to infinity, which min ignores
+        let scale = match across.min(down) {
+            s if s.is_finite() => s,
+            _ => 1.0,
+        };
+        let mut grid = Grid {
+            origin: bounds.top_left(),
+            scale,
+            cols,
+            rows,
+        };
+        let (c, r) = grid.cell(bounds.right(), bounds.bottom());
+        grid.cols = c + 1;
+        grid.rows = r + 1;
+        grid
+    }
+
+    fn blank(&self) -> Vec<Vec<char>> {
+        vec![vec![' '; self.cols]; self.rows]
+    }
+
+    // i64, so a rectangle's right and bottom edges can go straight in
+    fn cell(&self, x: i64, y: i64) -> (usize, usize) {
+        let dx = (x - i64::from(self.origin.x)) as f64;
+        let dy = (y - i64::from(self.origin.y)) as f64;
+        let c = (dx * self.scale).round() as usize;
+        let r = (dy * self.scale / ROW_ASPECT).round() as usize;
+        (c.min(self.cols - 1), r.min(self.rows - 1))
+    }
+
+    // the grid point nearest the middle of a cell, added up in i64. the last
+    // cells of a wide enough grid are past i32::MAX, where there are no
+    // points to ask a shape about
+    fn point(&self, c: usize, r: usize) -> Option<Point> {
+        let x = c as f64 / self.scale;
+        let y = r as f64 * ROW_ASPECT / self.scale;
+        let coord = |origin: i32, d: f64| i32::try_from(i64::from(origin) + d.round() as i64).ok();
+        Some(Point::new(
+            coord(self.origin.x, x)?,
+            coord(self.origin.y, y)?,
+        ))
+    }
+}
+
+// how world units map onto SVG pixels. i64 so big scenes times a big scale
+// don't overflow
+struct Frame {
+    origin: Point,
+    scale: i64,
+    width: i64,
+    height: i64,
+}
+
+impl Frame {
+    fn new(bounds: &Rectangle, scale: u32) -> Frame {
+        let scale = i64::from(scale.max(1));
+        Frame {
+            origin: bounds.top_left(),
+            scale,
+            width: i64::from(bounds.width) * scale + 2 * SVG_MARGIN,
+            height: i64::from(bounds.height) * scale + 2 * SVG_MARGIN,
+        }
+    }
+
+    fn open(&self) -> String {
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\">\n",
+            w = self.width,
+            h = self.height
+        )
+    }
+
+    fn pixel(&self, p: &Point) -> (i64, i64) {
+        (
+            SVG_MARGIN + (i64::from(p.x) - i64::from(self.origin.x)) * self.scale,
+            SVG_MARGIN + (i64::from(p.y) - i64::from(self.origin.y)) * self.scale,
+        )
+    }
+}
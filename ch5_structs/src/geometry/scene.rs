@@ -0,0 +1,56 @@
+use super::{Point, Rectangle, Shape};
+
+/// A pile of shapes of any kind, drawn in the order they were added, so later
+/// shapes sit on top of earlier ones.
+#[derive(Debug, Default)]
+pub struct Scene {
+    // trait objects: each Box can hold a different concrete shape, calls go
+    // through a vtable at runtime instead of being picked at compile time
+    shapes: Vec<Box<dyn Shape>>,
+}
+
+impl Scene {
+    pub fn new() -> Scene {
+        Scene::default()
+    }
+
+    pub fn add<S: Shape + 'static>(&mut self, shape: S) {
+        self.shapes.push(Box::new(shape));
+    }
+
+    pub fn len(&self) -> usize {
+        self.shapes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.shapes.is_empty()
+    }
+
+    /// Bottom to top.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = &dyn Shape> {
+        self.shapes.iter().map(|shape| shape.as_ref())
+    }
+
+    /// Sum of every shape's area. Overlaps get counted twice.
+    pub fn total_area(&self) -> f64 {
+        self.iter().map(|shape| shape.area()).sum()
+    }
+
+    /// The topmost shape under `p`, if any.
+    pub fn shape_at(&self, p: &Point) -> Option<&dyn Shape> {
+        self.iter().rev().find(|shape| shape.contains(p))
+    }
+
+    /// The box around everything, or `None` for an empty scene.
+    pub fn bounding_box(&self) -> Option<Rectangle> {
+        self.iter()
+            .map(|shape| shape.bounding_box())
+            .reduce(|all, b| all.union(&b))
+    }
+
+    /// Reorders the shapes smallest area first. Shapes with the same area
+    /// keep their order.
+    pub fn sort_by_area(&mut self) {
+        self.shapes.sort_by(|a, b| a.area().total_cmp(&b.area()));
+    }
+}
@@ -0,0 +1,95 @@
+use super::{Circle, Point, Polygon, Rectangle};
+use std::fmt::Debug;
+
+// what every shape can do, so different shapes can go in the same Vec as
+// Box<dyn Shape> and get treated the same (see Scene)
+
+// Rectangle already has its own area() returning u64. when both exist,
+// rect.area() picks the inherent one, Shape::area(&rect) picks this one
+pub trait Shape: Debug {
+    fn area(&self) -> f64;
+    fn perimeter(&self) -> f64;
+    fn bounding_box(&self) -> Rectangle;
+    /// Points on the edge count as inside.
+    fn contains(&self, p: &Point) -> bool;
+    /// A single self closing SVG element for the outline, in the shape's own
+    /// coordinates and with no styling, so the caller can add its own.
+    fn to_svg(&self) -> String;
+}
+
+impl Shape for Rectangle {
+    fn area(&self) -> f64 {
+        f64::from(self.width) * f64::from(self.height)
+    }
+
+    fn perimeter(&self) -> f64 {
+        2.0 * (f64::from(self.width) + f64::from(self.height))
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        *self
+    }
+
+    fn contains(&self, p: &Point) -> bool {
+        self.contains_point(p)
+    }
+
+    fn to_svg(&self) -> String {
+        format!(
+            r#"<rect x="{}" y="{}" width="{}" height="{}"/>"#,
+            self.x, self.y, self.width, self.height
+        )
+    }
+}
+
+impl Shape for Circle {
+    fn area(&self) -> f64 {
+        Circle::area(self)
+    }
+
+    fn perimeter(&self) -> f64 {
+        Circle::perimeter(self)
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        Circle::bounding_box(self)
+    }
+
+    fn contains(&self, p: &Point) -> bool {
+        self.contains_point(p)
+    }
+
+    fn to_svg(&self) -> String {
+        format!(
+            r#"<circle cx="{}" cy="{}" r="{}"/>"#,
+            self.center.x, self.center.y, self.radius
+        )
+    }
+}
+
+impl Shape for Polygon {
+    fn area(&self) -> f64 {
+        Polygon::area(self)
+    }
+
+    fn perimeter(&self) -> f64 {
+        Polygon::perimeter(self)
+    }
+
+    fn bounding_box(&self) -> Rectangle {
+        Polygon::bounding_box(self)
+    }
+
+    fn contains(&self, p: &Point) -> bool {
+        self.contains_point(p)
+    }
+
+    fn to_svg(&self) -> String {
+        let points: Vec<String> = self
+            .vertices()
+            .iter()
+            .map(|p| format!("{},{}", p.x, p.y))
+            .collect();
+        format!(r#"<polygon points="{}"/>"#, points.join(" "))
+    }
+}
@@ -0,0 +1,15 @@
+// the reusable bits of the structs chapter live here, main.rs is still the
+// walkthrough that shows them off
+
+// password hashes, logins and sessions, see src/auth.rs
+pub mod auth;
+// Color with RGBA, HSL, HSV, hex, CSS names and contrast, see src/color.rs
+pub mod color;
+// Rectangle, Point, Circle and Polygon, see src/geometry.rs
+pub mod geometry;
+// UserStore, users indexed by name and email, see src/store.rs
+pub mod store;
+// validated User with a builder, see src/user.rs
+pub mod user;
+// Vec3 and Point3, 3d maths over i32, f32 or f64, see src/vec3.rs
+pub mod vec3;
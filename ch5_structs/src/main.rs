@@ -1,7 +1,17 @@
 fn main() {
     declaring_structs();
+    user_store();
+    logins();
     tuple_structs();
+    colors();
+    vectors();
     rectangles();
+    generic_rectangles();
+    geometry();
+    scene();
+    rendering();
+    packing();
+    quadtree();
 }
 
 // structs are like tuples in the sense that they hold different data types
@@ -34,15 +44,9 @@ fn declaring_structs() {
     // let mut user2 = User {...}
 
     // if we have vars with the same names as a struct field, we can use field
-    // init shorthand
-    fn _build_user(email: String, username: String) -> User {
-        User {
-            email, // field init shorthand, fills with value for email
-            username,
-            _active: true,
-            _sign_in_count: 1,
-        }
-    }
+    // init shorthand, like User { email, username, ... } instead of
+    // User { email: email, username: username, ... }. UserBuilder::build in
+    // src/user.rs does this
 
     // we can also reuse fields of a struct, ex user2 has a different email than
     // user1 but the same everything else
@@ -58,6 +62,143 @@ fn declaring_structs() {
         "[declaring_structs] user2\tname:{}\temail:{}",
         user2.username, user2.email
     );
+
+    // the real User in src/user.rs keeps its fields private and validates
+    // them, and with_email does the ..user1 trick without moving out of user3
+    {
+        use ch5_structs::user::{EmailError, User, UserError};
+
+        let mut user3 = User::new("xXx_genericUsername_xXx", "generic@email.com")
+            .expect("valid username and email");
+        let user4 = user3.with_email("unique@email.com").expect("valid email");
+        println!(
+            "[declaring_structs] user3 {} <{}>, user4 {} <{}>",
+            user3.username(),
+            user3.email(),
+            user4.username(),
+            user4.email()
+        );
+
+        assert_eq!(user3.sign_in(), Ok(1));
+        assert_eq!(user3.sign_in(), Ok(2));
+        user3.deactivate();
+        assert_eq!(user3.sign_in(), Err(UserError::Inactive));
+
+        let bad = User::new("generic", "generic.email.com");
+        assert_eq!(bad, Err(UserError::Email(EmailError::MissingAt)));
+        println!("[declaring_structs] bad user: {}", bad.unwrap_err());
+    }
+}
+
+fn user_store() {
+    // a bunch of Users kept in src/store.rs, looked up by username or email
+    use ch5_structs::store::UserStore;
+    use ch5_structs::user::User;
+
+    let mut store = UserStore::new();
+    store
+        .insert(User::new("ferris", "ferris@rust-lang.org").unwrap())
+        .unwrap();
+    store
+        .insert(User::new("corro", "corro@unsafe.rs").unwrap())
+        .unwrap();
+
+    // emails have to be unique, and case doesn't make it a different inbox
+    let copycat = User::new("not_ferris", "Ferris@Rust-Lang.org").unwrap();
+    match store.insert(copycat) {
+        Err(e) => println!("[user_store] not_ferris wasn't let in: {}", e),
+        Ok(()) => println!("[user_store] not_ferris got in somehow"),
+    }
+
+    store
+        .update("ferris", |user| user.set_email("crab@rust-lang.org"))
+        .unwrap();
+    store
+        .update("corro", |user| user.sign_in().map(|_| ()))
+        .unwrap();
+    store.deactivate("corro").unwrap();
+    println!(
+        "[user_store] found by old email: {:?}, by new email: {:?}",
+        store
+            .get_by_email("ferris@rust-lang.org")
+            .map(User::username),
+        store.get_by_email("crab@rust-lang.org").map(User::username)
+    );
+
+    // save() and load() do this with a file, tests/store.rs has those
+    let mut saved = Vec::new();
+    store.write_to(&mut saved).unwrap();
+    let loaded = UserStore::read_from(&saved[..]).unwrap();
+    println!(
+        "[user_store] saved and loaded {} users:\n{}",
+        loaded.len(),
+        String::from_utf8_lossy(&saved).trim_end()
+    );
+
+    let removed = store.remove("corro").unwrap();
+    println!(
+        "[user_store] removed {}, {} left",
+        removed.username(),
+        store.len()
+    );
+}
+
+fn logins() {
+    // src/auth.rs keeps salted password hashes next to a UserStore and hands
+    // out sessions. a ManualClock stands in for the real time so we can skip
+    // ahead instead of waiting out the lockout
+    use ch5_structs::auth::{AuthConfig, AuthError, Authenticator, ManualClock};
+    use ch5_structs::store::UserStore;
+    use ch5_structs::user::User;
+    use std::time::{Duration, SystemTime};
+
+    let mut store = UserStore::new();
+    store
+        .insert(User::new("ferris", "ferris@rust-lang.org").unwrap())
+        .unwrap();
+
+    let clock = ManualClock::new(SystemTime::UNIX_EPOCH);
+    let config = AuthConfig {
+        max_failures: 3,
+        lockout: Duration::from_secs(60),
+        session_ttl: Duration::from_secs(600),
+        // hashing is meant to be slow, and a debug build makes it slower
+        // still. way fewer rounds than the default keeps the demo snappy
+        iterations: 1_000,
+    };
+    let mut auth = Authenticator::with_config(store, clock.clone(), config);
+    auth.set_password("ferris", "hunter2").unwrap();
+
+    let session = auth.login("ferris", "hunter2").unwrap();
+    assert_eq!(auth.validate(&session.token), Ok("ferris"));
+    assert_eq!(auth.store().get("ferris").unwrap().sign_in_count(), 1);
+
+    // three strikes and you're out, even with the right password
+    assert_eq!(
+        auth.login("ferris", "hunter3"),
+        Err(AuthError::InvalidCredentials)
+    );
+    auth.login("ferris", "hunter4").unwrap_err();
+    let locked = auth.login("ferris", "hunter5").unwrap_err();
+    println!("[logins] after 3 bad passwords: {}", locked);
+    assert!(auth.login("ferris", "hunter2").is_err());
+
+    // a minute later the lockout is over, but the first session has expired
+    // by the time ten minutes have gone by
+    clock.advance(Duration::from_secs(61));
+    let second = auth.login("ferris", "hunter2").unwrap();
+    clock.advance(Duration::from_secs(600 - 61));
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::SessionExpired)
+    );
+    assert_eq!(auth.validate(&second.token), Ok("ferris"));
+    assert!(auth.logout(&second.token));
+
+    println!(
+        "[logins] ferris has signed in {} times",
+        auth.store().get("ferris").unwrap().sign_in_count()
+    );
 }
 
 fn tuple_structs() {
@@ -76,23 +217,128 @@ fn tuple_structs() {
     // otherwise just like tuples, black.0 will access the first elem of black
 }
 
+fn colors() {
+    // the Color tuple struct above happily holds Color(-1, 300, 0). the one in
+    // src/color.rs can't, and does the things you'd want a colour to do
+    use ch5_structs::color::{Color, ColorError, Contrast, Hsl};
+    use std::convert::TryFrom;
+
+    assert_eq!(Color::try_from((0, 0, 0)), Ok(Color::BLACK));
+    assert_eq!(
+        Color::try_from((-1, 300, 0)),
+        Err(ColorError::Channel {
+            channel: 'r',
+            value: -1
+        })
+    );
+
+    // hex, names and HSL are all the same colour written differently
+    let purple: Color = "rebeccapurple".parse().unwrap();
+    assert_eq!(purple, Color::from_hex("#639").unwrap());
+    assert_eq!(purple.to_hex(), "#663399");
+    assert_eq!(purple.name(), Some("rebeccapurple"));
+    let hsl = purple.to_hsl();
+    assert_eq!(hsl.hue(), 270.0);
+    assert_eq!(Color::from(hsl), purple);
+    // lighter, same hue (give or take rounding to whole channels)
+    let lavender = Color::from(Hsl::new(hsl.hue(), hsl.saturation(), 0.8).unwrap());
+    assert!((lavender.to_hsl().hue() - 270.0).abs() < 1.0);
+
+    // half see-through red on white comes out pink
+    let glass = Color::rgb(255, 0, 0).with_alpha(128);
+    assert_eq!(glass.over(Color::WHITE), Color::rgb(255, 127, 127));
+    assert_eq!(
+        Color::BLACK.mix(Color::WHITE, 0.5),
+        Color::rgb(128, 128, 128)
+    );
+
+    // is text in this colour readable on that one?
+    assert_eq!(Color::BLACK.contrast_ratio(Color::WHITE), 21.0);
+    assert_eq!(purple.contrast(Color::WHITE), Contrast::Aaa);
+    assert_eq!(
+        Color::named("gray").unwrap().contrast(Color::WHITE),
+        Contrast::AaLarge
+    );
+    println!(
+        "[colors] {} is {}, {:.2}:1 against white ({:?}), lighter it's {}",
+        purple,
+        hsl,
+        purple.contrast_ratio(Color::WHITE),
+        purple.contrast(Color::WHITE),
+        lavender
+    );
+}
+
+fn vectors() {
+    // the Point tuple struct grown up: Point3 is a place, Vec3 is a step from
+    // one place to another, and they're different types for the same reason
+    // Color and Point are
+    use ch5_structs::color::Color;
+    use ch5_structs::vec3::{Point3, Vec3};
+
+    fn paint(color: Color) -> String {
+        color.to_hex()
+    }
+    let origin: Point3<i32> = Point3::origin();
+    // paint(origin); // mismatched types, a point isn't a colour
+    // origin + origin; // doesn't compile either, adding two places means nothing
+    assert_eq!(paint(Color::BLACK), "#000000");
+
+    // ints, with all the usual operators
+    let a = Point3::new(1, 2, 3);
+    let b = Point3::new(4, 6, 3);
+    let step: Vec3<i32> = b - a; // point - point is a vector
+    assert_eq!(step, Vec3::new(3, 4, 0));
+    assert_eq!(a + step, b); // point + vector is a point
+    assert_eq!(a.distance(b), 5.0);
+    assert_eq!(a.distance_squared(origin), 14);
+    assert_eq!(2 * step - step, step);
+    assert_eq!(-step + step, Vec3::zero());
+
+    let x = Vec3::new(1, 0, 0);
+    let y = Vec3::new(0, 1, 0);
+    assert_eq!(x.cross(y), Vec3::new(0, 0, 1)); // right handed
+    assert_eq!(x.dot(y), 0); // at right angles
+    let total: Vec3<i32> = vec![x, y, x].into_iter().sum();
+    assert_eq!(total, Vec3::new(2, 1, 0));
+
+    // floats can be normalized, f32 and f64 alike
+    let v = Vec3::new(3.0f32, 0.0, 4.0);
+    assert_eq!(v.normalize(), Some(Vec3::new(0.6, 0.0, 0.8)));
+    assert_eq!(Vec3::<f64>::zero().normalize(), None);
+    let mut p = Point3::new(0.5, 0.5, 0.5);
+    p += Vec3::new(1.0, 2.0, 3.0) * 0.5;
+    assert_eq!(p, Point3::new(1.0, 1.5, 2.0));
+
+    println!(
+        "[vectors] from {} to {} is {}, length {}, cross of x and y is {}",
+        a,
+        b,
+        step,
+        step.length(),
+        x.cross(y)
+    );
+}
+
 // unit-like struct, it do jus be empty tho, useful for putting traits on a type
 struct _NoThoughtsHeadEmpty {}
 
 fn rectangles() {
-    #[derive(Debug)] // automatically derive the Debug trait to be printed
-    struct Rectangle {
-        height: u32, // named for clarity: otherwise which is height and width?
-        width: u32,
-    };
+    // Rectangle used to be declared right here, it lives in
+    // src/geometry/rectangle.rs now along with its impl blocks, so other code
+    // can use it too. the fields are still pub, so the struct literal works
+    use ch5_structs::geometry::Rectangle;
 
     let rect1 = Rectangle {
         width: 30,
         height: 50,
+        ..Rectangle::default() // x and y are 0
     };
 
     // no method syntax approach
     {
+        // fine for these, but overflows past 65536 x 65536. the area method
+        // below returns a u64 so it can't
         fn area(rect: &Rectangle) -> u32 {
             rect.height * rect.width
         }
@@ -107,19 +353,7 @@ fn rectangles() {
 
     // method syntax approach
     {
-        // methods are defined within the context of a struct, enum or trait obj
-        // first paramter is always &self
-        impl Rectangle {
-            // implementation block, says what we can do with this struct
-            fn area(&self) -> u32 {
-                // &self is shorthand for self : &Rectangle in impl block
-                // &mut self is fine is we want to edit
-                // self would also be fine, but would prevent the initial instance
-                // from being used again since we take ownership
-                self.width * self.height
-            }
-        }
-
+        // area is a method in the impl block in src/geometry/rectangle.rs
         println!(
             // {:?} uses Debug, # new lines the value of the structs
             "[rectangles method] rect1 is {:#?} and its area is {}",
@@ -128,42 +362,301 @@ fn rectangles() {
                          // (&rect1).area()
         );
 
-        // realistically this shouldn't be in a separate impl block, it's
-        // allowed but bad form. This makes it chronological with the book
-        // and demonstrates that multiple impl blocks are allowed though, so
-        // we'll take it
-        impl Rectangle {
-            // other params: just list them after self
-            fn can_hold(&self, other: &Rectangle) -> bool {
-                self.width > other.width && self.height > other.height
-            }
-        }
-
-        let small_rect = Rectangle {
-            width: 1,
-            height: 1,
-        };
+        // the book adds can_hold in a second impl block to show that multiple
+        // impl blocks are allowed. it's all one block in the module, which is
+        // better form anyway
+        let small_rect = Rectangle::new(1, 1);
 
         println!(
             "[rectangles method] rect1 can hold rect2: {}",
             rect1.can_hold(&small_rect)
         );
 
-        // associated functions! (not methods)
-        // functions that don't take self, not methods! Use ::, like
-        // String::from. unique from any instance
-        impl Rectangle {
-            fn square(size: u32) -> Rectangle {
-                Rectangle {
-                    width: size,
-                    height: size,
-                }
-            }
-        }
+        // same size doesn't fit with can_hold, but does with the inclusive one
+        assert!(!rect1.can_hold(&rect1));
+        assert!(rect1.can_hold_inclusive(&rect1));
 
+        // square is an associated function, called with ::
         println!(
             "[rectangles method] made a square: {:#?}",
             Rectangle::square(4)
         );
     }
 }
+
+fn generic_rectangles() {
+    // width and height don't have to be u32. Rectangle<T> takes any
+    // Dimension, and plain Rectangle still means Rectangle<u32>
+    use ch5_structs::geometry::{ConvertError, Rectangle};
+
+    // u32 * u32 doesn't fit in a u32, so area() widens to u64. checked_area
+    // stays in u32 and says so when it can't
+    let big = Rectangle::new(100_000u32, 100_000);
+    assert_eq!(big.area(), 10_000_000_000u64);
+    assert_eq!(big.checked_area(), None);
+    assert_eq!(Rectangle::new(300u32, 200).checked_area(), Some(60_000));
+    assert_eq!(
+        Rectangle::square(u32::MAX).perimeter(),
+        4 * u64::from(u32::MAX)
+    );
+
+    // fractional sizes
+    let paper: Rectangle<f64> = Rectangle::new(21.0, 29.7);
+    assert!((paper.area() - 623.7).abs() < 1e-9);
+    assert!(paper.can_hold(&Rectangle::new(10.5, 14.85)));
+
+    // converting never quietly loses anything
+    assert_eq!(
+        paper.try_convert::<u32>(),
+        Err(ConvertError::Fractional) // 29.7 isn't a whole number
+    );
+    let rounded: Rectangle = paper.map_dims(f64::round).try_convert().unwrap();
+    assert_eq!(rounded, Rectangle::new(21, 30));
+    assert_eq!(
+        big.try_convert::<u16>(),
+        Err(ConvertError::OutOfRange) // 100_000 > 65_535
+    );
+    assert_eq!(
+        Rectangle::new(-1i32, 5).try_convert::<u32>(),
+        Err(ConvertError::OutOfRange)
+    );
+    assert_eq!(
+        Rectangle::new(f64::NAN, 1.0).try_convert::<u64>(),
+        Err(ConvertError::NotFinite)
+    );
+    assert_eq!(
+        Rectangle::new(u64::MAX, 1).try_convert::<f64>(),
+        Err(ConvertError::Inexact)
+    );
+    let wide: Rectangle<u64> = big.try_convert().unwrap();
+    assert_eq!(wide.checked_area(), Some(10_000_000_000));
+
+    println!(
+        "[generic rectangles] {:?} has area {} (checked in u32: {:?}), {:?} has area {:.2}",
+        big,
+        big.area(),
+        big.checked_area(),
+        paper,
+        paper.area()
+    );
+}
+
+fn geometry() {
+    // rectangles that know where they are, plus some friends
+    use ch5_structs::geometry::{Circle, Point, Polygon, Rectangle};
+
+    let a = Rectangle::at(Point::new(0, 0), 10, 10);
+    let b = a.translate(5, 5);
+    assert_eq!(
+        a.intersection(&b),
+        Some(Rectangle::at(Point::new(5, 5), 5, 5))
+    );
+    assert_eq!(a.union(&b), Rectangle::square(15));
+    // touching at a corner isn't overlapping
+    assert_eq!(a.intersection(&a.translate(10, 10)), None);
+    assert_eq!(a.scale(0.5), Rectangle::square(5));
+    println!(
+        "[geometry] {:?} and {:?} overlap in {:?}",
+        a,
+        b,
+        a.intersection(&b)
+    );
+
+    let circle = Circle::new(Point::new(0, 0), 5);
+    assert!(circle.contains_point(&Point::new(3, 4))); // right on the edge
+    assert!(!circle.contains_point(&Point::new(4, 4)));
+    assert!(circle.contains_rect(&Rectangle::at(Point::new(-3, -4), 6, 8)));
+    assert_eq!(
+        circle.bounding_box(),
+        Rectangle::at(Point::new(-5, -5), 10, 10)
+    );
+
+    // an L shape, which isn't convex. the 2x2 in the corner fits, the one
+    // that reaches into the notch doesn't
+    let l_shape = Polygon::new(vec![
+        Point::new(0, 0),
+        Point::new(4, 0),
+        Point::new(4, 2),
+        Point::new(2, 2),
+        Point::new(2, 4),
+        Point::new(0, 4),
+    ]);
+    assert_eq!(l_shape.area(), 12.0);
+    assert_eq!(l_shape.perimeter(), 16.0);
+    assert!(l_shape.contains_point(&Point::new(1, 3)));
+    assert!(!l_shape.contains_point(&Point::new(3, 3)));
+    assert!(l_shape.contains_rect(&Rectangle::new(2, 2)));
+    assert!(!l_shape.contains_rect(&Rectangle::at(Point::new(1, 1), 2, 2)));
+    println!(
+        "[geometry] L shape has area {} and bounding box {:?}",
+        l_shape.area(),
+        l_shape.bounding_box()
+    );
+}
+
+fn scene() {
+    // all the shapes implement the Shape trait, so a Scene can hold a mix of
+    // them as Box<dyn Shape> and ask each one for its area without caring
+    // which kind it is
+    use ch5_structs::geometry::{Circle, Point, Polygon, Rectangle, Scene, Shape};
+
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(0, 0), 20, 10));
+    scene.add(Circle::new(Point::new(5, 5), 2));
+    scene.add(Polygon::new(vec![
+        Point::new(10, 0),
+        Point::new(14, 0),
+        Point::new(12, 3),
+    ]));
+
+    // the circle was added after the rectangle, so it's on top
+    let hit = scene.shape_at(&Point::new(5, 6)).unwrap();
+    assert_eq!(hit.bounding_box(), Rectangle::at(Point::new(3, 3), 4, 4));
+    assert!(scene.shape_at(&Point::new(50, 50)).is_none());
+
+    scene.sort_by_area();
+    let areas: Vec<f64> = scene.iter().map(|shape| shape.area()).collect();
+    println!(
+        "[scene] {} shapes, total area {:.2}, sorted areas {:.2?}, bounds {:?}",
+        scene.len(),
+        scene.total_area(),
+        areas,
+        scene.bounding_box().unwrap()
+    );
+
+    // Rectangle has an area() of its own that returns u64, method call
+    // syntax picks that one. ask the trait for its version explicitly
+    let rect = Rectangle::new(3, 4);
+    assert_eq!(rect.area(), 12);
+    assert_eq!(Shape::area(&rect), 12.0);
+}
+
+fn rendering() {
+    // the book's can_hold example, drawn instead of printed with {:#?}. all
+    // three start at the origin, so whatever can_hold says fits is drawn
+    // inside rect1, and whatever doesn't sticks out of it
+    use ch5_structs::geometry::{render, Circle, Point, Rectangle, Scene};
+
+    let rect1 = Rectangle::new(30, 50);
+    let rect2 = Rectangle::new(10, 40);
+    let rect3 = Rectangle::new(60, 45);
+    assert!(rect1.can_hold(&rect2));
+    assert!(!rect1.can_hold(&rect3));
+
+    let rects = [rect1, rect2, rect3];
+    let text = render::ascii(&rects, 40, 20);
+    // same input, same output, every time
+    assert_eq!(text, render::ascii(&rects, 40, 20));
+    println!("[rendering] rect1 can hold rect2 but not rect3:\n{}", text);
+
+    let path = std::env::temp_dir().join("ch5_structs_rectangles.svg");
+    std::fs::write(&path, render::svg(&rects, 4)).unwrap();
+    println!("[rendering] wrote {}", path.display());
+
+    // a scene gets filled in rather than outlined, so circles look round(ish)
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(0, 0), 20, 10));
+    scene.add(Circle::new(Point::new(6, 5), 4));
+    scene.add(Rectangle::at(Point::new(12, 2), 6, 6));
+    println!(
+        "[rendering] scene:\n{}",
+        render::scene_ascii(&scene, 40, 12)
+    );
+
+    let path = std::env::temp_dir().join("ch5_structs_scene.svg");
+    std::fs::write(&path, render::scene_svg(&scene, 10)).unwrap();
+    println!("[rendering] wrote {}", path.display());
+}
+
+fn packing() {
+    // can_hold on a bigger scale: fit a pile of rectangles into as few bins
+    // as possible, see src/geometry/packing.rs
+    use ch5_structs::geometry::packing::{Algorithm, PackError, Packer};
+    use ch5_structs::geometry::Rectangle;
+
+    let labels = [
+        Rectangle::new(40, 20),
+        Rectangle::new(20, 40),
+        Rectangle::new(30, 30),
+        Rectangle::new(60, 10),
+        Rectangle::new(10, 50),
+        Rectangle::new(25, 25),
+        Rectangle::new(50, 30),
+    ];
+
+    for &algorithm in &[Algorithm::Guillotine, Algorithm::Skyline] {
+        for &rotation in &[false, true] {
+            let packing = Packer::new(64, 64, algorithm)
+                .allow_rotation(rotation)
+                .pack(&labels)
+                .unwrap();
+
+            // nothing overlaps and everything stays inside its bin
+            let bin = packing.bin_size;
+            for (i, a) in packing.placements.iter().enumerate() {
+                assert!(bin.contains_rect(&a.rect));
+                for b in &packing.placements[i + 1..] {
+                    assert!(a.bin != b.bin || !a.rect.intersects(&b.rect));
+                }
+            }
+
+            println!(
+                "[packing] {:?}, rotation {}: {} bins, {} wasted",
+                algorithm,
+                rotation,
+                packing.bins,
+                packing.wasted_area()
+            );
+        }
+    }
+
+    // the svg is handy for eyeballing the result in a browser
+    let packing = Packer::new(64, 64, Algorithm::Skyline)
+        .pack(&labels)
+        .unwrap();
+    let path = std::env::temp_dir().join("ch5_structs_packing.svg");
+    std::fs::write(&path, packing.to_svg()).unwrap();
+    println!("[packing] wrote {}", path.display());
+
+    let too_big = Packer::new(64, 64, Algorithm::Guillotine).pack(&[Rectangle::new(65, 1)]);
+    assert_eq!(too_big, Err(PackError::TooBig { index: 0 }));
+}
+
+fn quadtree() {
+    // checking can_hold/contains against every rectangle is O(n) per
+    // question. a quadtree (src/geometry/quadtree.rs) only looks near where
+    // you ask. `cargo bench --bench quadtree` times it against the naive
+    // scan with 100k rectangles
+    use ch5_structs::geometry::quadtree::QuadTree;
+    use ch5_structs::geometry::{Point, Rectangle};
+
+    // buildings on a map, tagged with their names
+    let mut map = QuadTree::new(Rectangle::square(100));
+    let library = map.insert(Rectangle::at(Point::new(10, 10), 20, 15), "library");
+    let cafe = map.insert(Rectangle::at(Point::new(25, 20), 10, 10), "cafe");
+    let station = map.insert(Rectangle::at(Point::new(70, 60), 25, 10), "station");
+    // off the edge of the map is allowed, it just doesn't get a box of its own
+    let farm = map.insert(Rectangle::at(Point::new(150, -20), 40, 40), "farm");
+
+    // the library and the cafe overlap, so a point can be in both
+    assert_eq!(map.query_point(&Point::new(27, 22)), [library, cafe]);
+    assert_eq!(map.query_region(&Rectangle::square(50)), [library, cafe]);
+    assert_eq!(
+        map.query_contained(&Rectangle::square(100)),
+        [library, cafe, station]
+    );
+    assert_eq!(map.nearest(&Point::new(90, 90)), Some(station));
+    assert_eq!(map.nearest(&Point::new(140, 0)), Some(farm));
+
+    let (_, name) = map.remove(cafe).unwrap();
+    assert!(map.get(cafe).is_none());
+    assert_eq!(map.query_point(&Point::new(27, 22)), [library]);
+    println!(
+        "[quadtree] {} buildings left after the {} closed, nearest to the middle is the {}",
+        map.len(),
+        name,
+        map.get(map.nearest(&Point::new(50, 50)).unwrap())
+            .unwrap()
+            .1
+    );
+}
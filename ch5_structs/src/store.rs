@@ -0,0 +1,250 @@
+// an in-memory pile of Users, indexed by username and by email so both lookups
+// are cheap and both stay unique. it can be saved to and loaded from a json
+// lines file (one user per line), which is handy for poking at by hand
+
+// usernames live in a BTreeMap so iterating (and saving) always goes in the
+// same order
+use crate::user::{User, UserError};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Write};
+use std::path::Path;
+
+#[derive(Debug, Default)]
+pub struct UserStore {
+    users: BTreeMap<String, User>,
+    // lowercased email -> username. "Bob@Mail.com" and "bob@mail.com" are
+    // the same inbox as far as we care
+    by_email: HashMap<String, String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StoreError {
+    DuplicateUsername(String),
+    DuplicateEmail(String),
+    NotFound(String),
+    User(UserError),
+}
+
+#[derive(Debug)]
+pub enum PersistError {
+    Io(io::Error),
+    // line numbers start at 1, like an editor would show
+    Json {
+        line: usize,
+        source: serde_json::Error,
+    },
+    Store {
+        line: usize,
+        source: StoreError,
+    },
+}
+
+// what actually goes in the file. it's separate from User so User doesn't
+// need to know about serde, and so loading has to go back through the
+// builder's validation
+#[derive(Serialize, Deserialize)]
+struct Record {
+    username: String,
+    email: String,
+    sign_in_count: u64,
+    active: bool,
+}
+
+fn email_key(email: &str) -> String {
+    email.to_lowercase()
+}
+
+impl UserStore {
+    pub fn new() -> UserStore {
+        UserStore::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.users.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.users.is_empty()
+    }
+
+    /// Adds a user, as long as nobody else has the same username or email.
+    pub fn insert(&mut self, user: User) -> Result<(), StoreError> {
+        if self.users.contains_key(user.username()) {
+            return Err(StoreError::DuplicateUsername(user.username().to_string()));
+        }
+        let key = email_key(user.email());
+        if self.by_email.contains_key(&key) {
+            return Err(StoreError::DuplicateEmail(user.email().to_string()));
+        }
+
+        self.by_email.insert(key, user.username().to_string());
+        self.users.insert(user.username().to_string(), user);
+        Ok(())
+    }
+
+    pub fn get(&self, username: &str) -> Option<&User> {
+        self.users.get(username)
+    }
+
+    pub fn get_by_email(&self, email: &str) -> Option<&User> {
+        let username = self.by_email.get(&email_key(email))?;
+        self.users.get(username)
+    }
+
+    /// Runs `edit` on the user, keeping the email index up to date. If `edit`
+    /// fails, or leaves the user with an email someone else already has,
+    /// nothing changes.
+    pub fn update<F>(&mut self, username: &str, edit: F) -> Result<&User, StoreError>
+    where
+        F: FnOnce(&mut User) -> Result<(), UserError>,
+    {
+        let old = self
+            .users
+            .get(username)
+            .ok_or_else(|| StoreError::NotFound(username.to_string()))?;
+
+        // work on a copy so a failed update can't leave things half done
+        let mut new = old.clone();
+        edit(&mut new).map_err(StoreError::User)?;
+
+        let old_key = email_key(old.email());
+        let new_key = email_key(new.email());
+        if old_key != new_key {
+            if self.by_email.contains_key(&new_key) {
+                return Err(StoreError::DuplicateEmail(new.email().to_string()));
+            }
+            self.by_email.remove(&old_key);
+            self.by_email.insert(new_key, username.to_string());
+        }
+
+        let slot = self.users.get_mut(username).expect("checked above");
+        *slot = new;
+        Ok(slot)
+    }
+
+    pub fn deactivate(&mut self, username: &str) -> Result<(), StoreError> {
+        self.update(username, |user| {
+            user.deactivate();
+            Ok(())
+        })
+        .map(|_| ())
+    }
+
+    /// Takes the user out of the store and hands it back.
+    pub fn remove(&mut self, username: &str) -> Result<User, StoreError> {
+        let user = self
+            .users
+            .remove(username)
+            .ok_or_else(|| StoreError::NotFound(username.to_string()))?;
+        self.by_email.remove(&email_key(user.email()));
+        Ok(user)
+    }
+
+    /// All the users, sorted by username.
+    pub fn iter(&self) -> impl Iterator<Item = &User> {
+        self.users.values()
+    }
+
+    /// Writes one json object per user, one per line.
+    pub fn write_to<W: Write>(&self, mut out: W) -> Result<(), PersistError> {
+        for user in self.iter() {
+            let record = Record {
+                username: user.username().to_string(),
+                email: user.email().to_string(),
+                sign_in_count: user.sign_in_count(),
+                active: user.is_active(),
+            };
+            // serde_json only fails writing here if the writer does
+            serde_json::to_writer(&mut out, &record).map_err(|e| PersistError::Io(e.into()))?;
+            out.write_all(b"\n")?;
+        }
+        out.flush()?;
+        Ok(())
+    }
+
+    /// Reads a store back from [`UserStore::write_to`]'s format. Blank lines
+    /// are skipped, anything else that isn't a valid, unique user is an error.
+    pub fn read_from<R: BufRead>(input: R) -> Result<UserStore, PersistError> {
+        let mut store = UserStore::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let number = i + 1;
+
+            let record: Record =
+                serde_json::from_str(&line).map_err(|source| PersistError::Json {
+                    line: number,
+                    source,
+                })?;
+            let store_err = |source| PersistError::Store {
+                line: number,
+                source,
+            };
+
+            let user = User::builder()
+                .username(&record.username)
+                .email(&record.email)
+                .sign_in_count(record.sign_in_count)
+                .active(record.active)
+                .build()
+                .map_err(|e| store_err(StoreError::User(e)))?;
+            store.insert(user).map_err(store_err)?;
+        }
+
+        Ok(store)
+    }
+
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<(), PersistError> {
+        self.write_to(BufWriter::new(File::create(path)?))
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<UserStore, PersistError> {
+        UserStore::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+impl fmt::Display for StoreError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StoreError::DuplicateUsername(name) => write!(f, "username {} is taken", name),
+            StoreError::DuplicateEmail(email) => write!(f, "email {} is taken", email),
+            StoreError::NotFound(name) => write!(f, "no user named {}", name),
+            StoreError::User(e) => e.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for PersistError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PersistError::Io(e) => e.fmt(f),
+            PersistError::Json { line, source } => write!(f, "line {}: {}", line, source),
+            PersistError::Store { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl Error for StoreError {}
+
+impl Error for PersistError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            PersistError::Io(e) => Some(e),
+            PersistError::Json { source, .. } => Some(source),
+            PersistError::Store { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for PersistError {
+    fn from(e: io::Error) -> PersistError {
+        PersistError::Io(e)
+    }
+}
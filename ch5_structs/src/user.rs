@@ -0,0 +1,266 @@
+// the User from declaring_structs, grown up: fields are private so nobody can
+// build one with a junk email, and the only way in is User::new or the builder,
+// which both validate
+
+// sign_in_count and active actually do something now too
+use std::error::Error;
+use std::fmt;
+
+pub const MAX_USERNAME_LEN: usize = 32;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct User {
+    username: String,
+    email: String,
+    sign_in_count: u64,
+    active: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UsernameError {
+    Empty,
+    TooLong(usize), // how many chars it actually was
+    InvalidChar(char),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmailError {
+    MissingAt,
+    MultipleAt,
+    EmptyLocalPart,
+    // no dot, or an empty label like "a@b..com"
+    InvalidDomain,
+    Whitespace,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UserError {
+    Username(UsernameError),
+    Email(EmailError),
+    // the builder was never given one of these
+    MissingUsername,
+    MissingEmail,
+    Inactive,
+}
+
+impl User {
+    /// A new active user who hasn't signed in yet.
+    pub fn new(username: &str, email: &str) -> Result<User, UserError> {
+        User::builder().username(username).email(email).build()
+    }
+
+    pub fn builder() -> UserBuilder {
+        UserBuilder::default()
+    }
+
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    pub fn email(&self) -> &str {
+        &self.email
+    }
+
+    pub fn sign_in_count(&self) -> u64 {
+        self.sign_in_count
+    }
+
+    pub fn is_active(&self) -> bool {
+        self.active
+    }
+
+    /// Records a sign in and returns the new count. Deactivated users can't
+    /// sign in.
+    pub fn sign_in(&mut self) -> Result<u64, UserError> {
+        if !self.active {
+            return Err(UserError::Inactive);
+        }
+        self.sign_in_count += 1;
+        Ok(self.sign_in_count)
+    }
+
+    pub fn activate(&mut self) {
+        self.active = true;
+    }
+
+    pub fn deactivate(&mut self) {
+        self.active = false;
+    }
+
+    /// Changes the email in place.
+    pub fn set_email(&mut self, email: &str) -> Result<(), UserError> {
+        validate_email(email)?;
+        self.email = email.to_string();
+        Ok(())
+    }
+
+    /// A copy of this user with a different email, like `User { email,
+    /// ..user1 }` in declaring_structs but without moving anything out of
+    /// `self`.
+    pub fn with_email(&self, email: &str) -> Result<User, UserError> {
+        validate_email(email)?;
+        // the struct update syntax works fine from a reference as long as
+        // the fields it fills in are Copy. username isn't, so it gets cloned
+        // by hand, sign_in_count and active get copied out of *self
+        Ok(User {
+            email: email.to_string(),
+            username: self.username.clone(),
+            ..*self
+        })
+    }
+}
+
+/// Builds a [`User`], checking the username and email in `build`.
+#[derive(Debug, Clone)]
+pub struct UserBuilder {
+    username: Option<String>,
+    email: Option<String>,
+    sign_in_count: u64,
+    active: bool,
+}
+
+impl Default for UserBuilder {
+    fn default() -> UserBuilder {
+        UserBuilder {
+            username: None,
+            email: None,
+            sign_in_count: 0,
+            active: true,
+        }
+    }
+}
+
+impl UserBuilder {
+    pub fn username(mut self, username: &str) -> UserBuilder {
+        self.username = Some(username.to_string());
+        self
+    }
+
+    pub fn email(mut self, email: &str) -> UserBuilder {
+        self.email = Some(email.to_string());
+        self
+    }
+
+    // mostly for bringing in users that already exist somewhere else
+    pub fn sign_in_count(mut self, count: u64) -> UserBuilder {
+        self.sign_in_count = count;
+        self
+    }
+
+    pub fn active(mut self, active: bool) -> UserBuilder {
+        self.active = active;
+        self
+    }
+
+    pub fn build(self) -> Result<User, UserError> {
+        let username = self.username.ok_or(UserError::MissingUsername)?;
+        let email = self.email.ok_or(UserError::MissingEmail)?;
+        validate_username(&username)?;
+        validate_email(&email)?;
+
+        Ok(User {
+            username, // field init shorthand, same as _build_user used to do
+            email,
+            sign_in_count: self.sign_in_count,
+            active: self.active,
+        })
+    }
+}
+
+/// Usernames are 1 to [`MAX_USERNAME_LEN`] ASCII letters, digits, `_`, `-` or
+/// `.`.
+pub fn validate_username(username: &str) -> Result<(), UsernameError> {
+    if username.is_empty() {
+        return Err(UsernameError::Empty);
+    }
+    if let Some(c) = username
+        .chars()
+        .find(|&c| !(c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.'))
+    {
+        return Err(UsernameError::InvalidChar(c));
+    }
+    // every char is ascii by now, so bytes == chars
+    if username.len() > MAX_USERNAME_LEN {
+        return Err(UsernameError::TooLong(username.len()));
+    }
+    Ok(())
+}
+
+/// A deliberately loose check: one `@`, something before it, and a dotted
+/// domain after it with no empty parts.
+pub fn validate_email(email: &str) -> Result<(), EmailError> {
+    if email.chars().any(char::is_whitespace) {
+        return Err(EmailError::Whitespace);
+    }
+
+    let mut parts = email.split('@');
+    let local = parts.next().unwrap_or("");
+    let domain = parts.next().ok_or(EmailError::MissingAt)?;
+    if parts.next().is_some() {
+        return Err(EmailError::MultipleAt);
+    }
+
+    if local.is_empty() {
+        return Err(EmailError::EmptyLocalPart);
+    }
+    if !domain.contains('.') || domain.split('.').any(str::is_empty) {
+        return Err(EmailError::InvalidDomain);
+    }
+    Ok(())
+}
+
+impl fmt::Display for UsernameError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UsernameError::Empty => write!(f, "username is empty"),
+            UsernameError::TooLong(len) => write!(
+                f,
+                "username is {} characters, the max is {}",
+                len, MAX_USERNAME_LEN
+            ),
+            UsernameError::InvalidChar(c) => write!(f, "username can't contain {:?}", c),
+        }
+    }
+}
+
+impl fmt::Display for EmailError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self {
+            EmailError::MissingAt => "is missing an @",
+            EmailError::MultipleAt => "has more than one @",
+            EmailError::EmptyLocalPart => "has nothing before the @",
+            EmailError::InvalidDomain => "has an invalid domain",
+            EmailError::Whitespace => "contains whitespace",
+        };
+        write!(f, "email {}", reason)
+    }
+}
+
+impl fmt::Display for UserError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UserError::Username(e) => e.fmt(f),
+            UserError::Email(e) => e.fmt(f),
+            UserError::MissingUsername => write!(f, "no username given"),
+            UserError::MissingEmail => write!(f, "no email given"),
+            UserError::Inactive => write!(f, "user is deactivated"),
+        }
+    }
+}
+
+impl Error for UsernameError {}
+impl Error for EmailError {}
+impl Error for UserError {}
+
+// so ? can turn the specific errors into a UserError
+impl From<UsernameError> for UserError {
+    fn from(e: UsernameError) -> UserError {
+        UserError::Username(e)
+    }
+}
+
+impl From<EmailError> for UserError {
+    fn from(e: EmailError) -> UserError {
+        UserError::Email(e)
+    }
+}
@@ -0,0 +1,303 @@
+// the Point(i32, i32, i32) tuple struct from tuple_structs() in main.rs, made
+// into something you can do maths with, plus Vec3 to go with it
+
+// the comment over there says a Color and a Point are different types even
+// though they hold the same thing, so one can't be passed as the other. the
+// same idea splits Point3 from Vec3 here: a point is a place, a vector is a
+// direction and a distance. point - point is the vector between them, point +
+// vector is another point, but point + point means nothing so it doesn't
+// compile (tests/compile_fail has the proof)
+
+// both work with i32, f32 or f64. integer maths overflows like it always
+// does (a panic in debug builds), so keep integer vectors small
+use std::fmt;
+use std::iter::Sum;
+use std::ops::{Add, AddAssign, Div, Mul, MulAssign, Neg, Sub, SubAssign};
+
+/// A number a [`Vec3`] or [`Point3`] can be made of.
+pub trait Scalar:
+    Copy
+    + PartialEq
+    + PartialOrd
+    + fmt::Debug
+    + fmt::Display
+    + Default
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Neg<Output = Self>
+{
+    fn to_f64(self) -> f64;
+}
+
+/// A [`Scalar`] that can be divided without truncating, so vectors of it can
+/// be normalized.
+pub trait Float: Scalar + Div<Output = Self> {
+    fn sqrt(self) -> Self;
+}
+
+impl Scalar for i32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Scalar for f32 {
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl Scalar for f64 {
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl Float for f32 {
+    fn sqrt(self) -> f32 {
+        f32::sqrt(self)
+    }
+}
+
+impl Float for f64 {
+    fn sqrt(self) -> f64 {
+        f64::sqrt(self)
+    }
+}
+
+/// A direction and length in 3d.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Vec3<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+/// A position in 3d.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Point3<T = f64> {
+    pub x: T,
+    pub y: T,
+    pub z: T,
+}
+
+impl<T: Scalar> Vec3<T> {
+    pub fn new(x: T, y: T, z: T) -> Vec3<T> {
+        Vec3 { x, y, z }
+    }
+
+    pub fn zero() -> Vec3<T> {
+        Vec3::default()
+    }
+
+    pub fn dot(&self, other: Vec3<T>) -> T {
+        self.x * other.x + self.y * other.y + self.z * other.z
+    }
+
+    /// At right angles to both, following the right hand rule. Its length is
+    /// the area of the parallelogram the two make.
+    pub fn cross(&self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(
+            self.y * other.z - self.z * other.y,
+            self.z * other.x - self.x * other.z,
+            self.x * other.y - self.y * other.x,
+        )
+    }
+
+    /// Cheaper than [`Vec3::length`] and exact for integers, good for
+    /// comparing lengths.
+    pub fn length_squared(&self) -> T {
+        self.dot(*self)
+    }
+
+    /// Always an f64, since the length of an integer vector usually isn't
+    /// an integer.
+    pub fn length(&self) -> f64 {
+        self.to_f64().length_squared().sqrt()
+    }
+
+    pub fn to_f64(&self) -> Vec3<f64> {
+        Vec3::new(self.x.to_f64(), self.y.to_f64(), self.z.to_f64())
+    }
+}
+
+impl<T: Float> Vec3<T> {
+    /// Same direction, length 1. `None` for the zero vector, which has no
+    /// direction.
+    pub fn normalize(&self) -> Option<Vec3<T>> {
+        let length = self.length_squared().sqrt();
+        if length == T::default() {
+            None
+        } else {
+            Some(*self / length)
+        }
+    }
+}
+
+impl<T: Scalar> Point3<T> {
+    pub fn new(x: T, y: T, z: T) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+
+    pub fn origin() -> Point3<T> {
+        Point3::default()
+    }
+
+    /// How far this point is from the origin, as a vector.
+    pub fn to_vec(&self) -> Vec3<T> {
+        Vec3::new(self.x, self.y, self.z)
+    }
+
+    pub fn distance_squared(&self, other: Point3<T>) -> T {
+        (*self - other).length_squared()
+    }
+
+    pub fn distance(&self, other: Point3<T>) -> f64 {
+        (*self - other).length()
+    }
+}
+
+// vector + vector, vector - vector, -vector
+impl<T: Scalar> Add for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn add(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x + other.x, self.y + other.y, self.z + other.z)
+    }
+}
+
+impl<T: Scalar> Sub for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: Vec3<T>) -> Vec3<T> {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Scalar> Neg for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn neg(self) -> Vec3<T> {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl<T: Scalar> AddAssign for Vec3<T> {
+    fn add_assign(&mut self, other: Vec3<T>) {
+        *self = *self + other;
+    }
+}
+
+impl<T: Scalar> SubAssign for Vec3<T> {
+    fn sub_assign(&mut self, other: Vec3<T>) {
+        *self = *self - other;
+    }
+}
+
+// vector * number. number * vector needs one impl per number type, since
+// the left hand side has to be a type from this crate for a generic impl
+impl<T: Scalar> Mul<T> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn mul(self, k: T) -> Vec3<T> {
+        Vec3::new(self.x * k, self.y * k, self.z * k)
+    }
+}
+
+impl<T: Scalar> MulAssign<T> for Vec3<T> {
+    fn mul_assign(&mut self, k: T) {
+        *self = *self * k;
+    }
+}
+
+macro_rules! scalar_times_vec {
+    ($($t:ty),*) => {$(
+        impl Mul<Vec3<$t>> for $t {
+            type Output = Vec3<$t>;
+
+            fn mul(self, v: Vec3<$t>) -> Vec3<$t> {
+                v * self
+            }
+        }
+    )*};
+}
+
+scalar_times_vec!(i32, f32, f64);
+
+// only for floats, integer division would quietly truncate
+impl<T: Float> Div<T> for Vec3<T> {
+    type Output = Vec3<T>;
+
+    fn div(self, k: T) -> Vec3<T> {
+        Vec3::new(self.x / k, self.y / k, self.z / k)
+    }
+}
+
+impl<T: Scalar> Sum for Vec3<T> {
+    fn sum<I: Iterator<Item = Vec3<T>>>(iter: I) -> Vec3<T> {
+        iter.fold(Vec3::zero(), |total, v| total + v)
+    }
+}
+
+// point + vector and point - vector move the point, point - point is the
+// vector from one to the other. no point + point
+impl<T: Scalar> Add<Vec3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn add(self, v: Vec3<T>) -> Point3<T> {
+        Point3::new(self.x + v.x, self.y + v.y, self.z + v.z)
+    }
+}
+
+impl<T: Scalar> Sub<Vec3<T>> for Point3<T> {
+    type Output = Point3<T>;
+
+    fn sub(self, v: Vec3<T>) -> Point3<T> {
+        Point3::new(self.x - v.x, self.y - v.y, self.z - v.z)
+    }
+}
+
+impl<T: Scalar> Sub for Point3<T> {
+    type Output = Vec3<T>;
+
+    fn sub(self, other: Point3<T>) -> Vec3<T> {
+        Vec3::new(self.x - other.x, self.y - other.y, self.z - other.z)
+    }
+}
+
+impl<T: Scalar> AddAssign<Vec3<T>> for Point3<T> {
+    fn add_assign(&mut self, v: Vec3<T>) {
+        *self = *self + v;
+    }
+}
+
+impl<T: Scalar> SubAssign<Vec3<T>> for Point3<T> {
+    fn sub_assign(&mut self, v: Vec3<T>) {
+        *self = *self - v;
+    }
+}
+
+impl<T> From<[T; 3]> for Vec3<T> {
+    fn from([x, y, z]: [T; 3]) -> Vec3<T> {
+        Vec3 { x, y, z }
+    }
+}
+
+impl<T> From<[T; 3]> for Point3<T> {
+    fn from([x, y, z]: [T; 3]) -> Point3<T> {
+        Point3 { x, y, z }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Vec3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "<{}, {}, {}>", self.x, self.y, self.z)
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Point3<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "({}, {}, {})", self.x, self.y, self.z)
+    }
+}
@@ -0,0 +1,256 @@
+// logins and sessions, with a ManualClock so lockouts and expiry can be
+// skipped through instead of waited out
+use ch5_structs::auth::{AuthConfig, AuthError, Authenticator, ManualClock, PasswordHash};
+use ch5_structs::store::{StoreError, UserStore};
+use ch5_structs::user::{User, UserError};
+use std::time::{Duration, SystemTime};
+
+const LOCKOUT: Duration = Duration::from_secs(60);
+const TTL: Duration = Duration::from_secs(600);
+
+fn deactivate(user: &mut User) -> Result<(), UserError> {
+    user.deactivate();
+    Ok(())
+}
+
+// ferris has a password, corro doesn't
+fn setup() -> (Authenticator<ManualClock>, ManualClock) {
+    let mut store = UserStore::new();
+    store
+        .insert(User::new("ferris", "ferris@rust-lang.org").unwrap())
+        .unwrap();
+    store
+        .insert(User::new("corro", "corro@unsafe.rs").unwrap())
+        .unwrap();
+
+    let clock = ManualClock::new(SystemTime::UNIX_EPOCH);
+    let config = AuthConfig {
+        max_failures: 3,
+        lockout: LOCKOUT,
+        session_ttl: TTL,
+        // the real count is slow on purpose, and slower in a debug build
+        iterations: 1_000,
+    };
+    let mut auth = Authenticator::with_config(store, clock.clone(), config);
+    auth.set_password("ferris", "hunter2").unwrap();
+    (auth, clock)
+}
+
+#[test]
+fn password_hashes() {
+    let hash = PasswordHash::new("hunter2", 1_000);
+    assert!(hash.verify("hunter2"));
+    assert!(!hash.verify("hunter3"));
+    assert!(!hash.verify(""));
+    // a fresh salt every time
+    assert_ne!(hash, PasswordHash::new("hunter2", 1_000));
+    // and the hash stays out of Debug
+    assert_eq!(
+        format!("{:?}", hash),
+        "PasswordHash { iterations: 1000, .. }"
+    );
+}
+
+#[test]
+fn login_counts_sign_ins() {
+    let (mut auth, _) = setup();
+    let first = auth.login("ferris", "hunter2").unwrap();
+    let second = auth.login("ferris", "hunter2").unwrap();
+    assert_ne!(first.token, second.token);
+    assert_eq!(first.username, "ferris");
+    assert_eq!(first.expires, SystemTime::UNIX_EPOCH + TTL);
+    assert_eq!(auth.store().get("ferris").unwrap().sign_in_count(), 2);
+}
+
+#[test]
+fn unknown_users_look_like_wrong_passwords() {
+    let (mut auth, _) = setup();
+    assert_eq!(
+        auth.login("ferris", "hunter3"),
+        Err(AuthError::InvalidCredentials)
+    );
+    assert_eq!(
+        auth.login("nobody", "hunter2"),
+        Err(AuthError::InvalidCredentials)
+    );
+    // no password set yet
+    assert_eq!(
+        auth.login("corro", "hunter2"),
+        Err(AuthError::InvalidCredentials)
+    );
+    assert_eq!(
+        auth.set_password("nobody", "hunter2"),
+        Err(AuthError::Store(StoreError::NotFound(String::from(
+            "nobody"
+        ))))
+    );
+}
+
+#[test]
+fn inactive_only_shows_after_the_right_password() {
+    let (mut auth, _) = setup();
+    auth.update_user("ferris", deactivate).unwrap();
+
+    // someone who only knows the name can't tell the account is there
+    assert_eq!(
+        auth.login("ferris", "hunter3"),
+        Err(AuthError::InvalidCredentials)
+    );
+    assert_eq!(auth.login("ferris", "hunter2"), Err(AuthError::Inactive));
+    assert_eq!(auth.store().get("ferris").unwrap().sign_in_count(), 0);
+}
+
+#[test]
+fn lockout_runs_out() {
+    let (mut auth, clock) = setup();
+    let until = SystemTime::UNIX_EPOCH + LOCKOUT;
+
+    for _ in 0..2 {
+        assert_eq!(
+            auth.login("ferris", "wrong"),
+            Err(AuthError::InvalidCredentials)
+        );
+    }
+    assert_eq!(
+        auth.login("ferris", "wrong"),
+        Err(AuthError::Locked { until })
+    );
+    // the right password doesn't help while it's locked
+    assert_eq!(
+        auth.login("ferris", "hunter2"),
+        Err(AuthError::Locked { until })
+    );
+
+    clock.advance(LOCKOUT - Duration::from_secs(1));
+    assert_eq!(
+        auth.login("ferris", "hunter2"),
+        Err(AuthError::Locked { until })
+    );
+
+    clock.advance(Duration::from_secs(1));
+    auth.login("ferris", "hunter2").unwrap();
+}
+
+#[test]
+fn failures_reset_after_a_good_login() {
+    let (mut auth, _) = setup();
+    for _ in 0..2 {
+        auth.login("ferris", "wrong").unwrap_err();
+    }
+    auth.login("ferris", "hunter2").unwrap();
+    // two more wrong ones is only two in a row, not four
+    for _ in 0..2 {
+        assert_eq!(
+            auth.login("ferris", "wrong"),
+            Err(AuthError::InvalidCredentials)
+        );
+    }
+}
+
+#[test]
+fn setting_a_password_clears_the_lockout() {
+    let (mut auth, _) = setup();
+    for _ in 0..3 {
+        auth.login("ferris", "wrong").unwrap_err();
+    }
+    auth.set_password("ferris", "correct horse").unwrap();
+    auth.login("ferris", "correct horse").unwrap();
+    assert_eq!(
+        auth.login("ferris", "hunter2"),
+        Err(AuthError::InvalidCredentials)
+    );
+}
+
+#[test]
+fn sessions_time_out() {
+    let (mut auth, clock) = setup();
+    let session = auth.login("ferris", "hunter2").unwrap();
+
+    clock.advance(TTL - Duration::from_secs(1));
+    assert_eq!(auth.validate(&session.token), Ok("ferris"));
+
+    clock.advance(Duration::from_secs(1));
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::SessionExpired)
+    );
+    // and it's gone for good after that
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::InvalidSession)
+    );
+    assert!(!auth.logout(&session.token));
+}
+
+#[test]
+fn logout_ends_the_session() {
+    let (mut auth, _) = setup();
+    let session = auth.login("ferris", "hunter2").unwrap();
+    let other = auth.login("ferris", "hunter2").unwrap();
+    assert!(auth.logout(&session.token));
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::InvalidSession)
+    );
+    assert_eq!(auth.validate(&other.token), Ok("ferris"));
+    assert_eq!(auth.validate("not a token"), Err(AuthError::InvalidSession));
+}
+
+#[test]
+fn sessions_end_with_their_user() {
+    let (mut auth, _) = setup();
+    let session = auth.login("ferris", "hunter2").unwrap();
+    auth.update_user("ferris", deactivate).unwrap();
+    assert_eq!(auth.validate(&session.token), Err(AuthError::Inactive));
+    // coming back doesn't bring the old session with it
+    auth.update_user("ferris", |user| {
+        user.activate();
+        Ok(())
+    })
+    .unwrap();
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::InvalidSession)
+    );
+
+    let session = auth.login("ferris", "hunter2").unwrap();
+    auth.remove_user("ferris").unwrap();
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::InvalidSession)
+    );
+}
+
+#[test]
+fn a_new_user_with_an_old_name_starts_fresh() {
+    let (mut auth, _) = setup();
+    let session = auth.login("ferris", "hunter2").unwrap();
+    let removed = auth.remove_user("ferris").unwrap();
+    assert_eq!(removed.username(), "ferris");
+    assert_eq!(
+        auth.remove_user("ferris"),
+        Err(AuthError::Store(StoreError::NotFound("ferris".to_string())))
+    );
+
+    auth.add_user(User::new("ferris", "someone.else@example.com").unwrap())
+        .unwrap();
+    // neither the old password nor the old session work for them
+    assert_eq!(
+        auth.login("ferris", "hunter2"),
+        Err(AuthError::InvalidCredentials)
+    );
+    assert_eq!(
+        auth.validate(&session.token),
+        Err(AuthError::InvalidSession)
+    );
+
+    auth.set_password("ferris", "correct horse").unwrap();
+    let session = auth.login("ferris", "correct horse").unwrap();
+    assert_eq!(auth.validate(&session.token), Ok("ferris"));
+    assert_eq!(auth.store().get("ferris").unwrap().sign_in_count(), 1);
+    // other people's sessions are left alone
+    auth.set_password("corro", "hunter2").unwrap();
+    let other = auth.login("corro", "hunter2").unwrap();
+    auth.remove_user("ferris").unwrap();
+    assert_eq!(auth.validate(&other.token), Ok("corro"));
+}
@@ -0,0 +1,132 @@
+// Circle's maths, small ones where the answers are easy to check and huge
+// ones where the radius is past i32::MAX or the center is out at the bounds
+use ch5_structs::geometry::{Circle, Point, Polygon, Rectangle};
+use std::f64::consts::PI;
+
+fn circle(x: i32, y: i32, radius: u32) -> Circle {
+    Circle::new(Point::new(x, y), radius)
+}
+
+#[test]
+fn area_and_perimeter() {
+    assert_eq!(circle(0, 0, 0).area(), 0.0);
+    assert_eq!(circle(3, -3, 1).area(), PI);
+    assert_eq!(circle(0, 0, 10).area(), 100.0 * PI);
+    assert_eq!(circle(0, 0, 10).perimeter(), 20.0 * PI);
+    // f64 has plenty of room, even squared
+    let huge = circle(0, 0, u32::MAX).area();
+    assert_eq!(huge, PI * (f64::from(u32::MAX) * f64::from(u32::MAX)));
+}
+
+#[test]
+fn contains() {
+    let c = circle(0, 0, 5);
+    // 3, 4, 5: right on the edge still counts
+    assert!(c.contains_point(&Point::new(3, 4)));
+    assert!(c.contains_point(&Point::new(0, -5)));
+    assert!(c.contains_point(&Point::new(0, 0)));
+    assert!(!c.contains_point(&Point::new(4, 4)));
+    assert!(!c.contains_point(&Point::new(6, 0)));
+
+    assert!(c.contains_rect(&Rectangle::at(Point::new(-3, -4), 6, 8)));
+    assert!(!c.contains_rect(&Rectangle::at(Point::new(-3, -4), 7, 8)));
+    assert!(c.contains_circle(&circle(1, 1, 3)));
+    assert!(c.contains_circle(&c));
+    assert!(!c.contains_circle(&circle(1, 0, 5)));
+    assert!(!c.contains_circle(&circle(0, 0, 6)));
+    assert!(c.contains_polygon(&Polygon::new(vec![
+        Point::new(-3, 4),
+        Point::new(5, 0),
+        Point::new(0, -5)
+    ])));
+    assert!(!c.contains_polygon(&Polygon::new(vec![
+        Point::new(-3, 4),
+        Point::new(5, 5),
+        Point::new(0, -5)
+    ])));
+
+    // a zero radius circle is just its center
+    let dot = circle(2, 2, 0);
+    assert!(dot.contains_point(&Point::new(2, 2)));
+    assert!(!dot.contains_point(&Point::new(2, 3)));
+}
+
+#[test]
+fn contains_out_at_the_bounds() {
+    // big enough to reach from one corner of the grid to the other
+    let everything = circle(0, 0, u32::MAX);
+    for &(x, y) in &[
+        (i32::MIN, i32::MIN),
+        (i32::MAX, i32::MAX),
+        (i32::MIN, i32::MAX),
+    ] {
+        assert!(everything.contains_point(&Point::new(x, y)), "{} {}", x, y);
+    }
+    // its edges reach past i32::MAX, where corners() can't go
+    let far = Rectangle::at(Point::new(i32::MAX - 1, 0), 2, 2);
+    assert!(everything.contains_rect(&far));
+    assert!(!circle(i32::MAX, 0, 1).contains_rect(&far));
+
+    // the grid's corners are 2^32 apart each way, the squares don't fit i64
+    let corner = circle(i32::MIN, i32::MIN, 1);
+    assert!(!corner.contains_point(&Point::new(i32::MAX, i32::MAX)));
+    assert!(!corner.contains_circle(&circle(i32::MAX, i32::MAX, 0)));
+    assert!(everything.contains_circle(&circle(i32::MIN, 0, 0)));
+    assert!(!everything.contains_circle(&circle(i32::MIN, i32::MIN, u32::MAX)));
+}
+
+#[test]
+fn bounding_box() {
+    assert_eq!(
+        circle(5, 5, 2).bounding_box(),
+        Rectangle::at(Point::new(3, 3), 4, 4)
+    );
+    assert_eq!(
+        circle(0, 0, 0).bounding_box(),
+        Rectangle::at(Point::new(0, 0), 0, 0)
+    );
+
+    // radius past i32::MAX, still fine with the center far enough right
+    let r = 3_000_000_000 / 2;
+    let wide = circle(i32::MAX, 0, r);
+    let b = wide.bounding_box();
+    assert_eq!(b.top_left(), Point::new(i32::MAX - r as i32, -(r as i32)));
+    assert_eq!(b.width, 3_000_000_000);
+    assert_eq!(b.right(), i64::from(i32::MAX) + i64::from(r));
+    assert!(b.contains_circle(&wide));
+
+    // the most that fits: top left at i32::MIN, a u32::MAX - 1 diameter
+    let max = circle(-1, -1, i32::MAX as u32);
+    assert_eq!(
+        max.checked_bounding_box(),
+        Some(Rectangle::at(
+            Point::new(i32::MIN, i32::MIN),
+            u32::MAX - 1,
+            u32::MAX - 1
+        ))
+    );
+}
+
+#[test]
+fn bounding_box_that_doesnt_fit() {
+    // the left edge would be before i32::MIN
+    assert_eq!(circle(0, 0, 3_000_000_000).checked_bounding_box(), None);
+    assert_eq!(circle(i32::MIN, 0, 1).checked_bounding_box(), None);
+    assert_eq!(circle(0, i32::MIN, 1).checked_bounding_box(), None);
+    // or the diameter past u32::MAX
+    assert_eq!(
+        circle(i32::MAX, i32::MAX, u32::MAX).checked_bounding_box(),
+        None
+    );
+
+    // and a rectangle can't hold what doesn't fit in one
+    let everything = Rectangle::at(Point::new(i32::MIN, i32::MIN), u32::MAX, u32::MAX);
+    assert!(!everything.contains_circle(&circle(0, 0, 3_000_000_000)));
+    assert!(everything.contains_circle(&circle(0, 0, 1_000_000_000)));
+}
+
+#[test]
+#[should_panic(expected = "doesn't fit in a Rectangle")]
+fn bounding_box_panics_when_it_doesnt_fit() {
+    circle(0, 0, 3_000_000_000).bounding_box();
+}
@@ -0,0 +1,194 @@
+// property tests for Color: conversions that should round trip are tried on
+// lots of random colours instead of a few picked by hand. the rng is seeded,
+// so a failure happens the same way every run
+use ch5_structs::color::{Color, ColorError, Contrast, Hsl, Hsv};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::convert::TryFrom;
+
+const CASES: usize = 100_000;
+
+fn random_colors(seed: u64) -> impl Iterator<Item = Color> {
+    let mut rng = StdRng::seed_from_u64(seed);
+    (0..CASES).map(move |_| Color::rgba(rng.gen(), rng.gen(), rng.gen(), rng.gen()))
+}
+
+// the spots most likely to go wrong: greys, where one channel is the biggest
+// and two tie, and the ends of every channel
+fn edge_colors() -> Vec<Color> {
+    let levels = [0, 1, 127, 128, 254, 255];
+    let mut colors = Vec::new();
+    for &r in &levels {
+        for &g in &levels {
+            for &b in &levels {
+                colors.push(Color::rgb(r, g, b));
+            }
+        }
+    }
+    colors
+}
+
+#[test]
+fn hex_round_trips() {
+    for color in random_colors(1).chain(edge_colors()) {
+        assert_eq!(Color::from_hex(&color.to_hex()), Ok(color));
+        assert_eq!(color.to_string().parse(), Ok(color));
+    }
+}
+
+#[test]
+fn hsl_round_trips() {
+    for color in random_colors(2).chain(edge_colors()) {
+        let opaque = color.with_alpha(255);
+        let hsl = color.to_hsl();
+        assert!((0.0..360.0).contains(&hsl.hue()), "{:?}", hsl);
+        // going through new checks the values are all in range
+        let again = Hsl::new(hsl.hue(), hsl.saturation(), hsl.lightness()).unwrap();
+        assert_eq!(Color::from(again), opaque, "{}", hsl);
+    }
+}
+
+#[test]
+fn hsv_round_trips() {
+    for color in random_colors(3).chain(edge_colors()) {
+        let opaque = color.with_alpha(255);
+        let hsv = color.to_hsv();
+        assert!((0.0..360.0).contains(&hsv.hue()), "{:?}", hsv);
+        let again = Hsv::new(hsv.hue(), hsv.saturation(), hsv.value()).unwrap();
+        assert_eq!(Color::from(again), opaque, "{}", hsv);
+    }
+}
+
+#[test]
+fn names_round_trip() {
+    for name in Color::names() {
+        let color = Color::named(name).unwrap();
+        // aliases like grey come back as gray, but still the same colour
+        assert_eq!(Color::named(color.name().unwrap()), Some(color));
+        assert_eq!(name.to_uppercase().parse(), Ok(color));
+    }
+    assert_eq!(Color::names().count(), 148);
+    assert_eq!(Color::named("grey"), Color::named("gray"));
+    assert_eq!(Color::rgb(1, 2, 3).name(), None);
+    assert_eq!(Color::BLACK.with_alpha(0).name(), None);
+}
+
+#[test]
+fn short_hex() {
+    assert_eq!(Color::from_hex("#fa0"), Ok(Color::rgb(0xff, 0xaa, 0x00)));
+    assert_eq!(
+        Color::from_hex("FA08"),
+        Ok(Color::rgba(0xff, 0xaa, 0x00, 0x88))
+    );
+    assert_eq!(Color::rgb(1, 2, 3).with_alpha(4).to_hex(), "#01020304");
+    for bad in &["", "#", "#ff", "#fffff", "#ggg", "#+ff", "#ffé", "##fff"] {
+        assert_eq!(Color::from_hex(bad), Err(ColorError::Hex(bad.to_string())));
+    }
+    assert_eq!(
+        "notacolour".parse::<Color>(),
+        Err(ColorError::Unknown("notacolour".to_string()))
+    );
+}
+
+#[test]
+fn validation() {
+    assert_eq!(Color::try_from((255, 0, 0)), Ok(Color::rgb(255, 0, 0)));
+    assert_eq!(
+        Color::try_from((0, 256, 0)),
+        Err(ColorError::Channel {
+            channel: 'g',
+            value: 256
+        })
+    );
+    assert_eq!(
+        Color::try_from((0, 0, 0, -1)),
+        Err(ColorError::Channel {
+            channel: 'a',
+            value: -1
+        })
+    );
+
+    // hue wraps, everything else has to be in range
+    assert_eq!(Hsl::new(-90.0, 1.0, 0.5).unwrap().hue(), 270.0);
+    assert_eq!(Hsl::new(720.0, 1.0, 0.5).unwrap().hue(), 0.0);
+    assert_eq!(Hsl::new(-1e-20, 1.0, 0.5).unwrap().hue(), 0.0);
+    assert_eq!(
+        Hsl::new(0.0, 1.5, 0.5),
+        Err(ColorError::Component("saturation"))
+    );
+    assert_eq!(
+        Hsv::new(0.0, 0.5, -0.1),
+        Err(ColorError::Component("value"))
+    );
+    assert_eq!(
+        Hsl::new(f64::NAN, 0.5, 0.5),
+        Err(ColorError::Component("hue"))
+    );
+    assert_eq!(
+        Hsl::new(0.0, 0.5, f64::NAN),
+        Err(ColorError::Component("lightness"))
+    );
+}
+
+#[test]
+fn blending() {
+    let mut rng = StdRng::seed_from_u64(4);
+    for (fg, bg) in random_colors(5).zip(random_colors(6)).take(10_000) {
+        // opaque covers everything, transparent covers nothing
+        assert_eq!(fg.with_alpha(255).over(bg), fg.with_alpha(255));
+        assert_eq!(fg.with_alpha(0).over(bg), bg);
+        // on an opaque background the result is opaque too
+        assert!(fg.over(bg.with_alpha(255)).is_opaque());
+
+        assert_eq!(fg.mix(bg, 0.0), fg);
+        assert_eq!(fg.mix(bg, 1.0), bg);
+        let t: f64 = rng.gen();
+        let mid = fg.mix(bg, t);
+        assert!(fg.r.min(bg.r) <= mid.r && mid.r <= fg.r.max(bg.r));
+    }
+    assert_eq!(
+        Color::BLACK.mix(Color::WHITE, 2.0),
+        Color::WHITE,
+        "t gets clamped"
+    );
+}
+
+#[test]
+fn contrast() {
+    for (a, b) in random_colors(7).zip(random_colors(8)).take(10_000) {
+        let ratio = a.contrast_ratio(b);
+        assert!((1.0..=21.0).contains(&ratio));
+        assert_eq!(ratio, b.contrast_ratio(a));
+        assert_eq!(a.contrast_ratio(a), 1.0);
+    }
+
+    // known values from the WCAG examples and every contrast checker out
+    // there
+    assert_eq!(Color::BLACK.contrast_ratio(Color::WHITE), 21.0);
+    let ratio = |a: &str, b: &str| {
+        let (a, b): (Color, Color) = (a.parse().unwrap(), b.parse().unwrap());
+        (a.contrast_ratio(b) * 100.0).round() / 100.0
+    };
+    assert_eq!(ratio("#767676", "white"), 4.54);
+    assert_eq!(ratio("#777777", "white"), 4.48);
+    assert_eq!(ratio("red", "white"), 4.0);
+
+    let white = Color::WHITE;
+    assert_eq!(
+        Color::from_hex("#595959").unwrap().contrast(white),
+        Contrast::Aaa
+    );
+    assert_eq!(
+        Color::from_hex("#767676").unwrap().contrast(white),
+        Contrast::Aa
+    );
+    assert_eq!(
+        Color::from_hex("#777777").unwrap().contrast(white),
+        Contrast::AaLarge
+    );
+    assert_eq!(
+        Color::named("yellow").unwrap().contrast(white),
+        Contrast::Fail
+    );
+    assert!(Contrast::Aaa > Contrast::Aa && Contrast::AaLarge > Contrast::Fail);
+}
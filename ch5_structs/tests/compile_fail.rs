@@ -0,0 +1,11 @@
+// the two commented out lines in vectors() in main.rs, painting a Point3 as
+// if it were a Color and adding two Point3s, each have a copy in
+// tests/compile_fail/. this checks rustc still rejects them with the same
+// error. if rustc changes its mind, the .stderr file won't match
+
+// regenerate the .stderr files with TRYBUILD=overwrite cargo test
+#[test]
+fn compile_fail() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/compile_fail/*.rs");
+}
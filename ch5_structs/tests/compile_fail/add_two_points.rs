@@ -0,0 +1,8 @@
+// vectors: a point plus a vector is a point, but a point plus a point isn't
+// anything
+use ch5_structs::vec3::Point3;
+
+fn main() {
+    let origin: Point3<i32> = Point3::origin();
+    let _ = origin + origin;
+}
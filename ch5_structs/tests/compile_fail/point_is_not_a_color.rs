@@ -0,0 +1,12 @@
+// vectors: same fields or not, a point isn't a colour
+use ch5_structs::color::Color;
+use ch5_structs::vec3::Point3;
+
+fn paint(color: Color) -> String {
+    color.to_hex()
+}
+
+fn main() {
+    let origin: Point3<i32> = Point3::origin();
+    paint(origin);
+}
@@ -0,0 +1,216 @@
+// both packing algorithms, on the easy cases, the awkward ones (rectangles
+// with no width or height) and random piles from a seeded rng
+use ch5_structs::geometry::packing::{Algorithm, PackError, Packer, Packing};
+use ch5_structs::geometry::Rectangle;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ALGORITHMS: [Algorithm; 2] = [Algorithm::Guillotine, Algorithm::Skyline];
+
+fn sizes(sizes: &[(u32, u32)]) -> Vec<Rectangle> {
+    sizes.iter().map(|&(w, h)| Rectangle::new(w, h)).collect()
+}
+
+// everything that should hold for any packing of `items`: each one placed
+// once, at its own size (or turned), inside its bin, not overlapping
+fn check(packing: &Packing, items: &[Rectangle]) {
+    let mut seen = vec![false; items.len()];
+    for p in &packing.placements {
+        assert!(!seen[p.index], "{} placed twice", p.index);
+        seen[p.index] = true;
+
+        let item = items[p.index];
+        let size = if p.rotated {
+            (item.height, item.width)
+        } else {
+            (item.width, item.height)
+        };
+        assert_eq!((p.rect.width, p.rect.height), size);
+        assert!(p.bin < packing.bins);
+        assert!(packing.bin_size.contains_rect(&p.rect), "{:?}", p);
+    }
+    assert!(seen.iter().all(|&s| s), "something wasn't placed");
+
+    for (i, a) in packing.placements.iter().enumerate() {
+        for b in &packing.placements[i + 1..] {
+            assert!(
+                a.bin != b.bin || !a.rect.intersects(&b.rect),
+                "{:?} {:?}",
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn fits_in_one_bin() {
+    // four quarters of the bin
+    let items = sizes(&[(5, 5), (5, 5), (5, 5), (5, 5)]);
+    for &algorithm in &ALGORITHMS {
+        let packing = Packer::new(10, 10, algorithm).pack(&items).unwrap();
+        check(&packing, &items);
+        assert_eq!(packing.bins, 1, "{:?}", algorithm);
+        assert_eq!(packing.wasted_area(), 0);
+    }
+}
+
+#[test]
+fn overflows_into_new_bins() {
+    // no two of these fit side by side or on top of each other
+    let items = sizes(&[(6, 6), (6, 6), (6, 6)]);
+    for &algorithm in &ALGORITHMS {
+        let packing = Packer::new(10, 10, algorithm).pack(&items).unwrap();
+        check(&packing, &items);
+        assert_eq!(packing.bins, 3, "{:?}", algorithm);
+        for bin in 0..3 {
+            assert_eq!(packing.in_bin(bin).count(), 1);
+        }
+        assert_eq!(packing.wasted_area(), 300 - 3 * 36);
+    }
+}
+
+#[test]
+fn rotation() {
+    let items = sizes(&[(10, 2)]);
+    for &algorithm in &ALGORITHMS {
+        let packer = Packer::new(2, 10, algorithm);
+        assert_eq!(packer.pack(&items), Err(PackError::TooBig { index: 0 }));
+
+        let packing = packer.allow_rotation(true).pack(&items).unwrap();
+        check(&packing, &items);
+        assert!(packing.placements[0].rotated);
+        assert_eq!(packing.placements[0].rect, Rectangle::new(2, 10));
+
+        // and it doesn't turn things that already fit
+        let square = Packer::new(10, 10, algorithm)
+            .allow_rotation(true)
+            .pack(&items)
+            .unwrap();
+        assert!(!square.placements[0].rotated);
+    }
+}
+
+#[test]
+fn too_big() {
+    let items = sizes(&[(1, 1), (11, 1), (1, 11)]);
+    for &algorithm in &ALGORITHMS {
+        assert_eq!(
+            Packer::new(10, 10, algorithm).pack(&items),
+            Err(PackError::TooBig { index: 1 })
+        );
+    }
+}
+
+#[test]
+fn bins_past_i32_max() {
+    // a placement that far right couldn't be a Rectangle
+    let items = sizes(&[(3_000_000_000, 5), (1_000_000_000, 5)]);
+    for &algorithm in &ALGORITHMS {
+        for &(w, h) in &[(u32::MAX, 10), (10, u32::MAX), (1 << 31, 1 << 31)] {
+            assert_eq!(
+                Packer::new(w, h, algorithm).pack(&items),
+                Err(PackError::BinTooBig)
+            );
+        }
+    }
+}
+
+#[test]
+fn bins_at_i32_max() {
+    let max = i32::MAX as u32;
+    // the first two fill a row exactly, the third goes under them
+    let items = sizes(&[(2_000_000_000, 5), (max - 2_000_000_000, 5), (max, 5)]);
+    for &algorithm in &ALGORITHMS {
+        let packing = Packer::new(max, 10, algorithm).pack(&items).unwrap();
+        check(&packing, &items);
+        assert_eq!(packing.bins, 1, "{:?}", algorithm);
+        assert_eq!(packing.wasted_area(), 0);
+        assert!(packing.placements.iter().all(|p| p.rect.x >= 0));
+        assert!(packing
+            .placements
+            .iter()
+            .any(|p| p.rect.right() == i64::from(max)));
+
+        // side by side, the bins are wider than a u32 in the svg
+        let packing = Packer::new(max, max, algorithm)
+            .pack(&sizes(&[(max, 1), (max, max), (max, max)]))
+            .unwrap();
+        assert_eq!(packing.bins, 3);
+        assert_eq!(
+            packing.wasted_area(),
+            u128::from(max) * u128::from(max) - u128::from(max)
+        );
+        let width = 3 * i64::from(max) + 4 * 10;
+        assert!(
+            packing.to_svg().contains(&format!(r#"width="{}""#, width)),
+            "{:?}",
+            algorithm
+        );
+    }
+}
+
+#[test]
+fn nothing_to_pack() {
+    for &algorithm in &ALGORITHMS {
+        let packing = Packer::new(10, 10, algorithm).pack(&[]).unwrap();
+        assert_eq!(packing.bins, 0);
+        assert!(packing.placements.is_empty());
+        assert_eq!(packing.wasted_area(), 0);
+    }
+}
+
+#[test]
+fn no_width_or_no_height() {
+    let items = sizes(&[(0, 0), (0, 4), (4, 0), (4, 4), (0, 3), (6, 10), (3, 0)]);
+    for &algorithm in &ALGORITHMS {
+        for &rotation in &[false, true] {
+            let packing = Packer::new(10, 10, algorithm)
+                .allow_rotation(rotation)
+                .pack(&items)
+                .unwrap();
+            check(&packing, &items);
+            // they take no room, so as long as there's a gap as long as they
+            // are, they don't need a bin of their own
+            assert_eq!(packing.bins, 1, "{:?}", algorithm);
+        }
+    }
+
+    // nothing but degenerate items, and a bin with no area either
+    let items = sizes(&[(0, 0), (0, 5), (0, 0)]);
+    for &algorithm in &ALGORITHMS {
+        let packing = Packer::new(0, 5, algorithm).pack(&items).unwrap();
+        check(&packing, &items);
+        assert_eq!(packing.bins, 1, "{:?}", algorithm);
+    }
+}
+
+#[test]
+fn random_piles() {
+    let mut rng = StdRng::seed_from_u64(35);
+    for _ in 0..300 {
+        let (bin_w, bin_h) = (rng.gen_range(1..40), rng.gen_range(1..40));
+        let items: Vec<Rectangle> = (0..rng.gen_range(0..30))
+            .map(|_| Rectangle::new(rng.gen_range(0..=bin_w), rng.gen_range(0..=bin_h)))
+            .collect();
+        let covered: u64 = items.iter().map(Rectangle::area).sum();
+
+        for &algorithm in &ALGORITHMS {
+            for &rotation in &[false, true] {
+                let packing = Packer::new(bin_w, bin_h, algorithm)
+                    .allow_rotation(rotation)
+                    .pack(&items)
+                    .unwrap();
+                check(&packing, &items);
+                // never more bins than items, and no fewer than the area needs
+                assert!(packing.bins <= items.len());
+                let bin_area = u64::from(bin_w) * u64::from(bin_h);
+                assert!(packing.bins as u64 * bin_area >= covered);
+                assert_eq!(
+                    packing.wasted_area(),
+                    u128::from(packing.bins as u64 * bin_area - covered)
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,128 @@
+// Polygon's containment checks, especially on shapes that aren't convex,
+// where a rectangle can poke out through a notch without any edges crossing
+use ch5_structs::geometry::{Circle, Point, Polygon, Rectangle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn polygon(points: &[(i32, i32)]) -> Polygon {
+    Polygon::new(points.iter().map(|&(x, y)| Point::new(x, y)).collect())
+}
+
+// a 6x4 block with a 2x2 notch cut into the bottom middle (y goes down)
+fn u_shape() -> Polygon {
+    polygon(&[
+        (0, 0),
+        (6, 0),
+        (6, 4),
+        (4, 4),
+        (4, 2),
+        (2, 2),
+        (2, 4),
+        (0, 4),
+    ])
+}
+
+#[test]
+fn points_in_a_u() {
+    let u = u_shape();
+    assert!(u.contains_point(&Point::new(1, 3)));
+    assert!(u.contains_point(&Point::new(3, 1)));
+    // the edges count, including the notch's
+    assert!(u.contains_point(&Point::new(3, 2)));
+    assert!(u.contains_point(&Point::new(4, 3)));
+    assert!(u.contains_point(&Point::new(6, 4)));
+    assert!(!u.contains_point(&Point::new(3, 3)));
+    assert!(!u.contains_point(&Point::new(3, 4)));
+    assert!(!u.contains_point(&Point::new(7, 1)));
+}
+
+#[test]
+fn rectangles_across_the_notch() {
+    let u = u_shape();
+    // every corner is inside and no edges cross, but its middle is the notch
+    let across = Rectangle::at(Point::new(0, 2), 6, 2);
+    assert!(!u.contains_rect(&across));
+    assert!(!u.contains_polygon(&across.to_polygon()));
+    assert!(!u.contains_rect(&Rectangle::new(6, 4)));
+    // just touching the notch from above is fine
+    assert!(u.contains_rect(&Rectangle::new(6, 2)));
+    assert!(u.contains_rect(&Rectangle::at(Point::new(0, 2), 2, 2)));
+    assert!(u.contains_rect(&Rectangle::at(Point::new(4, 0), 2, 4)));
+    // a line along the top of the notch is all boundary, a line below it isn't
+    assert!(u.contains_rect(&Rectangle::at(Point::new(0, 2), 6, 0)));
+    assert!(!u.contains_rect(&Rectangle::at(Point::new(0, 3), 6, 0)));
+}
+
+#[test]
+fn polygons_inside_polygons() {
+    let u = u_shape();
+    assert!(u.contains_polygon(&u));
+    // a triangle with its corners on the notch's outer corners, cutting
+    // straight through the notch
+    assert!(!u.contains_polygon(&polygon(&[(0, 0), (2, 4), (4, 4)])));
+    // and one that only goes round it
+    assert!(u.contains_polygon(&polygon(&[(0, 4), (0, 0), (6, 0), (6, 4), (4, 2), (2, 2)])));
+    // a proper crossing
+    assert!(!u.contains_polygon(&polygon(&[(1, 1), (7, 1), (1, 3)])));
+    assert!(u.contains_polygon(&Polygon::new(Vec::new())));
+}
+
+#[test]
+fn circles_in_a_u() {
+    let u = u_shape();
+    assert!(u.contains_circle(&Circle::new(Point::new(1, 1), 1)));
+    assert!(!u.contains_circle(&Circle::new(Point::new(1, 2), 2)));
+    assert!(!u.contains_circle(&Circle::new(Point::new(3, 3), 0)));
+}
+
+// a bar chart: columns of random heights standing on y = 10, so the tops
+// make plenty of notches. every edge is horizontal or vertical
+fn random_histogram(rng: &mut StdRng) -> Polygon {
+    let mut points = vec![Point::new(0, 10)];
+    let mut x = 0;
+    for _ in 0..rng.gen_range(1..6) {
+        let top = rng.gen_range(0..10);
+        let width = rng.gen_range(1..4);
+        points.push(Point::new(x, top));
+        x += width;
+        points.push(Point::new(x, top));
+    }
+    points.push(Point::new(x, 10));
+    points.dedup();
+    Polygon::new(points)
+}
+
+// with every edge on the grid lines, a rectangle with grid corners is inside
+// exactly when every point on the half grid in it is, so at double the size
+// checking the whole numbers is enough
+fn contains_by_brute_force(polygon: &Polygon, rect: &Rectangle) -> bool {
+    let (polygon, rect) = (polygon.scale(2.0), rect.scale(2.0));
+    let [top_left, _, bottom_right, _] = rect.corners();
+    (top_left.x..=bottom_right.x)
+        .all(|x| (top_left.y..=bottom_right.y).all(|y| polygon.contains_point(&Point::new(x, y))))
+}
+
+#[test]
+fn agrees_with_brute_force_on_random_notches() {
+    let mut rng = StdRng::seed_from_u64(33);
+    let mut inside = 0;
+    for _ in 0..2000 {
+        let polygon = random_histogram(&mut rng);
+        let rect = Rectangle::at(
+            Point::new(rng.gen_range(-1..14), rng.gen_range(-1..11)),
+            rng.gen_range(0..6),
+            rng.gen_range(0..6),
+        );
+        let expected = contains_by_brute_force(&polygon, &rect);
+        assert_eq!(
+            polygon.contains_rect(&rect),
+            expected,
+            "{:?} in {:?}",
+            rect,
+            polygon
+        );
+        inside += expected as usize;
+    }
+    // make sure both answers actually came up
+    assert!(inside > 100 && inside < 1900, "{} inside", inside);
+}
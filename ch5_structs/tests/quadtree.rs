@@ -0,0 +1,215 @@
+// every QuadTree query against checking every rectangle, on random ones from
+// a seeded rng so failures repeat. small trees so the naive scan stays quick,
+// but enough rectangles that the boxes split several times
+use ch5_structs::geometry::quadtree::{distance_squared, ItemId, QuadTree};
+use ch5_structs::geometry::{Point, Rectangle};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SIZE: i32 = 1_000;
+
+// mostly inside the tree's bounds, but some poke out or are right outside,
+// and some have no width or height
+fn random_rect(rng: &mut StdRng) -> Rectangle {
+    let corner = random_point(rng);
+    let mut side = || match rng.gen_range(0..10) {
+        0 => 0,
+        1 => rng.gen_range(100..400),
+        _ => rng.gen_range(1..40),
+    };
+    Rectangle::at(corner, side(), side())
+}
+
+fn random_point(rng: &mut StdRng) -> Point {
+    Point::new(
+        rng.gen_range(-SIZE / 10..SIZE * 11 / 10),
+        rng.gen_range(-SIZE / 10..SIZE * 11 / 10),
+    )
+}
+
+// the tree next to a plain list of what should be in it, None once removed
+struct Both {
+    tree: QuadTree<usize>,
+    ids: Vec<ItemId>,
+    rects: Vec<Option<Rectangle>>,
+}
+
+impl Both {
+    fn new(rng: &mut StdRng, count: usize) -> Both {
+        let mut both = Both::empty(Rectangle::square(SIZE as u32));
+        for _ in 0..count {
+            both.insert(random_rect(rng));
+        }
+        both
+    }
+
+    fn empty(bounds: Rectangle) -> Both {
+        Both {
+            tree: QuadTree::new(bounds),
+            ids: Vec::new(),
+            rects: Vec::new(),
+        }
+    }
+
+    fn insert(&mut self, rect: Rectangle) {
+        self.ids.push(self.tree.insert(rect, self.rects.len()));
+        self.rects.push(Some(rect));
+    }
+
+    fn remove(&mut self, i: usize) {
+        let removed = self.tree.remove(self.ids[i]).map(|(rect, _)| rect);
+        assert_eq!(removed, self.rects[i].take());
+    }
+
+    // the live rectangles that pass `keep`, as ids, in id order like the tree
+    fn naive(&self, keep: impl Fn(&Rectangle) -> bool) -> Vec<ItemId> {
+        self.rects
+            .iter()
+            .zip(&self.ids)
+            .filter(|(rect, _)| rect.as_ref().is_some_and(&keep))
+            .map(|(_, id)| *id)
+            .collect()
+    }
+
+    fn check(&self, rng: &mut StdRng) {
+        assert_eq!(
+            self.tree.len(),
+            self.rects.iter().filter(|r| r.is_some()).count()
+        );
+        for _ in 0..50 {
+            let p = random_point(rng);
+            assert_eq!(
+                self.tree.query_point(&p),
+                self.naive(|r| r.contains_point(&p)),
+                "{:?}",
+                p
+            );
+
+            let region = random_rect(rng);
+            assert_eq!(
+                self.tree.query_region(&region),
+                self.naive(|r| {
+                    i64::from(r.x) <= region.right()
+                        && i64::from(region.x) <= r.right()
+                        && i64::from(r.y) <= region.bottom()
+                        && i64::from(region.y) <= r.bottom()
+                }),
+                "{:?}",
+                region
+            );
+            assert_eq!(
+                self.tree.query_contained(&region),
+                self.naive(|r| region.contains_rect(r)),
+                "{:?}",
+                region
+            );
+
+            // min_by_key keeps the first of equal distances, so the lowest id
+            // like the tree
+            let nearest = self
+                .naive(|_| true)
+                .into_iter()
+                .min_by_key(|&id| distance_squared(self.tree.get(id).unwrap().0, &p));
+            assert_eq!(self.tree.nearest(&p), nearest, "{:?}", p);
+        }
+    }
+}
+
+#[test]
+fn queries_match_a_naive_scan() {
+    let mut rng = StdRng::seed_from_u64(36);
+    for &count in &[0, 1, 8, 9, 100, 2_000] {
+        let both = Both::new(&mut rng, count);
+        both.check(&mut rng);
+    }
+}
+
+#[test]
+fn queries_match_after_removing() {
+    let mut rng = StdRng::seed_from_u64(3636);
+    let mut both = Both::new(&mut rng, 1_000);
+
+    // take out a random half, checking as it goes
+    for round in 0..500 {
+        let i = rng.gen_range(0..both.rects.len());
+        both.remove(i);
+        if round % 100 == 0 {
+            both.check(&mut rng);
+        }
+    }
+    // then add some more, they get new ids rather than reusing old ones
+    for _ in 0..200 {
+        both.insert(random_rect(&mut rng));
+    }
+    both.check(&mut rng);
+
+    // everything out, and the tree's empty
+    for i in 0..both.rects.len() {
+        both.remove(i);
+    }
+    assert!(both.tree.is_empty());
+    both.check(&mut rng);
+}
+
+#[test]
+fn many_in_one_spot() {
+    // more than a box holds, all the same, so splitting never helps
+    let mut rng = StdRng::seed_from_u64(363);
+    let mut both = Both::new(&mut rng, 0);
+    for _ in 0..100 {
+        both.insert(Rectangle::at(Point::new(500, 500), 1, 1));
+    }
+    both.check(&mut rng);
+    assert_eq!(
+        both.tree.nearest(&Point::new(0, 0)),
+        Some(both.ids[0]),
+        "ties go to the lowest id"
+    );
+}
+
+#[test]
+fn bounds_past_i32_max() {
+    // the whole grid, and one whose middle is way past i32::MAX. enough in
+    // each that they split, all of them crowded up against the far corner
+    let mut rng = StdRng::seed_from_u64(36_036);
+    let near = i32::MAX - 100;
+    for &bounds in &[
+        Rectangle::at(Point::new(i32::MIN, i32::MIN), u32::MAX, u32::MAX),
+        Rectangle::at(Point::new(near, near), u32::MAX, u32::MAX),
+    ] {
+        let mut both = Both::empty(bounds);
+        for _ in 0..200 {
+            let corner = Point::new(
+                rng.gen_range(near..=i32::MAX),
+                rng.gen_range(near..=i32::MAX),
+            );
+            let side = rng.gen_range(0..=u32::MAX);
+            both.insert(Rectangle::at(corner, side, rng.gen_range(0..50)));
+        }
+        for _ in 0..200 {
+            let p = Point::new(
+                rng.gen_range(near..=i32::MAX),
+                rng.gen_range(near..=i32::MAX),
+            );
+            assert_eq!(
+                both.tree.query_point(&p),
+                both.naive(|r| r.contains_point(&p)),
+                "{:?}",
+                p
+            );
+            let region = Rectangle::at(p, rng.gen_range(0..20), rng.gen_range(0..20));
+            assert_eq!(
+                both.tree.query_contained(&region),
+                both.naive(|r| region.contains_rect(r)),
+                "{:?}",
+                region
+            );
+        }
+        let origin = Point::new(0, 0);
+        let nearest = both
+            .naive(|_| true)
+            .into_iter()
+            .min_by_key(|&id| distance_squared(both.tree.get(id).unwrap().0, &origin));
+        assert_eq!(both.tree.nearest(&origin), nearest);
+    }
+}
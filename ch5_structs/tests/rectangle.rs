@@ -0,0 +1,168 @@
+// Rectangle out at the edges of its number types: u32 sizes bigger than any
+// i32, positions at i32::MIN and i32::MAX, areas that need the Wide type and
+// integers too big for a float to hold
+use ch5_structs::geometry::{Circle, ConvertError, Point, Rectangle};
+
+const BIG: u32 = 3_000_000_000;
+
+#[test]
+fn sizes_past_i32_max() {
+    let wide = Rectangle::new(BIG, 1);
+    assert_eq!(wide.right(), 3_000_000_000);
+    assert_eq!(wide.bottom(), 1);
+    assert!(wide.contains_point(&Point::new(5, 0)));
+    assert!(wide.contains_point(&Point::new(i32::MAX, 1)));
+    assert!(!wide.contains_point(&Point::new(-1, 0)));
+    assert!(wide.contains_rect(&Rectangle::at(Point::new(1_000, 0), 5, 1)));
+    assert!(wide.contains_circle(&Circle::new(Point::new(100, 0), 0)));
+
+    let full = Rectangle::at(Point::new(i32::MIN, i32::MIN), u32::MAX, u32::MAX);
+    assert_eq!(full.right(), i64::from(i32::MAX));
+    assert_eq!(full.bottom(), i64::from(i32::MAX));
+    for &p in &[
+        (i32::MIN, i32::MIN),
+        (0, 0),
+        (i32::MAX, i32::MAX),
+        (i32::MIN, i32::MAX),
+    ] {
+        assert!(full.contains_point(&Point::new(p.0, p.1)), "{:?}", p);
+    }
+    assert!(full.contains_rect(&Rectangle::new(i32::MAX as u32, 1)));
+    // wide goes on past full's right edge
+    assert!(!full.contains_rect(&wide));
+    assert!(!wide.contains_rect(&full));
+}
+
+#[test]
+fn edges_past_i32_max() {
+    // the top left is on the grid, the rest of it runs off the end
+    let far = Rectangle::at(Point::new(i32::MAX - 1, i32::MAX), u32::MAX, u32::MAX);
+    assert_eq!(far.right(), i64::from(i32::MAX) - 1 + i64::from(u32::MAX));
+    assert_eq!(far.bottom(), i64::from(i32::MAX) + i64::from(u32::MAX));
+    assert!(far.contains_point(&Point::new(i32::MAX, i32::MAX)));
+    assert!(!far.contains_point(&Point::new(0, i32::MAX)));
+}
+
+#[test]
+fn overlap_at_the_bounds() {
+    let left = Rectangle::at(Point::new(i32::MIN, 0), u32::MAX, 10);
+    let right = Rectangle::at(Point::new(0, 5), BIG, 10);
+    assert_eq!(
+        left.intersection(&right),
+        Some(Rectangle::at(Point::new(0, 5), i32::MAX as u32, 5))
+    );
+    assert!(right.intersects(&left));
+
+    // both run past i32::MAX, the overlap does too
+    let far = Rectangle::at(Point::new(i32::MAX - 10, 5), BIG, 1);
+    assert_eq!(
+        right.intersection(&far),
+        Some(Rectangle::at(Point::new(i32::MAX - 10, 5), 852_516_363, 1))
+    );
+    assert_eq!(right.intersection(&far).unwrap().right(), right.right());
+
+    // just touching at i32::MAX doesn't count
+    let before = Rectangle::at(Point::new(0, 0), i32::MAX as u32, 1);
+    let after = Rectangle::at(Point::new(i32::MAX, 0), 1, 1);
+    assert_eq!(before.intersection(&after), None);
+}
+
+#[test]
+fn union_and_bounding_at_the_bounds() {
+    let min = Rectangle::at(Point::new(i32::MIN, i32::MIN), 0, 0);
+    let max = Rectangle::at(Point::new(i32::MAX, i32::MAX), 0, 0);
+    let everything = Rectangle::at(Point::new(i32::MIN, i32::MIN), u32::MAX, u32::MAX);
+    assert_eq!(min.union(&max), everything);
+    assert_eq!(
+        Rectangle::bounding(&[
+            Point::new(i32::MAX, i32::MIN),
+            Point::new(i32::MIN, i32::MAX)
+        ]),
+        Some(everything)
+    );
+    assert_eq!(everything.corners()[2], Point::new(i32::MAX, i32::MAX));
+}
+
+#[test]
+#[should_panic(expected = "wider or taller than u32::MAX")]
+fn union_too_wide_to_hold() {
+    let min = Rectangle::at(Point::new(i32::MIN, 0), 0, 0);
+    let far = Rectangle::at(Point::new(i32::MAX, 0), 1, 0);
+    min.union(&far);
+}
+
+#[test]
+#[should_panic(expected = "past i32::MAX")]
+fn corners_past_i32_max() {
+    Rectangle::at(Point::new(i32::MAX, 0), 1, 1).corners();
+}
+
+#[test]
+fn areas_in_the_wide_type() {
+    let biggest = Rectangle::square(u32::MAX);
+    assert_eq!(biggest.area(), u64::from(u32::MAX) * u64::from(u32::MAX));
+    assert_eq!(biggest.perimeter(), 4 * u64::from(u32::MAX));
+    assert_eq!(biggest.checked_area(), None);
+    assert_eq!(biggest.checked_perimeter(), None);
+
+    // f32 widens to f64, so even the biggest f32 area is finite
+    let huge = Rectangle::square(f32::MAX);
+    assert!(huge.area().is_finite());
+    assert_eq!(huge.checked_area(), None);
+    // f64 has nothing wider, so it runs out
+    assert_eq!(Rectangle::square(f64::MAX).area(), f64::INFINITY);
+    assert_eq!(Rectangle::square(f64::MAX).perimeter(), f64::INFINITY);
+}
+
+#[test]
+fn converting_to_floats_is_exact() {
+    // f32 holds every integer up to 2^24, f64 up to 2^53
+    let f32_max = 1 << 24;
+    assert_eq!(
+        Rectangle::new(f32_max, 1).try_convert::<f32>(),
+        Ok(Rectangle::new(16_777_216.0, 1.0))
+    );
+    assert_eq!(
+        Rectangle::new(f32_max + 1, 1).try_convert::<f32>(),
+        Err(ConvertError::Inexact)
+    );
+    // but f64 still can
+    assert_eq!(
+        Rectangle::new(f32_max + 1, 1).try_convert::<f64>(),
+        Ok(Rectangle::new(16_777_217.0, 1.0))
+    );
+    // past 2^24 the ones that land on an f32 are fine
+    assert_eq!(
+        Rectangle::new(u32::MAX - 255, 1).try_convert::<f32>(),
+        Ok(Rectangle::new(4_294_967_040.0, 1.0))
+    );
+    assert_eq!(
+        Rectangle::new(u32::MAX, 1).try_convert::<f32>(),
+        Err(ConvertError::Inexact)
+    );
+
+    let f64_max: u64 = 1 << 53;
+    assert!(Rectangle::new(f64_max, 1).try_convert::<f64>().is_ok());
+    assert_eq!(
+        Rectangle::new(f64_max + 1, 1).try_convert::<f64>(),
+        Err(ConvertError::Inexact)
+    );
+    assert_eq!(
+        Rectangle::new(1, -(1i64 << 53) - 1).try_convert::<f64>(),
+        Err(ConvertError::Inexact)
+    );
+
+    // f64 to f32 rounds, only running out of range fails
+    assert_eq!(
+        Rectangle::new(0.1, 1e300).try_convert::<f32>(),
+        Err(ConvertError::OutOfRange)
+    );
+    assert_eq!(
+        Rectangle::new(0.1, 2.5).try_convert::<f32>(),
+        Ok(Rectangle::new(0.1f32, 2.5))
+    );
+    assert_eq!(
+        Rectangle::new(f64::NAN, 1.0).try_convert::<f32>(),
+        Err(ConvertError::NotFinite)
+    );
+}
@@ -0,0 +1,118 @@
+// the renderers promise the same output for the same input, so their output
+// is checked in under tests/golden. a change to how things are drawn shows
+// up as a diff there, which is also an easy way to look at it
+
+// regenerate the golden files with GOLDEN=overwrite cargo test
+use ch5_structs::geometry::{render, Circle, Point, Polygon, Rectangle, Scene};
+use std::env;
+use std::fs;
+use std::path::Path;
+
+fn check(name: &str, actual: &str) {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests/golden")
+        .join(name);
+    if env::var("GOLDEN").is_ok_and(|v| v == "overwrite") {
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|e| panic!("can't read {}: {}", path.display(), e));
+    assert!(
+        expected == actual,
+        "{} doesn't match, got:\n{}",
+        path.display(),
+        actual
+    );
+}
+
+fn can_hold_rects() -> [Rectangle; 3] {
+    [
+        Rectangle::new(30, 50),
+        Rectangle::new(10, 40),
+        Rectangle::new(60, 45),
+    ]
+}
+
+fn scene() -> Scene {
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(-4, 0), 24, 12));
+    scene.add(Circle::new(Point::new(6, 6), 5));
+    scene.add(Polygon::new(vec![
+        Point::new(12, 2),
+        Point::new(18, 2),
+        Point::new(15, 10),
+    ]));
+    scene
+}
+
+#[test]
+fn rectangles_ascii() {
+    check("rectangles.txt", &render::ascii(&can_hold_rects(), 40, 20));
+}
+
+#[test]
+fn overlapping_ascii() {
+    let rects = [
+        Rectangle::at(Point::new(0, 0), 20, 10),
+        Rectangle::at(Point::new(10, 4), 20, 10),
+        Rectangle::at(Point::new(-5, 8), 1, 1), // too small for a label
+    ];
+    check("overlapping.txt", &render::ascii(&rects, 60, 20));
+}
+
+#[test]
+fn rectangles_svg() {
+    check("rectangles.svg", &render::svg(&can_hold_rects(), 4));
+}
+
+#[test]
+fn scene_ascii() {
+    check("scene.txt", &render::scene_ascii(&scene(), 48, 14));
+}
+
+#[test]
+fn scene_svg() {
+    check("scene.svg", &render::scene_svg(&scene(), 10));
+}
+
+#[test]
+fn nothing_to_draw() {
+    assert_eq!(render::ascii(&[], 40, 20), "");
+    assert_eq!(render::scene_ascii(&Scene::new(), 40, 20), "");
+}
+
+// far enough apart that the distances between them only fit an i64
+fn extreme_rects() -> [Rectangle; 3] {
+    [
+        Rectangle::at(Point::new(i32::MIN, i32::MIN), 1 << 30, 1 << 30),
+        Rectangle::at(Point::new(0, 0), 1 << 30, 1 << 30),
+        Rectangle::at(Point::new(i32::MAX - (1 << 30), i32::MAX - 10), 1 << 30, 10),
+    ]
+}
+
+#[test]
+fn extremes_ascii() {
+    check("extremes.txt", &render::ascii(&extreme_rects(), 40, 20));
+}
+
+#[test]
+fn extremes_svg() {
+    check("extremes.svg", &render::svg(&extreme_rects(), 1));
+}
+
+#[test]
+fn extremes_scene() {
+    // the right half is past i32::MAX, where no point can go, so the ascii
+    // leaves it blank
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(i32::MAX - 50, 0), 100, 20));
+    scene.add(Circle::new(Point::new(i32::MAX - 20, 10), 8));
+    scene.add(Polygon::new(vec![
+        Point::new(i32::MAX - 8, 2),
+        Point::new(i32::MAX, 2),
+        Point::new(i32::MAX, 18),
+    ]));
+    check("extremes_scene.txt", &render::scene_ascii(&scene, 50, 10));
+    check("extremes_scene.svg", &render::scene_svg(&scene, 2));
+}
@@ -0,0 +1,166 @@
+// a Scene of mixed shapes, and each shape through the Shape trait the way a
+// Scene sees it. shapes come back as &dyn Shape, so to_svg() is how the
+// tests tell which one they got
+use ch5_structs::geometry::{Circle, Point, Polygon, Rectangle, Scene, Shape};
+use std::f64::consts::PI;
+
+fn triangle() -> Polygon {
+    Polygon::new(vec![
+        Point::new(10, 0),
+        Point::new(14, 0),
+        Point::new(12, 3),
+    ])
+}
+
+// the one from main.rs: a rectangle, a circle on it, a triangle on both
+fn mixed() -> Scene {
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(0, 0), 20, 10));
+    scene.add(Circle::new(Point::new(5, 5), 2));
+    scene.add(triangle());
+    scene
+}
+
+fn svgs(scene: &Scene) -> Vec<String> {
+    scene.iter().map(|shape| shape.to_svg()).collect()
+}
+
+#[test]
+fn shapes_through_the_trait() {
+    let rect = Rectangle::at(Point::new(1, 2), 3, 4);
+    assert_eq!(Shape::area(&rect), 12.0);
+    assert_eq!(Shape::perimeter(&rect), 14.0);
+    assert_eq!(Shape::bounding_box(&rect), rect);
+    assert!(Shape::contains(&rect, &Point::new(4, 6)));
+    assert!(!Shape::contains(&rect, &Point::new(5, 6)));
+    assert_eq!(rect.to_svg(), r#"<rect x="1" y="2" width="3" height="4"/>"#);
+
+    let circle = Circle::new(Point::new(5, 5), 2);
+    assert_eq!(Shape::area(&circle), 4.0 * PI);
+    assert_eq!(Shape::perimeter(&circle), 4.0 * PI);
+    assert_eq!(
+        Shape::bounding_box(&circle),
+        Rectangle::at(Point::new(3, 3), 4, 4)
+    );
+    assert!(Shape::contains(&circle, &Point::new(5, 7)));
+    assert!(!Shape::contains(&circle, &Point::new(6, 7)));
+    assert_eq!(circle.to_svg(), r#"<circle cx="5" cy="5" r="2"/>"#);
+
+    let triangle = triangle();
+    assert_eq!(Shape::area(&triangle), 6.0);
+    assert_eq!(
+        Shape::perimeter(&triangle),
+        4.0 + 13f64.sqrt() + 13f64.sqrt()
+    );
+    assert_eq!(
+        Shape::bounding_box(&triangle),
+        Rectangle::at(Point::new(10, 0), 4, 3)
+    );
+    assert!(Shape::contains(&triangle, &Point::new(12, 3)));
+    assert!(!Shape::contains(&triangle, &Point::new(10, 3)));
+    assert_eq!(triangle.to_svg(), r#"<polygon points="10,0 14,0 12,3"/>"#);
+}
+
+#[test]
+fn empty_scene() {
+    let mut scene = Scene::new();
+    assert!(scene.is_empty());
+    assert_eq!(scene.len(), 0);
+    assert_eq!(scene.total_area(), 0.0);
+    assert_eq!(scene.bounding_box(), None);
+    assert!(scene.shape_at(&Point::new(0, 0)).is_none());
+    scene.sort_by_area();
+    assert!(scene.iter().next().is_none());
+}
+
+#[test]
+fn topmost_shape_wins() {
+    let scene = mixed();
+    assert_eq!(scene.len(), 3);
+
+    // the circle is on the rectangle, the triangle on the rectangle too
+    let at = |x, y| {
+        scene
+            .shape_at(&Point::new(x, y))
+            .map(|shape| shape.to_svg())
+    };
+    let all = svgs(&scene);
+    let (rect, circle, triangle) = (Some(&all[0]), Some(&all[1]), Some(&all[2]));
+    assert_eq!(at(5, 5).as_ref(), circle);
+    assert_eq!(at(5, 7).as_ref(), circle);
+    assert_eq!(at(12, 1).as_ref(), triangle);
+    // edges count, the triangle's bottom edge is on top of the rectangle's
+    assert_eq!(at(10, 0).as_ref(), triangle);
+    assert_eq!(at(1, 1).as_ref(), rect);
+    assert_eq!(at(20, 10).as_ref(), rect);
+    assert_eq!(at(21, 10), None);
+    assert_eq!(at(-1, 0), None);
+
+    // two of the same shape, the one added last is on top
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(0, 0), 4, 4));
+    scene.add(Rectangle::at(Point::new(2, 2), 4, 4));
+    let hit = scene.shape_at(&Point::new(3, 3)).unwrap();
+    assert_eq!(hit.bounding_box().top_left(), Point::new(2, 2));
+    let hit = scene.shape_at(&Point::new(1, 1)).unwrap();
+    assert_eq!(hit.bounding_box().top_left(), Point::new(0, 0));
+}
+
+#[test]
+fn total_area_and_bounds() {
+    let scene = mixed();
+    // overlaps count once per shape
+    assert_eq!(scene.total_area(), 200.0 + 4.0 * PI + 6.0);
+    assert_eq!(
+        scene.bounding_box(),
+        Some(Rectangle::at(Point::new(0, 0), 20, 10))
+    );
+
+    let mut scene = Scene::new();
+    scene.add(Circle::new(Point::new(-10, -10), 5));
+    scene.add(triangle());
+    assert_eq!(
+        scene.bounding_box(),
+        Some(Rectangle::at(Point::new(-15, -15), 29, 18))
+    );
+    // a single point is a shape with no size
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(7, 8), 0, 0));
+    assert_eq!(scene.total_area(), 0.0);
+    assert_eq!(
+        scene.bounding_box(),
+        Some(Rectangle::at(Point::new(7, 8), 0, 0))
+    );
+}
+
+#[test]
+fn sort_by_area() {
+    let mut scene = mixed();
+    scene.sort_by_area();
+    let areas: Vec<f64> = scene.iter().map(|shape| shape.area()).collect();
+    assert_eq!(areas, vec![6.0, 4.0 * PI, 200.0]);
+    // sorting changes what's on top
+    assert_eq!(scene.shape_at(&Point::new(5, 5)).unwrap().area(), 200.0);
+
+    // same areas keep the order they were added in
+    let mut scene = Scene::new();
+    scene.add(Rectangle::at(Point::new(0, 0), 2, 3));
+    scene.add(Circle::new(Point::new(0, 0), 1));
+    scene.add(Rectangle::at(Point::new(9, 9), 3, 2));
+    scene.add(Polygon::new(vec![
+        Point::new(0, 0),
+        Point::new(3, 0),
+        Point::new(3, 2),
+        Point::new(0, 2),
+    ]));
+    scene.sort_by_area();
+    assert_eq!(
+        svgs(&scene),
+        vec![
+            r#"<circle cx="0" cy="0" r="1"/>"#,
+            r#"<rect x="0" y="0" width="2" height="3"/>"#,
+            r#"<rect x="9" y="9" width="3" height="2"/>"#,
+            r#"<polygon points="0,0 3,0 3,2 0,2"/>"#,
+        ]
+    );
+}
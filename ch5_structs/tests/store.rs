@@ -0,0 +1,216 @@
+// UserStore: the unique indexes, updates that can't leave it half done, and
+// saving and loading. every test that touches the disk gets its own file so
+// they can run at the same time
+use ch5_structs::store::{PersistError, StoreError, UserStore};
+use ch5_structs::user::{EmailError, User, UserError};
+use std::fs;
+use std::path::PathBuf;
+
+fn user(name: &str, email: &str) -> User {
+    User::new(name, email).unwrap()
+}
+
+fn two_users() -> UserStore {
+    let mut store = UserStore::new();
+    store
+        .insert(user("ferris", "ferris@rust-lang.org"))
+        .unwrap();
+    store.insert(user("corro", "corro@unsafe.rs")).unwrap();
+    store
+}
+
+// a file in the temp dir nobody else is using, removed when dropped
+struct TempFile(PathBuf);
+
+impl TempFile {
+    fn new(test: &str) -> TempFile {
+        TempFile(std::env::temp_dir().join(format!(
+            "ch5_structs_{}_{}.jsonl",
+            test,
+            std::process::id()
+        )))
+    }
+}
+
+impl Drop for TempFile {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+#[test]
+fn duplicate_usernames_are_rejected() {
+    let mut store = two_users();
+    assert_eq!(
+        store.insert(user("ferris", "other@rust-lang.org")),
+        Err(StoreError::DuplicateUsername(String::from("ferris")))
+    );
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn duplicate_emails_are_rejected_whatever_the_case() {
+    let mut store = two_users();
+    for email in &[
+        "ferris@rust-lang.org",
+        "Ferris@Rust-Lang.org",
+        "FERRIS@RUST-LANG.ORG",
+    ] {
+        assert_eq!(
+            store.insert(user("not_ferris", email)),
+            Err(StoreError::DuplicateEmail(email.to_string()))
+        );
+    }
+    assert_eq!(store.len(), 2);
+    assert!(store.get("not_ferris").is_none());
+
+    // lookups don't care about case either
+    let found = store.get_by_email("CORRO@unsafe.rs").unwrap();
+    assert_eq!(found.username(), "corro");
+}
+
+#[test]
+fn updates_keep_the_email_index_right() {
+    let mut store = two_users();
+    store
+        .update("ferris", |user| user.set_email("crab@rust-lang.org"))
+        .unwrap();
+    assert!(store.get_by_email("ferris@rust-lang.org").is_none());
+    assert_eq!(
+        store.get_by_email("crab@rust-lang.org").unwrap().username(),
+        "ferris"
+    );
+    // the old email is free again
+    store
+        .insert(user("ferris2", "ferris@rust-lang.org"))
+        .unwrap();
+
+    // taking someone else's email, in any case, doesn't go through
+    assert_eq!(
+        store
+            .update("corro", |user| user.set_email("Crab@Rust-Lang.org"))
+            .unwrap_err(),
+        StoreError::DuplicateEmail(String::from("Crab@Rust-Lang.org"))
+    );
+    assert_eq!(store.get("corro").unwrap().email(), "corro@unsafe.rs");
+
+    // and neither does a failed edit, even if it changed things first
+    let err = store
+        .update("corro", |user| {
+            user.sign_in()?;
+            user.set_email("nope")
+        })
+        .unwrap_err();
+    assert_eq!(
+        err,
+        StoreError::User(UserError::Email(EmailError::MissingAt))
+    );
+    assert_eq!(store.get("corro").unwrap().sign_in_count(), 0);
+
+    assert_eq!(
+        store.update("nobody", |_| Ok(())).unwrap_err(),
+        StoreError::NotFound(String::from("nobody"))
+    );
+}
+
+#[test]
+fn deactivate_and_remove() {
+    let mut store = two_users();
+    store.deactivate("corro").unwrap();
+    assert!(!store.get("corro").unwrap().is_active());
+
+    let removed = store.remove("corro").unwrap();
+    assert_eq!(removed.username(), "corro");
+    assert_eq!(store.len(), 1);
+    assert!(store.get_by_email("corro@unsafe.rs").is_none());
+    assert_eq!(
+        store.remove("corro"),
+        Err(StoreError::NotFound(String::from("corro")))
+    );
+
+    // both of corro's keys are free now
+    store.insert(user("corro", "corro@unsafe.rs")).unwrap();
+}
+
+#[test]
+fn save_and_load_round_trip() {
+    let mut store = two_users();
+    store
+        .update("corro", |user| user.sign_in().map(|_| ()))
+        .unwrap();
+    store.deactivate("corro").unwrap();
+
+    let file = TempFile::new("round_trip");
+    store.save(&file.0).unwrap();
+    let loaded = UserStore::load(&file.0).unwrap();
+
+    assert_eq!(loaded.len(), 2);
+    assert!(store.iter().eq(loaded.iter()));
+    let corro = loaded.get("corro").unwrap();
+    assert_eq!(corro.sign_in_count(), 1);
+    assert!(!corro.is_active());
+    assert_eq!(
+        loaded
+            .get_by_email("FERRIS@rust-lang.org")
+            .unwrap()
+            .username(),
+        "ferris"
+    );
+
+    // and saving what was loaded gives the same file back
+    let again = TempFile::new("round_trip_again");
+    loaded.save(&again.0).unwrap();
+    assert_eq!(
+        fs::read_to_string(&file.0).unwrap(),
+        fs::read_to_string(&again.0).unwrap()
+    );
+}
+
+#[test]
+fn empty_store_round_trips() {
+    let file = TempFile::new("empty");
+    UserStore::new().save(&file.0).unwrap();
+    assert!(UserStore::load(&file.0).unwrap().is_empty());
+}
+
+#[test]
+fn loading_bad_files() {
+    let load = |text: &str| UserStore::read_from(text.as_bytes());
+    let ferris =
+        r#"{"username":"ferris","email":"ferris@rust-lang.org","sign_in_count":0,"active":true}"#;
+
+    // blank lines are fine
+    assert_eq!(load(&format!("\n{}\n\n", ferris)).unwrap().len(), 1);
+
+    match load(&format!("{}\nnot json", ferris)) {
+        Err(PersistError::Json { line: 2, .. }) => {}
+        other => panic!("{:?}", other),
+    }
+
+    // someone else with ferris's email in another case
+    let shouty = ferris.replace("ferris@rust-lang.org", "FERRIS@rust-lang.org");
+    let shouty = shouty.replace("\"ferris\"", "\"shouty\"");
+    match load(&format!("{}\n{}", ferris, shouty)) {
+        Err(PersistError::Store {
+            line: 2,
+            source: StoreError::DuplicateEmail(email),
+        }) => assert_eq!(email, "FERRIS@rust-lang.org"),
+        other => panic!("{:?}", other),
+    }
+
+    // records go back through User's validation
+    let bad = ferris.replace("ferris@rust-lang.org", "ferris");
+    match load(&bad) {
+        Err(PersistError::Store {
+            line: 1,
+            source: StoreError::User(UserError::Email(EmailError::MissingAt)),
+        }) => {}
+        other => panic!("{:?}", other),
+    }
+
+    let missing = TempFile::new("missing");
+    match UserStore::load(&missing.0) {
+        Err(PersistError::Io(e)) => assert_eq!(e.kind(), std::io::ErrorKind::NotFound),
+        other => panic!("{:?}", other),
+    }
+}
@@ -0,0 +1,140 @@
+// User's validation, one test per way a username or email can be wrong, plus
+// the builder and with_email
+use ch5_structs::user::{
+    validate_email, validate_username, EmailError, User, UserError, UsernameError, MAX_USERNAME_LEN,
+};
+
+#[test]
+fn usernames() {
+    assert_eq!(validate_username("ferris_the-crab.2"), Ok(()));
+    assert_eq!(validate_username(&"a".repeat(MAX_USERNAME_LEN)), Ok(()));
+
+    assert_eq!(validate_username(""), Err(UsernameError::Empty));
+    assert_eq!(
+        validate_username(&"a".repeat(MAX_USERNAME_LEN + 1)),
+        Err(UsernameError::TooLong(MAX_USERNAME_LEN + 1))
+    );
+    assert_eq!(
+        validate_username("ferris crab"),
+        Err(UsernameError::InvalidChar(' '))
+    );
+    // not ascii, so not allowed, even though it's a letter
+    assert_eq!(
+        validate_username("fërris"),
+        Err(UsernameError::InvalidChar('ë'))
+    );
+}
+
+#[test]
+fn emails() {
+    assert_eq!(validate_email("ferris@rust-lang.org"), Ok(()));
+    assert_eq!(validate_email("a.b+c@mail.example.com"), Ok(()));
+
+    assert_eq!(
+        validate_email("ferris.rust-lang.org"),
+        Err(EmailError::MissingAt)
+    );
+    assert_eq!(validate_email("a@b@c.com"), Err(EmailError::MultipleAt));
+    assert_eq!(
+        validate_email("@rust-lang.org"),
+        Err(EmailError::EmptyLocalPart)
+    );
+    assert_eq!(
+        validate_email("ferris@localhost"),
+        Err(EmailError::InvalidDomain)
+    );
+    assert_eq!(
+        validate_email("ferris@b..com"),
+        Err(EmailError::InvalidDomain)
+    );
+    assert_eq!(
+        validate_email("ferris@.com"),
+        Err(EmailError::InvalidDomain)
+    );
+    assert_eq!(
+        validate_email("ferris@com."),
+        Err(EmailError::InvalidDomain)
+    );
+    assert_eq!(validate_email("fer ris@a.com"), Err(EmailError::Whitespace));
+    assert_eq!(
+        validate_email("ferris@a.com\n"),
+        Err(EmailError::Whitespace)
+    );
+}
+
+#[test]
+fn new_and_builder_validate() {
+    assert_eq!(
+        User::new("", "ferris@a.com"),
+        Err(UserError::Username(UsernameError::Empty))
+    );
+    assert_eq!(
+        User::new("ferris", "ferris"),
+        Err(UserError::Email(EmailError::MissingAt))
+    );
+
+    assert_eq!(
+        User::builder().email("ferris@a.com").build(),
+        Err(UserError::MissingUsername)
+    );
+    assert_eq!(
+        User::builder().username("ferris").build(),
+        Err(UserError::MissingEmail)
+    );
+
+    let user = User::builder()
+        .username("ferris")
+        .email("ferris@a.com")
+        .sign_in_count(7)
+        .active(false)
+        .build()
+        .unwrap();
+    assert_eq!(user.username(), "ferris");
+    assert_eq!(user.email(), "ferris@a.com");
+    assert_eq!(user.sign_in_count(), 7);
+    assert!(!user.is_active());
+}
+
+#[test]
+fn sign_in_counts_while_active() {
+    let mut user = User::new("ferris", "ferris@a.com").unwrap();
+    assert!(user.is_active());
+    assert_eq!(user.sign_in_count(), 0);
+    assert_eq!(user.sign_in(), Ok(1));
+    assert_eq!(user.sign_in(), Ok(2));
+
+    user.deactivate();
+    assert_eq!(user.sign_in(), Err(UserError::Inactive));
+    assert_eq!(user.sign_in_count(), 2);
+
+    user.activate();
+    assert_eq!(user.sign_in(), Ok(3));
+}
+
+#[test]
+fn with_email_leaves_the_original_alone() {
+    let mut user = User::new("ferris", "ferris@a.com").unwrap();
+    user.sign_in().unwrap();
+    user.deactivate();
+
+    let moved = user.with_email("crab@b.org").unwrap();
+    assert_eq!(moved.email(), "crab@b.org");
+    // everything else carried over
+    assert_eq!(moved.username(), "ferris");
+    assert_eq!(moved.sign_in_count(), 1);
+    assert!(!moved.is_active());
+
+    // and user is still usable, with its old email
+    assert_eq!(user.email(), "ferris@a.com");
+    assert_eq!(user.username(), "ferris");
+
+    assert_eq!(
+        user.with_email("crab"),
+        Err(UserError::Email(EmailError::MissingAt))
+    );
+    assert_eq!(
+        user.set_email("not an email"),
+        Err(UserError::Email(EmailError::Whitespace))
+    );
+    assert_eq!(user.email(), "ferris@a.com");
+}
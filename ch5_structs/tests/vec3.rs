@@ -0,0 +1,147 @@
+// the maths in src/vec3.rs actually running, the compile_fail tests only
+// cover what shouldn't build. mostly i32, where the answers are exact, plus
+// floats for the things only they can do
+use ch5_structs::vec3::{Point3, Vec3};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn v(x: i32, y: i32, z: i32) -> Vec3<i32> {
+    Vec3::new(x, y, z)
+}
+
+// small enough that a cross of crosses can't overflow
+fn random_vec(rng: &mut StdRng) -> Vec3<i32> {
+    v(
+        rng.gen_range(-100..=100),
+        rng.gen_range(-100..=100),
+        rng.gen_range(-100..=100),
+    )
+}
+
+#[test]
+fn add_and_sub() {
+    let (a, b) = (v(1, 2, 3), v(10, -20, 30));
+    assert_eq!(a + b, v(11, -18, 33));
+    assert_eq!(a - b, v(-9, 22, -27));
+    assert_eq!(-a, v(-1, -2, -3));
+    assert_eq!(a + Vec3::zero(), a);
+    assert_eq!(a - a, Vec3::zero());
+
+    let mut c = a;
+    c += b;
+    assert_eq!(c, a + b);
+    c -= b;
+    assert_eq!(c, a);
+
+    assert_eq!(vec![a, b, -a].into_iter().sum::<Vec3<i32>>(), b);
+    assert_eq!(
+        Vec::<Vec3<i32>>::new().into_iter().sum::<Vec3<i32>>(),
+        Vec3::zero()
+    );
+}
+
+#[test]
+fn scaling() {
+    let a = v(1, -2, 3);
+    assert_eq!(a * 3, v(3, -6, 9));
+    assert_eq!(3 * a, a * 3);
+    let mut b = a;
+    b *= -1;
+    assert_eq!(b, -a);
+    assert_eq!(Vec3::new(3.0, -6.0, 1.5) / 3.0, Vec3::new(1.0, -2.0, 0.5));
+}
+
+#[test]
+fn dot() {
+    assert_eq!(v(1, 2, 3).dot(v(4, -5, 6)), 4 - 10 + 18);
+    // at right angles
+    assert_eq!(v(1, 0, 0).dot(v(0, 1, 0)), 0);
+    assert_eq!(v(3, 4, 12).length_squared(), 169);
+    assert_eq!(v(3, 4, 12).length(), 13.0);
+}
+
+#[test]
+fn cross() {
+    let (x, y, z) = (v(1, 0, 0), v(0, 1, 0), v(0, 0, 1));
+    // right handed
+    assert_eq!(x.cross(y), z);
+    assert_eq!(y.cross(z), x);
+    assert_eq!(z.cross(x), y);
+    assert_eq!(y.cross(x), -z);
+    assert_eq!(x.cross(x), Vec3::zero());
+    // the parallelogram of a 2x3 rectangle
+    assert_eq!(v(2, 0, 0).cross(v(0, 3, 0)).length(), 6.0);
+}
+
+#[test]
+fn cross_and_dot_properties() {
+    let mut rng = StdRng::seed_from_u64(40);
+    for _ in 0..1000 {
+        let (a, b, c) = (
+            random_vec(&mut rng),
+            random_vec(&mut rng),
+            random_vec(&mut rng),
+        );
+        let ab = a.cross(b);
+        // at right angles to both of them
+        assert_eq!(ab.dot(a), 0, "{} x {}", a, b);
+        assert_eq!(ab.dot(b), 0, "{} x {}", a, b);
+        assert_eq!(b.cross(a), -ab);
+        // |a x b|^2 + (a . b)^2 = |a|^2 |b|^2
+        assert_eq!(
+            i64::from(ab.length_squared()) + i64::from(a.dot(b)).pow(2),
+            i64::from(a.length_squared()) * i64::from(b.length_squared())
+        );
+        // both spread over addition
+        assert_eq!(a.dot(b + c), a.dot(b) + a.dot(c));
+        assert_eq!(a.cross(b + c), ab + a.cross(c));
+        // a . (b x c) is the same turned round
+        assert_eq!(a.dot(b.cross(c)), b.dot(c.cross(a)));
+    }
+}
+
+#[test]
+fn normalize() {
+    let unit = Vec3::new(3.0, 0.0, -4.0).normalize().unwrap();
+    assert_eq!(unit, Vec3::new(0.6, 0.0, -0.8));
+    assert!((unit.length() - 1.0).abs() < 1e-12);
+
+    let mut rng = StdRng::seed_from_u64(4040);
+    for _ in 0..1000 {
+        let a = random_vec(&mut rng).to_f64();
+        if let Some(unit) = a.normalize() {
+            assert!((unit.length() - 1.0).abs() < 1e-12, "{}", a);
+            // same direction
+            assert!((unit.dot(a) - a.length()).abs() < 1e-9, "{}", a);
+        }
+    }
+
+    // the zero vector has no direction, in any float type
+    assert_eq!(Vec3::<f64>::zero().normalize(), None);
+    assert_eq!(Vec3::<f32>::zero().normalize(), None);
+    assert_eq!(Vec3::new(-0.0, 0.0, -0.0).normalize(), None);
+    assert_eq!(Vec3::new(0.0f32, 1e-30, 0.0).normalize(), None);
+}
+
+#[test]
+fn points_and_vectors() {
+    let (p, q) = (Point3::new(1, 2, 3), Point3::new(4, 6, 3));
+    assert_eq!(q - p, v(3, 4, 0));
+    assert_eq!(p + (q - p), q);
+    assert_eq!(q - (q - p), p);
+    assert_eq!(p.distance_squared(q), 25);
+    assert_eq!(p.distance(q), 5.0);
+    assert_eq!(Point3::origin() + p.to_vec(), p);
+
+    let mut r = p;
+    r += v(1, 1, 1);
+    assert_eq!(r, Point3::from([2, 3, 4]));
+    r -= v(1, 1, 1);
+    assert_eq!(r, p);
+}
+
+#[test]
+fn display() {
+    assert_eq!(v(1, -2, 3).to_string(), "<1, -2, 3>");
+    assert_eq!(Point3::new(1.5, 0.0, -2.0).to_string(), "(1.5, 0, -2)");
+}
@@ -0,0 +1,128 @@
+// how the trie holds up with 100k rules, against the obvious approach of
+// checking every rule and keeping the longest match. run with
+//
+//     cargo bench --bench acl
+//
+// no benchmarking crate, just Instant, so treat the numbers as rough
+use ch6_enum_patternmatch::acl::{Acl, Action, Rule};
+use ch6_enum_patternmatch::ip::{Cidr, IpAddr, IpAddrKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::hint::black_box;
+use std::time::{Duration, Instant};
+
+const RULES: usize = 100_000;
+const LOOKUPS: usize = 1_000_000;
+// the linear scan is slow enough that it only gets a sample
+const LINEAR_LOOKUPS: usize = 1_000;
+
+fn random_addr(rng: &mut StdRng, kind: IpAddrKind) -> IpAddr {
+    IpAddr::from_bits(kind, rng.gen())
+}
+
+fn random_kind(rng: &mut StdRng) -> IpAddrKind {
+    if rng.gen_bool(0.5) {
+        IpAddrKind::V4
+    } else {
+        IpAddrKind::V6
+    }
+}
+
+// prefixes spread like a real table: mostly long, some short
+fn random_rule(rng: &mut StdRng) -> Rule {
+    let kind = random_kind(rng);
+    let prefix = match kind {
+        IpAddrKind::V4 => rng.gen_range(8..=32),
+        IpAddrKind::V6 => rng.gen_range(16..=64),
+    };
+    let action = if rng.gen_bool(0.5) {
+        Action::Allow
+    } else {
+        Action::Deny
+    };
+    Rule {
+        action,
+        cidr: Cidr::new(random_addr(rng, kind), prefix).unwrap(),
+    }
+}
+
+// random addresses would almost never hit a long prefix, so most lookups
+// are for somewhere inside a rule's network
+fn random_lookup(rng: &mut StdRng, rules: &[Rule]) -> IpAddr {
+    if rng.gen_bool(0.8) {
+        let cidr = rules[rng.gen_range(0..rules.len())].cidr;
+        let host = random_addr(rng, cidr.kind()).to_bits() & !cidr.netmask().to_bits();
+        IpAddr::from_bits(cidr.kind(), cidr.network().to_bits() | host)
+    } else {
+        let kind = random_kind(rng);
+        random_addr(rng, kind)
+    }
+}
+
+fn linear(rules: &[Rule], default: Action, addr: &IpAddr) -> Action {
+    rules
+        .iter()
+        .filter(|rule| rule.cidr.contains(addr))
+        .max_by_key(|rule| rule.cidr.prefix())
+        .map_or(default, |rule| rule.action)
+}
+
+fn per_lookup(total: Duration, n: usize) -> String {
+    format!("{:.1?}", total / n as u32)
+}
+
+fn main() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut rules: Vec<Rule> = (0..RULES).map(|_| random_rule(&mut rng)).collect();
+    // random networks can repeat at short prefixes, keep the first of each
+    let mut seen = std::collections::HashSet::new();
+    rules.retain(|rule| seen.insert(rule.cidr));
+
+    let start = Instant::now();
+    let mut acl = Acl::new(Action::Deny);
+    for rule in &rules {
+        acl.insert(*rule);
+    }
+    let built = start.elapsed();
+    assert_eq!(acl.len(), rules.len());
+    println!("built {} rules in {:.1?}", acl.len(), built);
+
+    let addrs: Vec<IpAddr> = (0..LOOKUPS)
+        .map(|_| random_lookup(&mut rng, &rules))
+        .collect();
+
+    let start = Instant::now();
+    let mut allowed = 0;
+    for addr in &addrs {
+        if acl.is_allowed(black_box(addr)) {
+            allowed += 1;
+        }
+    }
+    let trie = start.elapsed();
+    println!(
+        "trie:   {} lookups in {:.1?}, {} each ({} allowed)",
+        LOOKUPS,
+        trie,
+        per_lookup(trie, LOOKUPS),
+        allowed
+    );
+
+    let sample = &addrs[..LINEAR_LOOKUPS];
+    let start = Instant::now();
+    let expected: Vec<Action> = sample
+        .iter()
+        .map(|addr| linear(&rules, Action::Deny, black_box(addr)))
+        .collect();
+    let scan = start.elapsed();
+    println!(
+        "linear: {} lookups in {:.1?}, {} each",
+        LINEAR_LOOKUPS,
+        scan,
+        per_lookup(scan, LINEAR_LOOKUPS)
+    );
+
+    // the trie had better agree with the slow way
+    for (addr, want) in sample.iter().zip(expected) {
+        assert_eq!(acl.check(addr), want, "{}", addr);
+    }
+}
@@ -0,0 +1,310 @@
+// allow and deny rules for networks, like a firewall's. the most specific
+// rule that covers an address wins, so "deny 10.0.0.0/8" with "allow
+// 10.1.0.0/16" lets 10.1.2.3 in and keeps 10.2.3.4 out. anything no rule
+// covers gets the default
+
+// rules live in a radix trie per IP version (see acl/trie.rs), so a lookup
+// costs one walk down at most 128 bits however many rules there are
+
+// the rule file is one rule per line, # starts a comment:
+//
+//     default deny
+//     allow 127.0.0.0/8
+//     allow ::1        # a bare address is just that one address
+//     deny 10.0.0.0/8
+//     allow 10.1.0.0/16
+use crate::ip::{Cidr, CidrError, IpAddr, IpAddrKind};
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+mod trie;
+
+use self::trie::Trie;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Action {
+    Allow,
+    Deny,
+}
+
+/// One line of a rule file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rule {
+    pub action: Action,
+    pub cidr: Cidr,
+}
+
+#[derive(Debug)]
+pub struct Acl {
+    default: Action,
+    v4: Trie<Rule>,
+    v6: Trie<Rule>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RuleError {
+    /// The first word wasn't allow, deny or default.
+    UnknownAction(String),
+    /// allow or deny with no network after it.
+    MissingCidr,
+    Cidr(CidrError),
+    /// More words after the rule.
+    Trailing(String),
+    /// The same network twice. Which one should win?
+    Duplicate {
+        cidr: Cidr,
+        first_line: usize,
+    },
+    /// A second default line.
+    DuplicateDefault {
+        first_line: usize,
+    },
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    // line numbers start at 1, like an editor would show
+    Rule { line: usize, source: RuleError },
+}
+
+impl Acl {
+    /// No rules yet, everything gets `default`.
+    pub fn new(default: Action) -> Acl {
+        Acl {
+            default,
+            v4: Trie::new(),
+            v6: Trie::new(),
+        }
+    }
+
+    pub fn default_action(&self) -> Action {
+        self.default
+    }
+
+    /// How many rules, V4 and V6 together.
+    pub fn len(&self) -> usize {
+        self.v4.len() + self.v6.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Adds a rule, replacing (and handing back) any rule for the same
+    /// network.
+    pub fn insert(&mut self, rule: Rule) -> Option<Rule> {
+        let (key, len) = key(&rule.cidr.network(), rule.cidr.prefix());
+        self.trie_mut(rule.cidr.kind()).insert(key, len, rule)
+    }
+
+    /// The rule for exactly this network, if there is one.
+    pub fn get(&self, cidr: &Cidr) -> Option<&Rule> {
+        let (key, len) = key(&cidr.network(), cidr.prefix());
+        self.trie(cidr.kind()).get(key, len)
+    }
+
+    /// The most specific rule covering `addr`, or `None` if it's down to the
+    /// default.
+    pub fn matching_rule(&self, addr: &IpAddr) -> Option<&Rule> {
+        let (key, _) = key(addr, 0);
+        self.trie(addr.kind()).longest_match(key)
+    }
+
+    pub fn check(&self, addr: &IpAddr) -> Action {
+        self.matching_rule(addr)
+            .map_or(self.default, |rule| rule.action)
+    }
+
+    pub fn is_allowed(&self, addr: &IpAddr) -> bool {
+        self.check(addr) == Action::Allow
+    }
+
+    fn trie(&self, kind: IpAddrKind) -> &Trie<Rule> {
+        match kind {
+            IpAddrKind::V4 => &self.v4,
+            IpAddrKind::V6 => &self.v6,
+        }
+    }
+
+    fn trie_mut(&mut self, kind: IpAddrKind) -> &mut Trie<Rule> {
+        match kind {
+            IpAddrKind::V4 => &mut self.v4,
+            IpAddrKind::V6 => &mut self.v6,
+        }
+    }
+
+    /// Reads rules in the format at the top of src/acl.rs. With no default
+    /// line, the default is deny.
+    pub fn read_from<R: BufRead>(input: R) -> Result<Acl, LoadError> {
+        let mut acl = Acl::new(Action::Deny);
+        let mut default_line = None;
+        // which line each rule came from, for the duplicate error. the trie
+        // only keeps the rule itself
+        let mut lines = std::collections::HashMap::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            let number = i + 1;
+            let err = |source| LoadError::Rule {
+                line: number,
+                source,
+            };
+
+            match parse_line(&line).map_err(err)? {
+                Line::Blank => {}
+                Line::Default(action) => {
+                    if let Some(first_line) = default_line {
+                        return Err(err(RuleError::DuplicateDefault { first_line }));
+                    }
+                    default_line = Some(number);
+                    acl.default = action;
+                }
+                Line::Rule(rule) => {
+                    if let Some(&first_line) = lines.get(&rule.cidr) {
+                        return Err(err(RuleError::Duplicate {
+                            cidr: rule.cidr,
+                            first_line,
+                        }));
+                    }
+                    lines.insert(rule.cidr, number);
+                    acl.insert(rule);
+                }
+            }
+        }
+        Ok(acl)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Acl, LoadError> {
+        Acl::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+// where a network goes in the trie: its bits, moved to the top of the u128
+// for V4, and how many of them count
+fn key(addr: &IpAddr, prefix: u8) -> (u128, u8) {
+    let shift = 128 - u32::from(addr.kind().bits());
+    (addr.to_bits() << shift, prefix)
+}
+
+enum Line {
+    Blank,
+    Default(Action),
+    Rule(Rule),
+}
+
+fn parse_line(line: &str) -> Result<Line, RuleError> {
+    let line = line.split('#').next().unwrap_or("");
+    let mut words = line.split_whitespace();
+    let first = match words.next() {
+        Some(first) => first,
+        None => return Ok(Line::Blank),
+    };
+
+    let parsed = if first == "default" {
+        let action = words.next().ok_or(RuleError::MissingCidr)?;
+        Line::Default(action.parse()?)
+    } else {
+        let action: Action = first.parse()?;
+        let target = words.next().ok_or(RuleError::MissingCidr)?;
+        // a bare address means just that address
+        let cidr = if target.contains('/') {
+            target.parse().map_err(RuleError::Cidr)?
+        } else {
+            let addr = target
+                .parse()
+                .map_err(|e| RuleError::Cidr(CidrError::Addr(e)))?;
+            Cidr::host(addr)
+        };
+        Line::Rule(Rule { action, cidr })
+    };
+
+    match words.next() {
+        Some(extra) => Err(RuleError::Trailing(extra.to_string())),
+        None => Ok(parsed),
+    }
+}
+
+impl FromStr for Action {
+    type Err = RuleError;
+
+    fn from_str(s: &str) -> Result<Action, RuleError> {
+        match s {
+            "allow" => Ok(Action::Allow),
+            "deny" => Ok(Action::Deny),
+            _ => Err(RuleError::UnknownAction(s.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Action::Allow => write!(f, "allow"),
+            Action::Deny => write!(f, "deny"),
+        }
+    }
+}
+
+// the same format read_from takes
+impl fmt::Display for Rule {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} {}", self.action, self.cidr)
+    }
+}
+
+impl fmt::Display for RuleError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RuleError::UnknownAction(word) => {
+                write!(f, "expected allow, deny or default, not {:?}", word)
+            }
+            RuleError::MissingCidr => write!(f, "missing the network or action"),
+            RuleError::Cidr(e) => e.fmt(f),
+            RuleError::Trailing(word) => write!(f, "unexpected {:?} after the rule", word),
+            RuleError::Duplicate { cidr, first_line } => {
+                write!(f, "{} already has a rule on line {}", cidr, first_line)
+            }
+            RuleError::DuplicateDefault { first_line } => {
+                write!(f, "the default was already set on line {}", first_line)
+            }
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => e.fmt(f),
+            LoadError::Rule { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl Error for RuleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            RuleError::Cidr(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Rule { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
@@ -0,0 +1,132 @@
+// a path compressed binary trie (a radix tree) keyed by bit prefixes. each
+// node is a prefix, and its children carry on with a 0 or a 1 after it. runs
+// of nodes with only one child get squashed into one, so depth is bounded by
+// the number of stored prefixes that branch, not by the 128 bits
+
+// keys are left aligned in a u128, so bit 0 is the top bit. V4 addresses get
+// shifted up by 96 so both versions work the same way
+#[derive(Debug)]
+pub(super) struct Trie<T> {
+    root: Node<T>,
+    len: usize,
+}
+
+#[derive(Debug)]
+struct Node<T> {
+    key: u128, // only the first len bits mean anything, the rest are 0
+    len: u8,
+    value: Option<T>,
+    children: [Option<Box<Node<T>>>; 2],
+}
+
+impl<T> Trie<T> {
+    pub(super) fn new() -> Trie<T> {
+        Trie {
+            root: Node::new(0, 0, None),
+            len: 0,
+        }
+    }
+
+    pub(super) fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Stores `value` for the first `len` bits of `key`, handing back whatever
+    /// was there before.
+    pub(super) fn insert(&mut self, key: u128, len: u8, value: T) -> Option<T> {
+        let key = key & mask(len);
+        let mut node = &mut self.root;
+        loop {
+            if node.len == len {
+                let old = node.value.replace(value);
+                if old.is_none() {
+                    self.len += 1;
+                }
+                return old;
+            }
+
+            let slot = &mut node.children[bit(key, node.len)];
+            let child = match slot {
+                Some(child) => child,
+                None => {
+                    *slot = Some(Box::new(Node::new(key, len, Some(value))));
+                    self.len += 1;
+                    return None;
+                }
+            };
+
+            let common = common_prefix(child.key, key).min(child.len).min(len);
+            if common < child.len {
+                // the new key leaves the child's path part way along, so put a
+                // node at the fork with the old child under it
+                let mut fork = Box::new(Node::new(key & mask(common), common, None));
+                let old = slot.take().expect("matched Some above");
+                let side = bit(old.key, common);
+                fork.children[side] = Some(old);
+                *slot = Some(fork);
+            }
+            node = slot.as_mut().expect("just filled");
+        }
+    }
+
+    /// The value for exactly this prefix.
+    pub(super) fn get(&self, key: u128, len: u8) -> Option<&T> {
+        let key = key & mask(len);
+        let mut node = &self.root;
+        loop {
+            if node.len == len {
+                return node.value.as_ref().filter(|_| node.key == key);
+            }
+            node = node.children[bit(key, node.len)].as_deref()?;
+            if node.len > len || node.key != key & mask(node.len) {
+                return None;
+            }
+        }
+    }
+
+    /// The value for the longest stored prefix of `key`.
+    pub(super) fn longest_match(&self, key: u128) -> Option<&T> {
+        let mut best = None;
+        let mut node = &self.root;
+        loop {
+            if node.key != key & mask(node.len) {
+                return best;
+            }
+            if node.value.is_some() {
+                best = node.value.as_ref();
+            }
+            if node.len == 128 {
+                return best;
+            }
+            node = match node.children[bit(key, node.len)].as_deref() {
+                Some(child) => child,
+                None => return best,
+            };
+        }
+    }
+}
+
+impl<T> Node<T> {
+    fn new(key: u128, len: u8, value: Option<T>) -> Node<T> {
+        Node {
+            key,
+            len,
+            value,
+            children: [None, None],
+        }
+    }
+}
+
+// the first len bits set
+fn mask(len: u8) -> u128 {
+    u128::MAX.checked_shl(128 - u32::from(len)).unwrap_or(0)
+}
+
+// bit i counting from the top, as an index into children
+fn bit(key: u128, i: u8) -> usize {
+    ((key >> (127 - u32::from(i))) & 1) as usize
+}
+
+fn common_prefix(a: u128, b: u128) -> u8 {
+    (a ^ b).leading_zeros() as u8
+}
@@ -0,0 +1,84 @@
+// a REPL for the expression language in src/expr.rs. cargo run --bin calc,
+// then try:
+//
+//     let r = 7
+//     if r * r > 40 then r * 3 else -1
+//     :fold let two = 2 in two * 3 + r
+//
+// lines come from stdin, so a script can be piped in too
+use ch6_enum_patternmatch::expr::{Env, Expr, Statement};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+type an expression to see its value, or:
+  let <name> = <expr>   keep a value around for later lines
+  :fold <expr>          the expression with its constant parts worked out
+  :ast <expr>           the syntax tree it parses to
+  :vars                 everything let so far
+  :help
+  :quit";
+
+fn main() {
+    let mut env = Env::new();
+    println!("{}\n", HELP);
+
+    let stdin = io::stdin();
+    loop {
+        print!("> ");
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .expect("Failed to read line")
+            == 0
+        {
+            break; // end of input
+        }
+        let line = line.trim();
+        let (command, rest) = match line.find(' ') {
+            Some(space) => (&line[..space], line[space + 1..].trim()),
+            None => (line, ""),
+        };
+
+        match command {
+            "" => {}
+            ":quit" | ":q" => break,
+            ":help" => println!("{}", HELP),
+            ":vars" => {
+                if env.is_empty() {
+                    println!("nothing yet");
+                }
+                for (name, value) in env.iter() {
+                    println!("{} = {}", name, value);
+                }
+            }
+            ":fold" => match rest.parse::<Expr>() {
+                Ok(expr) => println!("{}", expr.fold()),
+                Err(e) => println!("parse error: {}", e),
+            },
+            ":ast" => match rest.parse::<Expr>() {
+                Ok(expr) => println!("{:#?}", expr),
+                Err(e) => println!("parse error: {}", e),
+            },
+            _ if command.starts_with(':') => {
+                println!("don't know {:?}, try :help", command)
+            }
+            _ => match line.parse::<Statement>() {
+                Ok(Statement::Let(name, expr)) => match expr.eval(&env) {
+                    Ok(value) => {
+                        env.set(&name, value);
+                        println!("{} = {}", name, value);
+                    }
+                    Err(e) => println!("error: {}", e),
+                },
+                Ok(Statement::Expr(expr)) => match expr.eval(&env) {
+                    Ok(value) => println!("{}", value),
+                    Err(e) => println!("error: {}", e),
+                },
+                Err(e) => println!("parse error: {}", e),
+            },
+        }
+    }
+}
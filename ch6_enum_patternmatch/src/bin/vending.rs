@@ -0,0 +1,130 @@
+// a vending machine at the terminal, see src/vending.rs for the machine
+// itself. cargo run --bin vending, then try:
+//
+//     insert quarter
+//     insert dollar
+//     select A1
+//
+// commands come one per line from stdin, so a script can be piped in too
+use ch6_enum_patternmatch::money::{format_cents, Coin};
+use ch6_enum_patternmatch::vending::{Machine, Output};
+use std::io::{self, BufRead, Write};
+
+const HELP: &str = "\
+commands:
+  insert <coin>   nickel, dime, quarter or dollar (pennies and halves bounce)
+  select <code>   buy what's in that slot
+  cancel          get your money back
+  menu            what's for sale
+  status          the machine's state and coin box
+  quit";
+
+fn stocked() -> Machine {
+    let mut machine = Machine::new();
+    machine.stock("A1", "crisps", 125, 5);
+    machine.stock("A2", "pretzels", 100, 5);
+    machine.stock("B1", "chocolate bar", 165, 3);
+    machine.stock("B2", "gum", 35, 10);
+    machine.stock("C1", "water", 90, 1);
+    for coin in &[Coin::Nickel, Coin::Dime, Coin::Quarter] {
+        machine.load_coins(*coin, 4);
+    }
+    machine
+}
+
+fn menu(machine: &Machine) {
+    for (code, slot) in machine.slots() {
+        let left = if slot.count == 0 {
+            String::from("sold out")
+        } else {
+            format!("{} left", slot.count)
+        };
+        println!(
+            "  {}  {:<14} {:>6}  ({})",
+            code,
+            slot.name,
+            format_cents(slot.price),
+            left
+        );
+    }
+}
+
+fn main() {
+    let mut machine = stocked();
+    println!("{}\n", HELP);
+    menu(&machine);
+
+    let stdin = io::stdin();
+    loop {
+        print!("[{}] > ", machine.state());
+        io::stdout().flush().expect("Failed to flush stdout");
+
+        let mut line = String::new();
+        if stdin
+            .lock()
+            .read_line(&mut line)
+            .expect("Failed to read line")
+            == 0
+        {
+            break; // end of input
+        }
+        let line = line.trim();
+        let (command, arg) = match line.find(' ') {
+            Some(space) => (&line[..space], line[space + 1..].trim()),
+            None => (line, ""),
+        };
+
+        let result = match command {
+            "" => continue,
+            "insert" => match arg.parse::<Coin>() {
+                Ok(coin) => machine.insert(coin),
+                Err(e) => {
+                    println!("{}", e);
+                    continue;
+                }
+            },
+            "select" => machine.select(arg),
+            "cancel" => machine.cancel(),
+            "menu" => {
+                menu(&machine);
+                continue;
+            }
+            "status" => {
+                println!("{}, coin box {}", machine.state(), machine.coin_box());
+                continue;
+            }
+            "help" => {
+                println!("{}", HELP);
+                continue;
+            }
+            "quit" => break,
+            _ => {
+                println!("don't know {:?}, try help", command);
+                continue;
+            }
+        };
+
+        match result {
+            Ok(outputs) => {
+                for output in outputs {
+                    match output {
+                        Output::Change(_) => {
+                            let coins: Vec<String> =
+                                output.coins().iter().map(|c| c.to_string()).collect();
+                            println!("{} ({})", output, coins.join(", "));
+                        }
+                        Output::Rejected(_) | Output::Dispensed(_) => println!("{}", output),
+                    }
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+    }
+
+    // anything left in when the customer walks off goes back to them
+    if let Ok(outputs) = machine.cancel() {
+        for output in outputs {
+            println!("{}", output);
+        }
+    }
+}
@@ -0,0 +1,183 @@
+// Messages from src/message.rs sent between threads. any number of threads
+// publish, any number subscribe, and each subscriber says which kinds of
+// message it wants. under the hood it's one mpsc channel per subscriber
+//
+// publishing holds a lock while it hands the message to every subscriber,
+// so all of them see messages in the same order: the order the publishes
+// happened in. one thread's messages always arrive in the order it sent them
+//
+// a Quit goes to every subscriber whatever it asked for, ends their
+// subscriptions (so a `for message in subscription` loop just finishes) and
+// closes the bus, so later publishes fail
+use crate::message::{Message, MessageKind};
+use std::error::Error;
+use std::fmt;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// A handle on the bus. Clone it to publish or subscribe from another
+/// thread, they all share the one bus.
+#[derive(Debug, Clone, Default)]
+pub struct Bus {
+    shared: Arc<Mutex<Shared>>,
+}
+
+#[derive(Debug, Default)]
+struct Shared {
+    subscribers: Vec<(Filter, Sender<Message>)>,
+    closed: bool,
+}
+
+/// Which kinds of message a subscriber wants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Filter {
+    bits: u8, // one per MessageKind
+}
+
+/// The receiving end, from [`Bus::subscribe`]. Iterating blocks waiting for
+/// the next message, and stops at a Quit or once every [`Bus`] handle has
+/// been dropped.
+#[derive(Debug)]
+pub struct Subscription {
+    receiver: Receiver<Message>,
+    filter: Filter,
+    done: bool,
+}
+
+/// From publishing on a bus that's had a Quit. Hands the message back.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Closed(pub Message);
+
+impl Bus {
+    pub fn new() -> Bus {
+        Bus::default()
+    }
+
+    /// Sends `message` to everyone subscribed to its kind. A Quit goes to
+    /// everyone and closes the bus.
+    pub fn publish(&self, message: Message) -> Result<(), Closed> {
+        let mut shared = self.shared.lock().unwrap();
+        if shared.closed {
+            return Err(Closed(message));
+        }
+        let quit = message.kind() == MessageKind::Quit;
+        // a failed send means the subscription got dropped, so forget it
+        shared.subscribers.retain(|(filter, sender)| {
+            if !quit && !filter.matches(&message) {
+                return true;
+            }
+            sender.send(message.clone()).is_ok()
+        });
+        if quit {
+            shared.closed = true;
+            // nothing else can be sent now, so let the senders go
+            shared.subscribers.clear();
+        }
+        Ok(())
+    }
+
+    /// Everything published from now on that `filter` lets through.
+    /// Subscribing to a closed bus gets a subscription that's already over.
+    pub fn subscribe(&self, filter: Filter) -> Subscription {
+        let (sender, receiver) = mpsc::channel();
+        let mut shared = self.shared.lock().unwrap();
+        if !shared.closed {
+            shared.subscribers.push((filter, sender));
+        }
+        Subscription {
+            receiver,
+            filter,
+            done: shared.closed,
+        }
+    }
+
+    /// Whether a Quit has been published.
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().unwrap().closed
+    }
+
+    /// How many subscriptions are still listening, as far as the bus knows.
+    /// Dropped ones are noticed on the next publish to them.
+    pub fn subscribers(&self) -> usize {
+        self.shared.lock().unwrap().subscribers.len()
+    }
+}
+
+impl Filter {
+    /// Every kind of message.
+    pub const ALL: Filter = Filter { bits: 0b1111 };
+    /// Nothing but the Quit everyone gets.
+    pub const NONE: Filter = Filter { bits: 0 };
+
+    pub fn only(kind: MessageKind) -> Filter {
+        Filter::NONE.and(kind)
+    }
+
+    /// This filter, letting `kind` through too.
+    pub fn and(self, kind: MessageKind) -> Filter {
+        Filter {
+            bits: self.bits | bit(kind),
+        }
+    }
+
+    pub fn allows(&self, kind: MessageKind) -> bool {
+        self.bits & bit(kind) != 0
+    }
+
+    pub fn matches(&self, message: &Message) -> bool {
+        self.allows(message.kind())
+    }
+}
+
+fn bit(kind: MessageKind) -> u8 {
+    1 << kind as u8
+}
+
+impl From<MessageKind> for Filter {
+    fn from(kind: MessageKind) -> Filter {
+        Filter::only(kind)
+    }
+}
+
+impl Subscription {
+    pub fn filter(&self) -> Filter {
+        self.filter
+    }
+
+    /// Whether this subscription is over, from a Quit or the bus going
+    /// away.
+    pub fn is_done(&self) -> bool {
+        self.done
+    }
+
+    /// Waits for the next message. `None` after a Quit or once the bus is
+    /// gone.
+    pub fn recv(&mut self) -> Option<Message> {
+        if self.done {
+            return None;
+        }
+        match self.receiver.recv() {
+            Ok(Message::Quit) | Err(_) => {
+                self.done = true;
+                None
+            }
+            Ok(message) => Some(message),
+        }
+    }
+}
+
+impl Iterator for Subscription {
+    type Item = Message;
+
+    fn next(&mut self) -> Option<Message> {
+        self.recv()
+    }
+}
+
+impl fmt::Display for Closed {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "the bus has quit, can't publish {:?}", self.0)
+    }
+}
+
+impl Error for Closed {}
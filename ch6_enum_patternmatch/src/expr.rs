@@ -0,0 +1,249 @@
+// a little expression language, to have match do something bigger than
+// tell coins apart. it has whole numbers and booleans, variables, if and
+// let, and the usual operators:
+//
+//     let r = 7 in if r * r > 40 && !(r % 2 == 0) then r * 3 else -1
+//
+// from most to least tightly binding:
+//
+//     -x  !x                  unary
+//     *  /  %                 left to right
+//     +  -                    left to right
+//     ==  !=  <  <=  >  >=    only one per expression, a < b < c is an error
+//     &&                      only looks right if the left is true
+//     ||                      only looks right if the left is false
+//     if c then a else b      these two reach as far right as they can
+//     let x = v in body
+//
+// the pieces are in expr/: parse.rs turns text into an Expr, eval.rs works
+// out its Value, and fold.rs works out what it can ahead of time. each of
+// them is one big match over the variants below, so adding a variant means
+// the compiler points at every place that needs to know about it
+//
+// src/bin/calc.rs is a REPL for it
+use std::fmt;
+
+mod eval;
+mod fold;
+mod parse;
+
+pub use self::eval::{Env, EvalError};
+pub use self::parse::{ParseError, Statement, MAX_DEPTH};
+
+/// What an expression works out to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Value {
+    Int(i64),
+    Bool(bool),
+}
+
+/// The kinds of [`Value`], for saying what was wanted and what was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Type {
+    Int,
+    Bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum UnOp {
+    /// `-`, on an Int.
+    Neg,
+    /// `!`, on a Bool.
+    Not,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    /// Rounds towards zero, like Rust's `/`.
+    Div,
+    /// Takes the sign of the left side, like Rust's `%`.
+    Rem,
+    /// On two Ints or two Bools.
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    And,
+    Or,
+}
+
+/// The syntax tree.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Expr {
+    Lit(Value),
+    Var(String),
+    Unary(UnOp, Box<Expr>),
+    Binary(BinOp, Box<Expr>, Box<Expr>),
+    /// `if cond then yes else no`, only one branch gets evaluated.
+    If(Box<Expr>, Box<Expr>, Box<Expr>),
+    /// `let name = value in body`, with `name` only bound inside `body`.
+    Let(String, Box<Expr>, Box<Expr>),
+}
+
+impl Value {
+    pub fn type_of(&self) -> Type {
+        match self {
+            Value::Int(_) => Type::Int,
+            Value::Bool(_) => Type::Bool,
+        }
+    }
+}
+
+impl UnOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            UnOp::Neg => "-",
+            UnOp::Not => "!",
+        }
+    }
+}
+
+impl BinOp {
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BinOp::Add => "+",
+            BinOp::Sub => "-",
+            BinOp::Mul => "*",
+            BinOp::Div => "/",
+            BinOp::Rem => "%",
+            BinOp::Eq => "==",
+            BinOp::Ne => "!=",
+            BinOp::Lt => "<",
+            BinOp::Le => "<=",
+            BinOp::Gt => ">",
+            BinOp::Ge => ">=",
+            BinOp::And => "&&",
+            BinOp::Or => "||",
+        }
+    }
+
+    // how tightly it binds, bigger is tighter. if and let are 0, unary
+    // operators 6 and anything that can't be split 7
+    fn precedence(&self) -> u8 {
+        match self {
+            BinOp::Or => 1,
+            BinOp::And => 2,
+            BinOp::Eq | BinOp::Ne | BinOp::Lt | BinOp::Le | BinOp::Gt | BinOp::Ge => 3,
+            BinOp::Add | BinOp::Sub => 4,
+            BinOp::Mul | BinOp::Div | BinOp::Rem => 5,
+        }
+    }
+}
+
+impl Expr {
+    pub fn int(n: i64) -> Expr {
+        Expr::Lit(Value::Int(n))
+    }
+
+    pub fn bool(b: bool) -> Expr {
+        Expr::Lit(Value::Bool(b))
+    }
+
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    pub fn unary(op: UnOp, operand: Expr) -> Expr {
+        Expr::Unary(op, Box::new(operand))
+    }
+
+    pub fn binary(op: BinOp, left: Expr, right: Expr) -> Expr {
+        Expr::Binary(op, Box::new(left), Box::new(right))
+    }
+
+    pub fn if_(cond: Expr, yes: Expr, no: Expr) -> Expr {
+        Expr::If(Box::new(cond), Box::new(yes), Box::new(no))
+    }
+
+    pub fn let_(name: &str, value: Expr, body: Expr) -> Expr {
+        Expr::Let(name.to_string(), Box::new(value), Box::new(body))
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Expr::If(..) | Expr::Let(..) => 0,
+            Expr::Binary(op, ..) => op.precedence(),
+            // -5 prints as the literal it is, but reads like a negation
+            Expr::Lit(Value::Int(n)) if *n < 0 => 6,
+            Expr::Unary(..) => 6,
+            Expr::Lit(_) | Expr::Var(_) => 7,
+        }
+    }
+
+    // prints self, in parentheses if it binds looser than `min`
+    fn fmt_at(&self, f: &mut fmt::Formatter, min: u8) -> fmt::Result {
+        if self.precedence() < min {
+            write!(f, "(")?;
+            self.fmt_at(f, 0)?;
+            return write!(f, ")");
+        }
+        match self {
+            Expr::Lit(value) => write!(f, "{}", value),
+            Expr::Var(name) => write!(f, "{}", name),
+            Expr::Unary(op, operand) => {
+                write!(f, "{}", op.symbol())?;
+                match (op, &**operand) {
+                    // -5 would parse back as the literal, not a negation
+                    (UnOp::Neg, Expr::Lit(Value::Int(n))) if *n >= 0 => write!(f, "({})", n),
+                    // and --5 is easier to misread than - -5
+                    (UnOp::Neg, Expr::Lit(Value::Int(n))) => write!(f, " {}", n),
+                    _ => operand.fmt_at(f, 6),
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                let p = op.precedence();
+                // left to right, so only the right side needs brackets at
+                // the same level. comparisons don't chain at all
+                let left_min = if p == 3 { p + 1 } else { p };
+                left.fmt_at(f, left_min)?;
+                write!(f, " {} ", op.symbol())?;
+                right.fmt_at(f, p + 1)
+            }
+            Expr::If(cond, yes, no) => {
+                write!(f, "if ")?;
+                cond.fmt_at(f, 0)?;
+                write!(f, " then ")?;
+                yes.fmt_at(f, 0)?;
+                write!(f, " else ")?;
+                no.fmt_at(f, 0)
+            }
+            Expr::Let(name, value, body) => {
+                write!(f, "let {} = ", name)?;
+                value.fmt_at(f, 0)?;
+                write!(f, " in ")?;
+                body.fmt_at(f, 0)
+            }
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Int(n) => write!(f, "{}", n),
+            Value::Bool(b) => write!(f, "{}", b),
+        }
+    }
+}
+
+impl fmt::Display for Type {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Type::Int => write!(f, "int"),
+            Type::Bool => write!(f, "bool"),
+        }
+    }
+}
+
+/// The source form, with only the parentheses it needs, so it parses back
+/// to the same tree.
+impl fmt::Display for Expr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.fmt_at(f, 0)
+    }
+}
@@ -0,0 +1,215 @@
+// working out an Expr's Value. the arithmetic is a match on the operator
+// and both values at once, so a pattern like (Div, Int(_), Int(0)) picks
+// out division by zero by itself, and whatever no arm takes is a type
+// mismatch
+//
+// variables come from two places: an Env, for names bound outside the
+// expression (a REPL's lets), and the lets inside it. those are a chain of
+// Scopes on the stack, innermost first, so shadowing is just finding the
+// nearest one
+use super::{BinOp, Expr, Type, UnOp, Value};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    /// `/` or `%` by zero.
+    DivisionByZero,
+    /// A value of the wrong type for `op`, which is an operator's symbol
+    /// or `if`.
+    TypeMismatch {
+        op: &'static str,
+        expected: Type,
+        found: Type,
+    },
+    UnboundVariable(String),
+    /// A result an i64 can't hold.
+    Overflow,
+}
+
+/// Variables bound outside any expression, sorted by name.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Env {
+    vars: BTreeMap<String, Value>,
+}
+
+// one let's binding, and the ones around it
+struct Scope<'a> {
+    name: &'a str,
+    value: Value,
+    outer: Option<&'a Scope<'a>>,
+}
+
+impl Env {
+    pub fn new() -> Env {
+        Env::default()
+    }
+
+    /// Binds `name`, replacing what it was. Gives back the old value.
+    pub fn set(&mut self, name: &str, value: Value) -> Option<Value> {
+        self.vars.insert(name.to_string(), value)
+    }
+
+    pub fn get(&self, name: &str) -> Option<Value> {
+        self.vars.get(name).copied()
+    }
+
+    pub fn remove(&mut self, name: &str) -> Option<Value> {
+        self.vars.remove(name)
+    }
+
+    pub fn len(&self) -> usize {
+        self.vars.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vars.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, Value)> {
+        self.vars
+            .iter()
+            .map(|(name, value)| (name.as_str(), *value))
+    }
+}
+
+impl Expr {
+    /// The value, with any variable the expression doesn't bind itself
+    /// looked up in `env`.
+    pub fn eval(&self, env: &Env) -> Result<Value, EvalError> {
+        self.eval_in(env, None)
+    }
+
+    fn eval_in(&self, env: &Env, scope: Option<&Scope>) -> Result<Value, EvalError> {
+        match self {
+            Expr::Lit(value) => Ok(*value),
+            Expr::Var(name) => lookup(name, env, scope),
+            Expr::Unary(op, operand) => unary(*op, operand.eval_in(env, scope)?),
+            // these two only look at the right if they have to, so
+            // false && 1 / 0 is false
+            Expr::Binary(op @ BinOp::And, left, right)
+            | Expr::Binary(op @ BinOp::Or, left, right) => {
+                let stop = *op == BinOp::Or;
+                match left.eval_in(env, scope)? {
+                    Value::Bool(b) if b == stop => Ok(Value::Bool(b)),
+                    Value::Bool(_) => match right.eval_in(env, scope)? {
+                        Value::Bool(b) => Ok(Value::Bool(b)),
+                        other => Err(mismatch(op.symbol(), Type::Bool, other)),
+                    },
+                    other => Err(mismatch(op.symbol(), Type::Bool, other)),
+                }
+            }
+            Expr::Binary(op, left, right) => {
+                let left = left.eval_in(env, scope)?;
+                let right = right.eval_in(env, scope)?;
+                binary(*op, left, right)
+            }
+            Expr::If(cond, yes, no) => match cond.eval_in(env, scope)? {
+                Value::Bool(true) => yes.eval_in(env, scope),
+                Value::Bool(false) => no.eval_in(env, scope),
+                other => Err(mismatch("if", Type::Bool, other)),
+            },
+            Expr::Let(name, value, body) => {
+                let inner = Scope {
+                    name,
+                    value: value.eval_in(env, scope)?,
+                    outer: scope,
+                };
+                body.eval_in(env, Some(&inner))
+            }
+        }
+    }
+}
+
+fn lookup(name: &str, env: &Env, mut scope: Option<&Scope>) -> Result<Value, EvalError> {
+    while let Some(Scope {
+        name: bound,
+        value,
+        outer,
+    }) = scope
+    {
+        if *bound == name {
+            return Ok(*value);
+        }
+        scope = *outer;
+    }
+    env.get(name)
+        .ok_or_else(|| EvalError::UnboundVariable(name.to_string()))
+}
+
+fn mismatch(op: &'static str, expected: Type, found: Value) -> EvalError {
+    EvalError::TypeMismatch {
+        op,
+        expected,
+        found: found.type_of(),
+    }
+}
+
+// an i64 operation's answer, or Overflow
+fn int(result: Option<i64>) -> Result<Value, EvalError> {
+    result.map(Value::Int).ok_or(EvalError::Overflow)
+}
+
+pub(super) fn unary(op: UnOp, value: Value) -> Result<Value, EvalError> {
+    match (op, value) {
+        (UnOp::Neg, Value::Int(n)) => int(n.checked_neg()),
+        (UnOp::Not, Value::Bool(b)) => Ok(Value::Bool(!b)),
+        (UnOp::Neg, other) => Err(mismatch(op.symbol(), Type::Int, other)),
+        (UnOp::Not, other) => Err(mismatch(op.symbol(), Type::Bool, other)),
+    }
+}
+
+// both sides already worked out, so && and || don't short circuit here
+pub(super) fn binary(op: BinOp, left: Value, right: Value) -> Result<Value, EvalError> {
+    use self::Value::{Bool, Int};
+
+    match (op, left, right) {
+        (BinOp::Div, Int(_), Int(0)) | (BinOp::Rem, Int(_), Int(0)) => {
+            Err(EvalError::DivisionByZero)
+        }
+        (BinOp::Add, Int(a), Int(b)) => int(a.checked_add(b)),
+        (BinOp::Sub, Int(a), Int(b)) => int(a.checked_sub(b)),
+        (BinOp::Mul, Int(a), Int(b)) => int(a.checked_mul(b)),
+        // i64::MIN / -1 is the one overflow these two have
+        (BinOp::Div, Int(a), Int(b)) => int(a.checked_div(b)),
+        (BinOp::Rem, Int(a), Int(b)) => int(a.checked_rem(b)),
+        (BinOp::Lt, Int(a), Int(b)) => Ok(Bool(a < b)),
+        (BinOp::Le, Int(a), Int(b)) => Ok(Bool(a <= b)),
+        (BinOp::Gt, Int(a), Int(b)) => Ok(Bool(a > b)),
+        (BinOp::Ge, Int(a), Int(b)) => Ok(Bool(a >= b)),
+        (BinOp::Eq, a, b) if a.type_of() == b.type_of() => Ok(Bool(a == b)),
+        (BinOp::Ne, a, b) if a.type_of() == b.type_of() => Ok(Bool(a != b)),
+        (BinOp::And, Bool(a), Bool(b)) => Ok(Bool(a && b)),
+        (BinOp::Or, Bool(a), Bool(b)) => Ok(Bool(a || b)),
+        // == and != take either type, as long as it's the same on both
+        // sides, so the left one is what's expected
+        (BinOp::Eq, a, b) | (BinOp::Ne, a, b) => Err(mismatch(op.symbol(), a.type_of(), b)),
+        (BinOp::And, a, b) | (BinOp::Or, a, b) => {
+            let wrong = if a.type_of() == Type::Bool { b } else { a };
+            Err(mismatch(op.symbol(), Type::Bool, wrong))
+        }
+        // everything else wants two ints
+        (_, a, b) => {
+            let wrong = if a.type_of() == Type::Int { b } else { a };
+            Err(mismatch(op.symbol(), Type::Int, wrong))
+        }
+    }
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::DivisionByZero => write!(f, "division by zero"),
+            EvalError::TypeMismatch {
+                op,
+                expected,
+                found,
+            } => write!(f, "{} wants {}, not {}", op, expected, found),
+            EvalError::UnboundVariable(name) => write!(f, "{} isn't bound to anything", name),
+            EvalError::Overflow => write!(f, "too big for an i64"),
+        }
+    }
+}
+
+impl Error for EvalError {}
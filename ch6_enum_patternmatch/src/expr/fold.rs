@@ -0,0 +1,79 @@
+// constant folding: working out ahead of time whatever doesn't depend on a
+// variable. 2 * 3 + x becomes 6 + x, if true then a else b becomes a, and a
+// let of a constant gets the constant put in wherever the name's used
+//
+// the folded expression has to evaluate to exactly what the original did,
+// errors included. so anything that would fail, like 1 / 0, is left alone
+// to fail when it's run, and nothing like x * 1 becomes x: that's only
+// true when x turns out to be an Int
+use super::eval::{binary, unary};
+use super::{BinOp, Expr, Value};
+
+impl Expr {
+    /// The same expression with every constant part worked out. Evaluates
+    /// to the same thing, or the same error, in any [`Env`](super::Env).
+    pub fn fold(&self) -> Expr {
+        match self {
+            Expr::Lit(_) | Expr::Var(_) => self.clone(),
+            Expr::Unary(op, operand) => match operand.fold() {
+                Expr::Lit(value) => match unary(*op, value) {
+                    Ok(result) => Expr::Lit(result),
+                    Err(_) => Expr::unary(*op, Expr::Lit(value)),
+                },
+                operand => Expr::unary(*op, operand),
+            },
+            Expr::Binary(op, left, right) => match (op, left.fold(), right.fold()) {
+                // these short circuit, whatever the right side is
+                (BinOp::And, Expr::Lit(Value::Bool(false)), _) => Expr::bool(false),
+                (BinOp::Or, Expr::Lit(Value::Bool(true)), _) => Expr::bool(true),
+                (op, Expr::Lit(a), Expr::Lit(b)) => match binary(*op, a, b) {
+                    Ok(result) => Expr::Lit(result),
+                    Err(_) => Expr::binary(*op, Expr::Lit(a), Expr::Lit(b)),
+                },
+                (op, left, right) => Expr::binary(*op, left, right),
+            },
+            Expr::If(cond, yes, no) => match cond.fold() {
+                Expr::Lit(Value::Bool(true)) => yes.fold(),
+                Expr::Lit(Value::Bool(false)) => no.fold(),
+                cond => Expr::if_(cond, yes.fold(), no.fold()),
+            },
+            Expr::Let(name, value, body) => match value.fold() {
+                Expr::Lit(value) => body.substitute(name, value).fold(),
+                value => Expr::Let(name.clone(), Box::new(value), Box::new(body.fold())),
+            },
+        }
+    }
+
+    // self with `name` replaced by `value` wherever it isn't shadowed
+    fn substitute(&self, name: &str, value: Value) -> Expr {
+        match self {
+            Expr::Var(var) if var == name => Expr::Lit(value),
+            Expr::Lit(_) | Expr::Var(_) => self.clone(),
+            Expr::Unary(op, operand) => Expr::unary(*op, operand.substitute(name, value)),
+            Expr::Binary(op, left, right) => Expr::binary(
+                *op,
+                left.substitute(name, value),
+                right.substitute(name, value),
+            ),
+            Expr::If(cond, yes, no) => Expr::if_(
+                cond.substitute(name, value),
+                yes.substitute(name, value),
+                no.substitute(name, value),
+            ),
+            // an inner let of the same name hides it in the body, but not
+            // in its own value
+            Expr::Let(inner, bound, body) => {
+                let body = if inner == name {
+                    (**body).clone()
+                } else {
+                    body.substitute(name, value)
+                };
+                Expr::Let(
+                    inner.clone(),
+                    Box::new(bound.substitute(name, value)),
+                    Box::new(body),
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,499 @@
+// text to Expr. a lexer cuts the input into tokens, then a recursive
+// descent parser with one function per precedence level (see the table in
+// expr.rs) builds the tree. each level calls the next tighter one for its
+// operands, so 1 + 2 * 3 comes out as 1 + (2 * 3) without any lookahead
+// past one token
+use super::{BinOp, Expr, UnOp, Value};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// How deeply an expression can nest, in brackets or in the tree it parses
+/// to. Parsing, evaluating, folding and printing all recurse, so past some
+/// depth a line would run them out of stack. A debug build spends about
+/// 12KiB of stack on each level of brackets, so this leaves room to spare
+/// on the 2MiB a spawned thread gets.
+pub const MAX_DEPTH: usize = 128;
+
+/// A line of input to a REPL: an expression, or `let name = value` with no
+/// `in`, which keeps `name` around for the lines after it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Statement {
+    Let(String, Expr),
+    Expr(Expr),
+}
+
+/// What went wrong and where, as a byte offset into the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Nothing but whitespace.
+    Empty,
+    /// A character no token starts with.
+    UnexpectedChar { pos: usize, ch: char },
+    /// A token where something else had to be, as it was written.
+    Unexpected {
+        pos: usize,
+        found: String,
+        expected: &'static str,
+    },
+    /// The input ran out while `expected` was still wanted.
+    UnexpectedEnd { expected: &'static str },
+    /// A number an i64 can't hold.
+    NumberTooBig { pos: usize },
+    /// `a < b < c`, which is almost never what anyone means.
+    ChainedComparison { pos: usize },
+    /// Nested more than [`MAX_DEPTH`] deep, like `((((1))))` or
+    /// `1 + 1 + 1 + 1` with a lot more of them.
+    TooDeep { pos: usize },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    // the digits are in the source, see Lexed
+    Int,
+    Ident,
+    True,
+    False,
+    If,
+    Then,
+    Else,
+    Let,
+    In,
+    Plus,
+    Minus,
+    Star,
+    Slash,
+    Percent,
+    EqEq,
+    NotEq,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    AndAnd,
+    OrOr,
+    Bang,
+    Assign,
+    LParen,
+    RParen,
+}
+
+// a token and the slice of input it came from
+#[derive(Debug, Clone, Copy)]
+struct Lexed<'a> {
+    token: Token,
+    pos: usize,
+    text: &'a str,
+}
+
+fn lex(input: &str) -> Result<Vec<Lexed<'_>>, ParseError> {
+    let bytes = input.as_bytes();
+    let mut tokens = Vec::new();
+    let mut pos = 0;
+    while pos < bytes.len() {
+        let start = pos;
+        let c = bytes[pos];
+        let next = bytes.get(pos + 1).copied();
+        let token = match (c, next) {
+            (b' ', _) | (b'\t', _) | (b'\r', _) | (b'\n', _) => {
+                pos += 1;
+                continue;
+            }
+            (b'0'..=b'9', _) => {
+                while pos < bytes.len() && bytes[pos].is_ascii_digit() {
+                    pos += 1;
+                }
+                Token::Int
+            }
+            (b'a'..=b'z', _) | (b'A'..=b'Z', _) | (b'_', _) => {
+                while pos < bytes.len()
+                    && (bytes[pos].is_ascii_alphanumeric() || bytes[pos] == b'_')
+                {
+                    pos += 1;
+                }
+                match &input[start..pos] {
+                    "true" => Token::True,
+                    "false" => Token::False,
+                    "if" => Token::If,
+                    "then" => Token::Then,
+                    "else" => Token::Else,
+                    "let" => Token::Let,
+                    "in" => Token::In,
+                    _ => Token::Ident,
+                }
+            }
+            (b'=', Some(b'=')) => two(&mut pos, Token::EqEq),
+            (b'!', Some(b'=')) => two(&mut pos, Token::NotEq),
+            (b'<', Some(b'=')) => two(&mut pos, Token::Le),
+            (b'>', Some(b'=')) => two(&mut pos, Token::Ge),
+            (b'&', Some(b'&')) => two(&mut pos, Token::AndAnd),
+            (b'|', Some(b'|')) => two(&mut pos, Token::OrOr),
+            (b'+', _) => one(&mut pos, Token::Plus),
+            (b'-', _) => one(&mut pos, Token::Minus),
+            (b'*', _) => one(&mut pos, Token::Star),
+            (b'/', _) => one(&mut pos, Token::Slash),
+            (b'%', _) => one(&mut pos, Token::Percent),
+            (b'<', _) => one(&mut pos, Token::Lt),
+            (b'>', _) => one(&mut pos, Token::Gt),
+            (b'!', _) => one(&mut pos, Token::Bang),
+            (b'=', _) => one(&mut pos, Token::Assign),
+            (b'(', _) => one(&mut pos, Token::LParen),
+            (b')', _) => one(&mut pos, Token::RParen),
+            _ => {
+                let ch = input[start..].chars().next().expect("pos is in bounds");
+                return Err(ParseError::UnexpectedChar { pos: start, ch });
+            }
+        };
+        tokens.push(Lexed {
+            token,
+            pos: start,
+            text: &input[start..pos],
+        });
+    }
+    Ok(tokens)
+}
+
+fn one(pos: &mut usize, token: Token) -> Token {
+    *pos += 1;
+    token
+}
+
+fn two(pos: &mut usize, token: Token) -> Token {
+    *pos += 2;
+    token
+}
+
+struct Parser<'a> {
+    tokens: Vec<Lexed<'a>>,
+    next: usize,
+    // how many nested() calls deep the parser is
+    depth: usize,
+    // how tall the tree of the expression parsed last is, a literal is 1
+    height: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Result<Parser<'a>, ParseError> {
+        let tokens = lex(input)?;
+        if tokens.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        Ok(Parser {
+            tokens,
+            next: 0,
+            depth: 0,
+            height: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.next).map(|lexed| lexed.token)
+    }
+
+    fn bump(&mut self) -> Option<Lexed<'a>> {
+        let lexed = self.tokens.get(self.next).copied();
+        self.next += 1;
+        lexed
+    }
+
+    // the error for finding whatever's next instead of `expected`
+    fn unexpected(&self, expected: &'static str) -> ParseError {
+        match self.tokens.get(self.next) {
+            Some(lexed) => ParseError::Unexpected {
+                pos: lexed.pos,
+                found: lexed.text.to_string(),
+                expected,
+            },
+            None => ParseError::UnexpectedEnd { expected },
+        }
+    }
+
+    fn expect(&mut self, token: Token, expected: &'static str) -> Result<Lexed<'a>, ParseError> {
+        if self.peek() == Some(token) {
+            Ok(self.bump().expect("just peeked it"))
+        } else {
+            Err(self.unexpected(expected))
+        }
+    }
+
+    // everywhere the parser calls itself goes through here, so brackets or
+    // minus signs can't take it past MAX_DEPTH calls deep
+    fn nested<T>(
+        &mut self,
+        parse: impl FnOnce(&mut Parser<'a>) -> Result<T, ParseError>,
+    ) -> Result<T, ParseError> {
+        if self.depth == MAX_DEPTH {
+            // at whatever was about to be parsed, or the last token if the
+            // input's run out
+            let lexed = self.tokens.get(self.next).or_else(|| self.tokens.last());
+            let pos = lexed.expect("the parser has tokens").pos;
+            return Err(ParseError::TooDeep { pos });
+        }
+        self.depth += 1;
+        let result = parse(self);
+        self.depth -= 1;
+        result
+    }
+
+    // a node over children of the given heights, one taller than the
+    // tallest. a long chain like 1 + 1 + 1 only nests in the tree, never in
+    // the parser, so this is what catches it
+    fn node(&mut self, pos: usize, heights: &[usize]) -> Result<(), ParseError> {
+        let height = heights.iter().max().map_or(1, |h| h + 1);
+        if height > MAX_DEPTH {
+            return Err(ParseError::TooDeep { pos });
+        }
+        self.height = height;
+        Ok(())
+    }
+
+    fn end(&self) -> Result<(), ParseError> {
+        match self.peek() {
+            None => Ok(()),
+            Some(_) => Err(self.unexpected("an operator or the end")),
+        }
+    }
+
+    fn expr(&mut self) -> Result<Expr, ParseError> {
+        self.nested(|p| match p.peek() {
+            Some(Token::If) => {
+                let pos = p.bump().expect("just peeked it").pos;
+                let cond = p.expr()?;
+                let cond_height = p.height;
+                p.expect(Token::Then, "then")?;
+                let yes = p.expr()?;
+                let yes_height = p.height;
+                p.expect(Token::Else, "else")?;
+                let no = p.expr()?;
+                p.node(pos, &[cond_height, yes_height, p.height])?;
+                Ok(Expr::if_(cond, yes, no))
+            }
+            Some(Token::Let) => {
+                let pos = p.tokens[p.next].pos;
+                let (name, value) = p.binding()?;
+                let value_height = p.height;
+                p.expect(Token::In, "in")?;
+                let body = p.expr()?;
+                p.node(pos, &[value_height, p.height])?;
+                Ok(Expr::Let(name, Box::new(value), Box::new(body)))
+            }
+            _ => p.or(),
+        })
+    }
+
+    // let name = value, up to where the in would be
+    fn binding(&mut self) -> Result<(String, Expr), ParseError> {
+        self.expect(Token::Let, "let")?;
+        let name = self.expect(Token::Ident, "a name")?.text.to_string();
+        self.expect(Token::Assign, "=")?;
+        let value = self.expr()?;
+        Ok((name, value))
+    }
+
+    // one left to right level: operands from `operand`, joined by whichever
+    // of `ops` comes next
+    fn left_assoc(
+        &mut self,
+        ops: &[(Token, BinOp)],
+        operand: fn(&mut Parser<'a>) -> Result<Expr, ParseError>,
+    ) -> Result<Expr, ParseError> {
+        let mut left = operand(self)?;
+        while let Some(&(_, op)) = ops.iter().find(|(token, _)| Some(*token) == self.peek()) {
+            let pos = self.bump().expect("just peeked it").pos;
+            let left_height = self.height;
+            let right = operand(self)?;
+            self.node(pos, &[left_height, self.height])?;
+            left = Expr::binary(op, left, right);
+        }
+        Ok(left)
+    }
+
+    fn or(&mut self) -> Result<Expr, ParseError> {
+        self.left_assoc(&[(Token::OrOr, BinOp::Or)], Parser::and)
+    }
+
+    fn and(&mut self) -> Result<Expr, ParseError> {
+        self.left_assoc(&[(Token::AndAnd, BinOp::And)], Parser::comparison)
+    }
+
+    fn comparison(&mut self) -> Result<Expr, ParseError> {
+        let left = self.sum()?;
+        let op = match comparison_op(self.peek()) {
+            Some(op) => op,
+            None => return Ok(left),
+        };
+        let pos = self.bump().expect("just peeked it").pos;
+        let left_height = self.height;
+        let right = self.sum()?;
+        if comparison_op(self.peek()).is_some() {
+            let pos = self.tokens[self.next].pos;
+            return Err(ParseError::ChainedComparison { pos });
+        }
+        self.node(pos, &[left_height, self.height])?;
+        Ok(Expr::binary(op, left, right))
+    }
+
+    fn sum(&mut self) -> Result<Expr, ParseError> {
+        let ops = [(Token::Plus, BinOp::Add), (Token::Minus, BinOp::Sub)];
+        self.left_assoc(&ops, Parser::product)
+    }
+
+    fn product(&mut self) -> Result<Expr, ParseError> {
+        let ops = [
+            (Token::Star, BinOp::Mul),
+            (Token::Slash, BinOp::Div),
+            (Token::Percent, BinOp::Rem),
+        ];
+        self.left_assoc(&ops, Parser::unary)
+    }
+
+    fn unary(&mut self) -> Result<Expr, ParseError> {
+        match self.peek() {
+            Some(Token::Minus) => {
+                let pos = self.bump().expect("just peeked it").pos;
+                // a minus right on a number is part of it, which is the
+                // only way to write i64::MIN
+                if self.peek() == Some(Token::Int) {
+                    let lexed = self.bump().expect("just peeked it");
+                    self.height = 1;
+                    return Ok(Expr::int(number(lexed, true)?));
+                }
+                let operand = self.nested(Parser::unary)?;
+                self.node(pos, &[self.height])?;
+                Ok(Expr::unary(UnOp::Neg, operand))
+            }
+            Some(Token::Bang) => {
+                let pos = self.bump().expect("just peeked it").pos;
+                let operand = self.nested(Parser::unary)?;
+                self.node(pos, &[self.height])?;
+                Ok(Expr::unary(UnOp::Not, operand))
+            }
+            _ => self.atom(),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, ParseError> {
+        // a leaf, unless it's brackets, if or let. those leave the height
+        // of whatever they parsed instead
+        self.height = 1;
+        match self.peek() {
+            Some(Token::Int) => {
+                let lexed = self.bump().expect("just peeked it");
+                Ok(Expr::int(number(lexed, false)?))
+            }
+            Some(Token::True) => {
+                self.bump();
+                Ok(Expr::Lit(Value::Bool(true)))
+            }
+            Some(Token::False) => {
+                self.bump();
+                Ok(Expr::Lit(Value::Bool(false)))
+            }
+            Some(Token::Ident) => {
+                let lexed = self.bump().expect("just peeked it");
+                Ok(Expr::var(lexed.text))
+            }
+            Some(Token::LParen) => {
+                self.bump();
+                let inner = self.expr()?;
+                self.expect(Token::RParen, ")")?;
+                Ok(inner)
+            }
+            // so 1 + if c then 2 else 3 works without brackets
+            Some(Token::If) | Some(Token::Let) => self.expr(),
+            _ => Err(self.unexpected("a value")),
+        }
+    }
+}
+
+fn comparison_op(token: Option<Token>) -> Option<BinOp> {
+    match token? {
+        Token::EqEq => Some(BinOp::Eq),
+        Token::NotEq => Some(BinOp::Ne),
+        Token::Lt => Some(BinOp::Lt),
+        Token::Le => Some(BinOp::Le),
+        Token::Gt => Some(BinOp::Gt),
+        Token::Ge => Some(BinOp::Ge),
+        _ => None,
+    }
+}
+
+// the digits of an Int token, negated if there was a minus on them
+fn number(lexed: Lexed, negative: bool) -> Result<i64, ParseError> {
+    let too_big = ParseError::NumberTooBig { pos: lexed.pos };
+    let digits: i128 = lexed.text.parse().map_err(|_| too_big.clone())?;
+    let n = if negative { -digits } else { digits };
+    if n < i128::from(i64::MIN) || n > i128::from(i64::MAX) {
+        return Err(too_big);
+    }
+    Ok(n as i64)
+}
+
+impl FromStr for Expr {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Expr, ParseError> {
+        let mut parser = Parser::new(s)?;
+        let expr = parser.expr()?;
+        parser.end()?;
+        Ok(expr)
+    }
+}
+
+impl FromStr for Statement {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Statement, ParseError> {
+        let mut parser = Parser::new(s)?;
+        // a let that ends without an in binds for good, any other let is
+        // the start of an expression
+        if parser.peek() == Some(Token::Let) {
+            if let Ok((name, value)) = parser.binding() {
+                if parser.peek().is_none() {
+                    return Ok(Statement::Let(name, value));
+                }
+            }
+            parser.next = 0;
+        }
+        let expr = parser.expr()?;
+        parser.end()?;
+        Ok(Statement::Expr(expr))
+    }
+}
+
+impl fmt::Display for Statement {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Statement::Let(name, value) => write!(f, "let {} = {}", name, value),
+            Statement::Expr(expr) => expr.fmt(f),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "nothing to parse"),
+            ParseError::UnexpectedChar { pos, ch } => {
+                write!(f, "at {}: {:?} isn't part of the language", pos, ch)
+            }
+            ParseError::Unexpected {
+                pos,
+                found,
+                expected,
+            } => write!(f, "at {}: expected {}, found {:?}", pos, expected, found),
+            ParseError::UnexpectedEnd { expected } => {
+                write!(f, "expected {}, but that's the end", expected)
+            }
+            ParseError::NumberTooBig { pos } => write!(f, "at {}: too big for an i64", pos),
+            ParseError::ChainedComparison { pos } => {
+                write!(f, "at {}: comparisons don't chain, try a < b && b < c", pos)
+            }
+            ParseError::TooDeep { pos } => {
+                write!(f, "at {}: nested more than {} deep", pos, MAX_DEPTH)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,12 @@
+// the IpAddr enum from enum_basics() in main.rs, for real. the book's version
+// holds a String, so IpAddr::V4("banana") is fine by it. here V4 holds four
+// octets and V6 eight 16 bit segments, so only real addresses can exist, and
+// parsing a string is where the checking happens
+
+// everything is meant to agree with std::net, which tests/ip.rs checks. std
+// is what you'd use in real code, this is for the enum practice
+mod addr;
+mod cidr;
+
+pub use self::addr::{AddrParseError, IpAddr, IpAddrKind};
+pub use self::cidr::{Addrs, Cidr, CidrError, Subnets};
@@ -0,0 +1,301 @@
+use std::error::Error;
+use std::fmt;
+use std::net;
+use std::str::FromStr;
+
+/// Which version an address is, without the address itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpAddrKind {
+    V4,
+    V6,
+}
+
+/// An IPv4 or IPv6 address. Every V4 sorts before every V6, and within a
+/// version they sort numerically.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum IpAddr {
+    /// `127.0.0.1` is `V4([127, 0, 0, 1])`.
+    V4([u8; 4]),
+    /// `::1` is `V6([0, 0, 0, 0, 0, 0, 0, 1])`.
+    V6([u16; 8]),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AddrParseError {
+    Empty,
+    /// Not four parts between the dots.
+    OctetCount(usize),
+    /// A part that isn't a number from 0 to 255 without leading zeros.
+    Octet(String),
+    /// Not eight segments, and no `::` to make up the difference.
+    SegmentCount(usize),
+    /// A `::` with all eight segments already there, so it stands for
+    /// nothing.
+    NothingToCompress,
+    /// More than one `::`, so there's no telling how many zeros each is.
+    DoubleColons,
+    /// A part that isn't 1 to 4 hex digits.
+    Segment(String),
+}
+
+impl IpAddrKind {
+    /// How many bits an address of this kind has.
+    pub fn bits(&self) -> u8 {
+        match self {
+            IpAddrKind::V4 => 32,
+            IpAddrKind::V6 => 128,
+        }
+    }
+}
+
+impl IpAddr {
+    pub fn kind(&self) -> IpAddrKind {
+        match self {
+            IpAddr::V4(_) => IpAddrKind::V4,
+            IpAddr::V6(_) => IpAddrKind::V6,
+        }
+    }
+
+    /// The address as one number, in the low bits for V4. Handy for masks
+    /// and for counting through a range.
+    pub fn to_bits(&self) -> u128 {
+        match self {
+            IpAddr::V4(octets) => u128::from(u32::from_be_bytes(*octets)),
+            IpAddr::V6(segments) => segments
+                .iter()
+                .fold(0, |bits, &s| (bits << 16) | u128::from(s)),
+        }
+    }
+
+    /// The opposite of [`IpAddr::to_bits`]. Anything past 32 bits is ignored
+    /// for V4.
+    pub fn from_bits(kind: IpAddrKind, bits: u128) -> IpAddr {
+        match kind {
+            IpAddrKind::V4 => IpAddr::V4((bits as u32).to_be_bytes()),
+            IpAddrKind::V6 => {
+                let mut segments = [0; 8];
+                for (i, s) in segments.iter_mut().enumerate() {
+                    *s = (bits >> (16 * (7 - i))) as u16;
+                }
+                IpAddr::V6(segments)
+            }
+        }
+    }
+
+    /// `0.0.0.0` or `::`, meaning "no address in particular".
+    pub fn is_unspecified(&self) -> bool {
+        self.to_bits() == 0
+    }
+
+    /// `127.0.0.0/8` or `::1`, this machine.
+    pub fn is_loopback(&self) -> bool {
+        match self {
+            IpAddr::V4(o) => o[0] == 127,
+            IpAddr::V6(_) => self.to_bits() == 1,
+        }
+    }
+
+    /// For networks that don't reach the internet: `10.0.0.0/8`,
+    /// `172.16.0.0/12` and `192.168.0.0/16` for V4, unique local
+    /// `fc00::/7` for V6.
+    pub fn is_private(&self) -> bool {
+        match self {
+            IpAddr::V4(o) => {
+                o[0] == 10 || o[0] == 172 && (o[1] & 0xf0) == 16 || o[0] == 192 && o[1] == 168
+            }
+            IpAddr::V6(s) => (s[0] & 0xfe00) == 0xfc00,
+        }
+    }
+
+    /// `224.0.0.0/4` or `ff00::/8`, one sender to a group of receivers.
+    pub fn is_multicast(&self) -> bool {
+        match self {
+            IpAddr::V4(o) => (o[0] & 0xf0) == 224,
+            IpAddr::V6(s) => (s[0] & 0xff00) == 0xff00,
+        }
+    }
+
+    /// `169.254.0.0/16` or `fe80::/10`, only good on the local link and
+    /// usually made up by the machine itself.
+    pub fn is_link_local(&self) -> bool {
+        match self {
+            IpAddr::V4(o) => o[0] == 169 && o[1] == 254,
+            IpAddr::V6(s) => (s[0] & 0xffc0) == 0xfe80,
+        }
+    }
+}
+
+fn parse_v4(s: &str) -> Result<[u8; 4], AddrParseError> {
+    let parts: Vec<&str> = s.split('.').collect();
+    if parts.len() != 4 {
+        return Err(AddrParseError::OctetCount(parts.len()));
+    }
+    let mut octets = [0; 4];
+    for (octet, part) in octets.iter_mut().zip(parts) {
+        // u8::from_str would take "+1" and "001", std doesn't
+        let digits =
+            !part.is_empty() && part.len() <= 3 && part.bytes().all(|b| b.is_ascii_digit());
+        let leading_zero = part.len() > 1 && part.starts_with('0');
+        *octet = match part.parse() {
+            Ok(n) if digits && !leading_zero => n,
+            _ => return Err(AddrParseError::Octet(part.to_string())),
+        };
+    }
+    Ok(octets)
+}
+
+// the colon separated segments on one side of a ::. a dotted V4 address is
+// allowed as the last part (like ::ffff:192.0.2.1), and counts as two
+fn parse_segments(s: &str, v4_at_end: bool) -> Result<Vec<u16>, AddrParseError> {
+    if s.is_empty() {
+        return Ok(Vec::new());
+    }
+    let parts: Vec<&str> = s.split(':').collect();
+    let mut segments = Vec::with_capacity(8);
+    for (i, part) in parts.iter().enumerate() {
+        if v4_at_end && i == parts.len() - 1 && part.contains('.') {
+            let [a, b, c, d] = parse_v4(part)?;
+            segments.push(u16::from_be_bytes([a, b]));
+            segments.push(u16::from_be_bytes([c, d]));
+            continue;
+        }
+        let hex =
+            !part.is_empty() && part.len() <= 4 && part.bytes().all(|b| b.is_ascii_hexdigit());
+        if !hex {
+            return Err(AddrParseError::Segment(part.to_string()));
+        }
+        segments.push(u16::from_str_radix(part, 16).expect("checked it's hex"));
+    }
+    Ok(segments)
+}
+
+fn parse_v6(s: &str) -> Result<[u16; 8], AddrParseError> {
+    let mut halves = s.split("::");
+    let (head, tail) = match (halves.next(), halves.next(), halves.next()) {
+        (Some(all), None, _) => (parse_segments(all, true)?, None),
+        (Some(head), Some(tail), None) => (
+            parse_segments(head, false)?,
+            Some(parse_segments(tail, true)?),
+        ),
+        _ => return Err(AddrParseError::DoubleColons),
+    };
+
+    let mut segments = [0; 8];
+    match tail {
+        None if head.len() == 8 => segments.copy_from_slice(&head),
+        None => return Err(AddrParseError::SegmentCount(head.len())),
+        // the :: is at least one segment of zeros, so at most 7 written out
+        Some(tail) if head.len() + tail.len() >= 8 => {
+            return Err(AddrParseError::NothingToCompress)
+        }
+        Some(tail) => {
+            segments[..head.len()].copy_from_slice(&head);
+            segments[8 - tail.len()..].copy_from_slice(&tail);
+        }
+    }
+    Ok(segments)
+}
+
+/// Dotted decimal for V4. V6 has a colon so it can't be mistaken for one.
+impl FromStr for IpAddr {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<IpAddr, AddrParseError> {
+        if s.is_empty() {
+            Err(AddrParseError::Empty)
+        } else if s.contains(':') {
+            parse_v6(s).map(IpAddr::V6)
+        } else {
+            parse_v4(s).map(IpAddr::V4)
+        }
+    }
+}
+
+// the canonical form from RFC 5952: lowercase, no leading zeros, and the
+// longest run of two or more zero segments (the first, if there's a tie)
+// squashed into ::. IPv4 mapped addresses end in dotted decimal
+impl fmt::Display for IpAddr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let segments = match self {
+            IpAddr::V4([a, b, c, d]) => return write!(f, "{}.{}.{}.{}", a, b, c, d),
+            IpAddr::V6(segments) => segments,
+        };
+
+        if let [0, 0, 0, 0, 0, 0xffff, ab, cd] = *segments {
+            let ([a, b], [c, d]) = (ab.to_be_bytes(), cd.to_be_bytes());
+            return write!(f, "::ffff:{}.{}.{}.{}", a, b, c, d);
+        }
+
+        // (start, len) of the longest run of zeros
+        let mut longest = (0, 0);
+        let mut i = 0;
+        while i < 8 {
+            let len = segments[i..].iter().take_while(|&&s| s == 0).count();
+            if len > longest.1 {
+                longest = (i, len);
+            }
+            i += len.max(1);
+        }
+
+        let join = |f: &mut fmt::Formatter, part: &[u16]| {
+            let hex: Vec<String> = part.iter().map(|s| format!("{:x}", s)).collect();
+            write!(f, "{}", hex.join(":"))
+        };
+        match longest {
+            (start, len) if len >= 2 => {
+                join(f, &segments[..start])?;
+                write!(f, "::")?;
+                join(f, &segments[start + len..])
+            }
+            _ => join(f, segments),
+        }
+    }
+}
+
+impl From<net::IpAddr> for IpAddr {
+    fn from(addr: net::IpAddr) -> IpAddr {
+        match addr {
+            net::IpAddr::V4(v4) => IpAddr::V4(v4.octets()),
+            net::IpAddr::V6(v6) => IpAddr::V6(v6.segments()),
+        }
+    }
+}
+
+impl From<IpAddr> for net::IpAddr {
+    fn from(addr: IpAddr) -> net::IpAddr {
+        match addr {
+            IpAddr::V4(octets) => net::IpAddr::from(octets),
+            IpAddr::V6(segments) => net::IpAddr::from(segments),
+        }
+    }
+}
+
+impl fmt::Display for AddrParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            AddrParseError::Empty => write!(f, "empty address"),
+            AddrParseError::OctetCount(n) => {
+                write!(f, "an IPv4 address has 4 parts between dots, not {}", n)
+            }
+            AddrParseError::Octet(part) => write!(
+                f,
+                "{:?} isn't a number from 0 to 255 (without leading zeros)",
+                part
+            ),
+            AddrParseError::SegmentCount(n) => write!(
+                f,
+                "an IPv6 address has 8 segments (or a :: for missing ones), not {}",
+                n
+            ),
+            AddrParseError::NothingToCompress => {
+                write!(f, ":: needs to stand for at least one segment of zeros")
+            }
+            AddrParseError::DoubleColons => write!(f, ":: can only appear once"),
+            AddrParseError::Segment(part) => {
+                write!(f, "{:?} isn't 1 to 4 hex digits", part)
+            }
+        }
+    }
+}
+
+impl Error for AddrParseError {}
@@ -0,0 +1,233 @@
+use super::{AddrParseError, IpAddr, IpAddrKind};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+// a network written the CIDR way, like 192.168.0.0/16: the first 16 bits are
+// the network, the rest pick a machine on it. the prefix length says how many
+// bits are fixed, so a bigger prefix is a smaller network
+
+/// A network address and a prefix length. Host bits are always zero, so
+/// `10.1.2.3/8` is stored as `10.0.0.0/8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Cidr {
+    network: IpAddr,
+    prefix: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CidrError {
+    /// The address part didn't parse.
+    Addr(AddrParseError),
+    /// No `/`, or something after it that isn't a number.
+    Prefix(String),
+    /// Longer than the address has bits (32 for V4, 128 for V6).
+    PrefixTooLong { prefix: u8, max: u8 },
+}
+
+impl Cidr {
+    /// The network `addr` is on. Host bits get cleared.
+    pub fn new(addr: IpAddr, prefix: u8) -> Result<Cidr, CidrError> {
+        let max = addr.kind().bits();
+        if prefix > max {
+            return Err(CidrError::PrefixTooLong { prefix, max });
+        }
+        let network = IpAddr::from_bits(addr.kind(), addr.to_bits() & mask(addr.kind(), prefix));
+        Ok(Cidr { network, prefix })
+    }
+
+    /// The network that's just this one address.
+    pub fn host(addr: IpAddr) -> Cidr {
+        Cidr {
+            network: addr,
+            prefix: addr.kind().bits(),
+        }
+    }
+
+    pub fn network(&self) -> IpAddr {
+        self.network
+    }
+
+    pub fn prefix(&self) -> u8 {
+        self.prefix
+    }
+
+    pub fn kind(&self) -> IpAddrKind {
+        self.network.kind()
+    }
+
+    /// Ones for the network bits, like `255.255.0.0` for a /16.
+    pub fn netmask(&self) -> IpAddr {
+        IpAddr::from_bits(self.kind(), mask(self.kind(), self.prefix))
+    }
+
+    /// The highest address on the network. For V4 that's the broadcast
+    /// address.
+    pub fn last(&self) -> IpAddr {
+        let host_bits = !mask(self.kind(), self.prefix) & mask(self.kind(), self.kind().bits());
+        IpAddr::from_bits(self.kind(), self.network.to_bits() | host_bits)
+    }
+
+    /// How many addresses the network has, or `None` for `::/0`, which has
+    /// one too many to fit in a u128.
+    pub fn size(&self) -> Option<u128> {
+        1u128.checked_shl(u32::from(self.kind().bits() - self.prefix))
+    }
+
+    /// Whether `addr` is on this network. Never true for the other version.
+    pub fn contains(&self, addr: &IpAddr) -> bool {
+        addr.kind() == self.kind()
+            && addr.to_bits() & mask(self.kind(), self.prefix) == self.network.to_bits()
+    }
+
+    /// Whether all of `other` is inside this network (including being the
+    /// same network).
+    pub fn contains_cidr(&self, other: &Cidr) -> bool {
+        other.prefix >= self.prefix && self.contains(&other.network)
+    }
+
+    /// Every address on the network, in order, network address and last
+    /// address included.
+    pub fn addrs(&self) -> Addrs {
+        Addrs {
+            kind: self.kind(),
+            next: Some(self.network.to_bits()),
+            last: self.last().to_bits(),
+        }
+    }
+
+    /// The networks this one splits into with the longer `prefix`, in order.
+    /// A /24 split into /26s gives four. A prefix shorter than this network's
+    /// just gives the network back.
+    pub fn subnets(&self, prefix: u8) -> Result<Subnets, CidrError> {
+        let max = self.kind().bits();
+        if prefix > max {
+            return Err(CidrError::PrefixTooLong { prefix, max });
+        }
+        Ok(Subnets {
+            kind: self.kind(),
+            prefix: prefix.max(self.prefix),
+            next: Some(self.network.to_bits()),
+            last: self.last().to_bits(),
+        })
+    }
+}
+
+// `prefix` ones followed by zeros, `bits` wide
+fn mask(kind: IpAddrKind, prefix: u8) -> u128 {
+    let bits = u32::from(kind.bits());
+    let all = u128::MAX >> (128 - bits);
+    // shifting by the full width overflows, hence the checked version
+    let host = all.checked_shr(u32::from(prefix)).unwrap_or(0);
+    all & !host
+}
+
+/// From [`Cidr::addrs`].
+#[derive(Debug, Clone)]
+pub struct Addrs {
+    kind: IpAddrKind,
+    // None once we're done, since last can be the biggest u128 there is
+    next: Option<u128>,
+    last: u128,
+}
+
+impl Iterator for Addrs {
+    type Item = IpAddr;
+
+    fn next(&mut self) -> Option<IpAddr> {
+        let bits = self.next?;
+        self.next = if bits < self.last {
+            Some(bits + 1)
+        } else {
+            None
+        };
+        Some(IpAddr::from_bits(self.kind, bits))
+    }
+}
+
+/// From [`Cidr::subnets`].
+#[derive(Debug, Clone)]
+pub struct Subnets {
+    kind: IpAddrKind,
+    prefix: u8,
+    next: Option<u128>,
+    last: u128,
+}
+
+impl Iterator for Subnets {
+    type Item = Cidr;
+
+    fn next(&mut self) -> Option<Cidr> {
+        let bits = self.next?;
+        let subnet = Cidr {
+            network: IpAddr::from_bits(self.kind, bits),
+            prefix: self.prefix,
+        };
+        let subnet_last = subnet.last().to_bits();
+        self.next = if subnet_last < self.last {
+            Some(subnet_last + 1)
+        } else {
+            None
+        };
+        Some(subnet)
+    }
+}
+
+/// `addr/prefix`, like `10.0.0.0/8` or `fe80::/10`.
+impl FromStr for Cidr {
+    type Err = CidrError;
+
+    fn from_str(s: &str) -> Result<Cidr, CidrError> {
+        let (addr, prefix) = match s.rfind('/') {
+            Some(slash) => (&s[..slash], &s[slash + 1..]),
+            None => return Err(CidrError::Prefix(String::new())),
+        };
+        let addr: IpAddr = addr.parse().map_err(CidrError::Addr)?;
+        let digits = !prefix.is_empty() && prefix.bytes().all(|b| b.is_ascii_digit());
+        let prefix = match prefix.parse() {
+            Ok(prefix) if digits => prefix,
+            _ => return Err(CidrError::Prefix(prefix.to_string())),
+        };
+        Cidr::new(addr, prefix)
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.network, self.prefix)
+    }
+}
+
+impl fmt::Display for CidrError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CidrError::Addr(e) => e.fmt(f),
+            CidrError::Prefix(prefix) if prefix.is_empty() => {
+                write!(f, "expected an address, a / and a prefix length")
+            }
+            CidrError::Prefix(prefix) => write!(f, "{:?} isn't a prefix length", prefix),
+            CidrError::PrefixTooLong { prefix, max } => {
+                write!(
+                    f,
+                    "prefix /{} is longer than the {} bits there are",
+                    prefix, max
+                )
+            }
+        }
+    }
+}
+
+impl Error for CidrError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            CidrError::Addr(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<AddrParseError> for CidrError {
+    fn from(e: AddrParseError) -> CidrError {
+        CidrError::Addr(e)
+    }
+}
@@ -0,0 +1,30 @@
+// the reusable bits of the enums chapter live here, main.rs is still the
+// walkthrough that shows them off
+
+// IpAddr with real octets and segments, plus CIDR networks, see src/ip.rs
+pub mod ip;
+
+// allow/deny rules for addresses with longest prefix matching, see src/acl.rs
+pub mod acl;
+
+// the Message enum as commands with text and binary encodings, see
+// src/message.rs
+pub mod message;
+
+// publish/subscribe for Messages across threads, see src/bus.rs
+pub mod bus;
+
+// every US coin and bill, a Purse, making change, and other currencies,
+// see src/money.rs
+pub mod money;
+
+// inc() from main.rs generalized: checked arithmetic lifted over Option and
+// Result, see src/lift.rs
+pub mod lift;
+
+// a vending machine state machine taking Coins, see src/vending.rs
+pub mod vending;
+
+// an expression language with a parser, evaluator and constant folding, see
+// src/expr.rs
+pub mod expr;
@@ -0,0 +1,240 @@
+// inc() in main.rs, for more than adding one to an i32. each of these takes
+// plain arithmetic and lifts it over Option (or Result): nothing in means
+// nothing out, and so does overflowing, rather than panicking in debug and
+// wrapping in release
+//
+// they're all written out as a match, the way inc is, since that's what
+// this chapter is about. every one's doc says the short way std already has
+// of doing the same thing, which is what real code should use. main.rs and
+// tests/lift.rs check the two agree
+use std::error::Error;
+use std::fmt;
+
+/// An integer with overflow checked arithmetic.
+pub trait Checked: Copy {
+    const ZERO: Self;
+    const ONE: Self;
+
+    /// `None` on overflow.
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+}
+
+macro_rules! checked_int {
+    ($($t:ty),*) => {$(
+        impl Checked for $t {
+            const ZERO: $t = 0;
+            const ONE: $t = 1;
+
+            fn checked_add(self, other: $t) -> Option<$t> {
+                <$t>::checked_add(self, other)
+            }
+
+            fn checked_mul(self, other: $t) -> Option<$t> {
+                <$t>::checked_mul(self, other)
+            }
+        }
+    )*};
+}
+
+checked_int!(i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize);
+
+/// The error the `Result` helpers turn an overflow into. Any error type
+/// with a `From<Overflow>` can come out of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+/// One more, or `None` if there's no room for it.
+///
+/// Same as `x.and_then(|n| n.checked_add(1))`.
+pub fn inc<T: Checked>(x: Option<T>) -> Option<T> {
+    match x {
+        Some(n) => n.checked_add(T::ONE),
+        None => None,
+    }
+}
+
+/// Both added, if there are both and the sum fits.
+///
+/// Same as `a?.checked_add(b?)` in a function returning `Option`.
+pub fn add<T: Checked>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_add(b),
+        _ => None,
+    }
+}
+
+/// Both multiplied, if there are both and the product fits.
+///
+/// Same as `a?.checked_mul(b?)` in a function returning `Option`.
+pub fn mul<T: Checked>(a: Option<T>, b: Option<T>) -> Option<T> {
+    match (a, b) {
+        (Some(a), Some(b)) => a.checked_mul(b),
+        _ => None,
+    }
+}
+
+/// Everything added up, or `None` if anything was `None` or the total
+/// overflowed. Nothing at all adds up to zero.
+///
+/// Same as `iter.try_fold(0, |sum, n| sum.checked_add(n?))`.
+pub fn sum<T, I>(iter: I) -> Option<T>
+where
+    T: Checked,
+    I: IntoIterator<Item = Option<T>>,
+{
+    let mut total = T::ZERO;
+    for n in iter {
+        total = match n {
+            Some(n) => total.checked_add(n)?,
+            None => return None,
+        };
+    }
+    Some(total)
+}
+
+/// Everything multiplied, like [`sum`]. Nothing at all multiplies to one.
+///
+/// Same as `iter.try_fold(1, |product, n| product.checked_mul(n?))`.
+pub fn product<T, I>(iter: I) -> Option<T>
+where
+    T: Checked,
+    I: IntoIterator<Item = Option<T>>,
+{
+    let mut total = T::ONE;
+    for n in iter {
+        total = match n {
+            Some(n) => total.checked_mul(n)?,
+            None => return None,
+        };
+    }
+    Some(total)
+}
+
+/// A pair, if there are both halves.
+///
+/// Same as `a.zip(b)`.
+pub fn zip<A, B>(a: Option<A>, b: Option<B>) -> Option<(A, B)> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some((a, b)),
+        _ => None,
+    }
+}
+
+/// The halves of a pair, both there or both not.
+///
+/// Same as `pair.unzip()`.
+pub fn unzip<A, B>(pair: Option<(A, B)>) -> (Option<A>, Option<B>) {
+    match pair {
+        Some((a, b)) => (Some(a), Some(b)),
+        None => (None, None),
+    }
+}
+
+/// Both added, or the first error, or [`Overflow`].
+///
+/// Same as `Ok(a?.checked_add(b?).ok_or(Overflow)?)`.
+pub fn try_add<T, E>(a: Result<T, E>, b: Result<T, E>) -> Result<T, E>
+where
+    T: Checked,
+    E: From<Overflow>,
+{
+    match (a, b) {
+        (Ok(a), Ok(b)) => match a.checked_add(b) {
+            Some(sum) => Ok(sum),
+            None => Err(E::from(Overflow)),
+        },
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    }
+}
+
+/// Both multiplied, or the first error, or [`Overflow`].
+///
+/// Same as `Ok(a?.checked_mul(b?).ok_or(Overflow)?)`.
+pub fn try_mul<T, E>(a: Result<T, E>, b: Result<T, E>) -> Result<T, E>
+where
+    T: Checked,
+    E: From<Overflow>,
+{
+    match (a, b) {
+        (Ok(a), Ok(b)) => match a.checked_mul(b) {
+            Some(product) => Ok(product),
+            None => Err(E::from(Overflow)),
+        },
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    }
+}
+
+/// Everything added up, stopping at the first error or at [`Overflow`].
+///
+/// Same as `iter.try_fold(0, |sum, n| sum.checked_add(n?).ok_or(Overflow))`
+/// with the errors converted.
+pub fn try_sum<T, E, I>(iter: I) -> Result<T, E>
+where
+    T: Checked,
+    E: From<Overflow>,
+    I: IntoIterator<Item = Result<T, E>>,
+{
+    let mut total = T::ZERO;
+    for n in iter {
+        total = match n {
+            Ok(n) => match total.checked_add(n) {
+                Some(sum) => sum,
+                None => return Err(E::from(Overflow)),
+            },
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(total)
+}
+
+/// Everything multiplied, like [`try_sum`].
+///
+/// Same as `iter.try_fold(1, |product, n| product.checked_mul(n?).ok_or(Overflow))`
+/// with the errors converted.
+pub fn try_product<T, E, I>(iter: I) -> Result<T, E>
+where
+    T: Checked,
+    E: From<Overflow>,
+    I: IntoIterator<Item = Result<T, E>>,
+{
+    let mut total = T::ONE;
+    for n in iter {
+        total = match n {
+            Ok(n) => match total.checked_mul(n) {
+                Some(product) => product,
+                None => return Err(E::from(Overflow)),
+            },
+            Err(e) => return Err(e),
+        };
+    }
+    Ok(total)
+}
+
+/// A pair, or the first half's error if both failed.
+///
+/// Same as `Ok((a?, b?))`, or `a.and_then(|a| b.map(|b| (a, b)))`.
+pub fn try_zip<A, B, E>(a: Result<A, E>, b: Result<B, E>) -> Result<(A, B), E> {
+    match (a, b) {
+        (Ok(a), Ok(b)) => Ok((a, b)),
+        (Err(e), _) | (_, Err(e)) => Err(e),
+    }
+}
+
+/// The halves of a pair, or the error twice.
+///
+/// Same as `(pair.clone().map(|(a, _)| a), pair.map(|(_, b)| b))`.
+pub fn try_unzip<A, B, E: Clone>(pair: Result<(A, B), E>) -> (Result<A, E>, Result<B, E>) {
+    match pair {
+        Ok((a, b)) => (Ok(a), Ok(b)),
+        Err(e) => (Err(e.clone()), Err(e)),
+    }
+}
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+impl Error for Overflow {}
@@ -12,7 +12,7 @@ fn enum_basics() {
         enum IpAddrKind {
             V4,
             V6,
-        };
+        }
 
         // declaration:
         let _four = IpAddrKind::V4;
@@ -28,8 +28,135 @@ fn enum_basics() {
             V6(String), // tuple struct
         }
 
-        let _home = IpAddr::V4(String::from("127.0.0.1"));
-        let _loopback = IpAddr::V6(String::from("::1"));
+        let home = IpAddr::V4(String::from("127.0.0.1"));
+        let loopback = IpAddr::V6(String::from("::1"));
+
+        // the catch: any String will do, this is an "address" too
+        let banana = IpAddr::V4(String::from("banana"));
+        for addr in &[home, loopback, banana] {
+            match addr {
+                IpAddr::V4(s) => println!("[enum_basics] v4 {}", s),
+                IpAddr::V6(s) => println!("[enum_basics] v6 {}", s),
+            }
+        }
+    }
+
+    // the same enum in src/ip.rs holds the parsed numbers instead, so the
+    // checking happens once, when the string gets parsed
+    {
+        use ch6_enum_patternmatch::ip::{AddrParseError, IpAddr, IpAddrKind};
+
+        let home: IpAddr = "127.0.0.1".parse().unwrap();
+        assert_eq!(home, IpAddr::V4([127, 0, 0, 1]));
+        assert_eq!(home.kind(), IpAddrKind::V4);
+        assert!(home.is_loopback());
+        assert_eq!(
+            "banana".parse::<IpAddr>(),
+            Err(AddrParseError::OctetCount(1))
+        );
+        assert_eq!(
+            "127.0.0.256".parse::<IpAddr>(),
+            Err(AddrParseError::Octet(String::from("256")))
+        );
+
+        // V6 gets printed the short way, however it was written
+        let loopback: IpAddr = "0000:0000:0000:0000:0000:0000:0000:0001".parse().unwrap();
+        assert_eq!(loopback, IpAddr::V6([0, 0, 0, 0, 0, 0, 0, 1]));
+        assert_eq!(loopback.to_string(), "::1");
+        let doc: IpAddr = "2001:DB8:0:0:1:0:0:1".parse().unwrap();
+        assert_eq!(doc.to_string(), "2001:db8::1:0:0:1");
+
+        // matching on the variants still works like it does above
+        for addr in &[home, loopback, doc] {
+            match addr {
+                IpAddr::V4([a, b, c, d]) => {
+                    println!("[enum_basics] v4 {} has octets {:?}", addr, [a, b, c, d])
+                }
+                IpAddr::V6(segments) => {
+                    println!("[enum_basics] v6 {} has segments {:x?}", addr, segments)
+                }
+            }
+        }
+    }
+
+    // networks, written the CIDR way
+    {
+        use ch6_enum_patternmatch::ip::{Cidr, IpAddr};
+
+        let lan: Cidr = "192.168.1.0/24".parse().unwrap();
+        let printer: IpAddr = "192.168.1.20".parse().unwrap();
+        assert!(lan.contains(&printer));
+        assert!(printer.is_private());
+        assert_eq!(lan.netmask().to_string(), "255.255.255.0");
+        assert_eq!(lan.last().to_string(), "192.168.1.255");
+        assert_eq!(lan.size(), Some(256));
+
+        let quarters: Vec<String> = lan.subnets(26).unwrap().map(|c| c.to_string()).collect();
+        assert_eq!(
+            quarters,
+            [
+                "192.168.1.0/26",
+                "192.168.1.64/26",
+                "192.168.1.128/26",
+                "192.168.1.192/26"
+            ]
+        );
+        let link_local: Cidr = "fe80::/10".parse().unwrap();
+        assert!(link_local.contains(&"fe80::1".parse().unwrap()));
+        assert!(!link_local.contains(&printer)); // wrong version altogether
+        println!(
+            "[enum_basics] {} has {} addresses, split into /26s: {:?}",
+            lan,
+            lan.size().unwrap(),
+            quarters
+        );
+    }
+
+    // an allow list, say for who gets to talk to a local test server. the
+    // most specific rule wins, so the lab is let in even though the rest of
+    // 10/8 isn't
+    {
+        use ch6_enum_patternmatch::acl::{Acl, Action, LoadError, RuleError};
+        use std::fs;
+
+        let rules = "\
+            # who gets to reach the test services\n\
+            default deny\n\
+            allow 127.0.0.0/8\n\
+            allow ::1\n\
+            deny 10.0.0.0/8\n\
+            allow 10.1.0.0/16   # the lab\n\
+            deny 10.1.99.7      # except that one flaky box\n\
+            allow fd00::/8\n";
+        let path = std::env::temp_dir().join("ch6_test_services.acl");
+        fs::write(&path, rules).unwrap();
+        let acl = Acl::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(acl.len(), 6);
+        let check = |addr: &str| acl.check(&addr.parse().unwrap());
+        assert_eq!(check("127.0.0.1"), Action::Allow);
+        assert_eq!(check("::1"), Action::Allow);
+        assert_eq!(check("10.1.2.3"), Action::Allow);
+        assert_eq!(check("10.1.99.7"), Action::Deny);
+        assert_eq!(check("10.2.3.4"), Action::Deny);
+        assert_eq!(check("fd12::5"), Action::Allow);
+        assert_eq!(check("8.8.8.8"), Action::Deny); // nothing matched, the default
+        let rule = acl.matching_rule(&"10.1.2.3".parse().unwrap()).unwrap();
+        assert_eq!(rule.to_string(), "allow 10.1.0.0/16");
+
+        // mistakes say which line they're on
+        match Acl::read_from("allow 10.0.0.0/8\nallow 10.0.0.0/8\n".as_bytes()) {
+            Err(LoadError::Rule { line: 2, source }) => {
+                assert!(matches!(source, RuleError::Duplicate { first_line: 1, .. }))
+            }
+            other => panic!("expected a duplicate, got {:?}", other),
+        }
+        let err = Acl::read_from("permit 10.0.0.0/8".as_bytes()).unwrap_err();
+        println!(
+            "[enum_basics] 10.1.2.3 matched \"{}\", bad file: {}",
+            rule, err
+        );
     }
 
     // fancier enum declarations
@@ -49,6 +176,94 @@ fn enum_basics() {
             }
         }
     }
+
+    // the same enum in src/message.rs actually gets made and used, as the
+    // commands of a little protocol
+    {
+        use ch6_enum_patternmatch::message::{Message, ParseError, State};
+
+        let script =
+            "move 3 -4\nwrite hello\\nthere\ncolor 255 128 0\nmove 1 1\nquit\nwrite too late";
+        let messages: Vec<Message> = script.lines().map(|l| l.parse().unwrap()).collect();
+        assert_eq!(messages[0], Message::Move { x: 3, y: -4 });
+        assert_eq!(messages[1], Message::Write(String::from("hello\nthere")));
+
+        // each prints back as the line it came from
+        for (line, message) in script.lines().zip(&messages) {
+            assert_eq!(message.to_string(), line);
+        }
+
+        // the binary form is shorter for numbers, and self delimiting, so
+        // messages can go back to back
+        let mut bytes = Vec::new();
+        for message in &messages {
+            message.encode_into(&mut bytes);
+        }
+        assert_eq!(Message::decode_all(&bytes).unwrap(), messages);
+        assert_eq!(
+            Message::Move { x: 3, y: -4 }.encode(),
+            [1, 0, 0, 0, 3, 255, 255, 255, 252]
+        );
+
+        let mut state = State::new();
+        let applied = state.run(&messages);
+        assert_eq!(applied, 5); // the write after quit is ignored
+        assert_eq!(state.position, (4, -3));
+        assert_eq!(state.log, ["hello\nthere"]);
+        assert_eq!(state.color, (255, 128, 0));
+        assert!(state.has_quit());
+
+        assert_eq!(
+            "move 3".parse::<Message>(),
+            Err(ParseError::MissingArgument("y"))
+        );
+        let err = "jump 1 2".parse::<Message>().unwrap_err();
+        println!(
+            "[enum_basics] ended at {:?} after {} messages, bad line: {}",
+            state.position, applied, err
+        );
+    }
+
+    // messages between threads. a mover that only cares where things go,
+    // a logger that only cares what's written, and a Quit to stop both
+    {
+        use ch6_enum_patternmatch::bus::{Bus, Filter};
+        use ch6_enum_patternmatch::message::{Message, MessageKind};
+        use std::thread;
+
+        let bus = Bus::new();
+        let moves = bus.subscribe(Filter::only(MessageKind::Move));
+        let mover = thread::spawn(move || {
+            moves.fold((0, 0), |(x, y), message| match message {
+                Message::Move { x: dx, y: dy } => (x + dx, y + dy),
+                _ => (x, y),
+            })
+        });
+        let writes = bus.subscribe(Filter::only(MessageKind::Write));
+        let logger = thread::spawn(move || writes.count());
+
+        let sender = bus.clone();
+        thread::spawn(move || {
+            sender.publish(Message::Move { x: 1, y: 2 }).unwrap();
+            sender.publish(Message::Write(String::from("hi"))).unwrap();
+            sender.publish(Message::Move { x: 3, y: 4 }).unwrap();
+        })
+        .join()
+        .unwrap();
+        bus.publish(Message::Quit).unwrap();
+
+        let position = mover.join().unwrap();
+        let logged = logger.join().unwrap();
+        assert_eq!(position, (4, 6));
+        assert_eq!(logged, 1);
+        assert!(bus
+            .publish(Message::Write(String::from("anyone?")))
+            .is_err());
+        println!(
+            "[enum_basics] the mover ended at {:?}, the logger saw {} write",
+            position, logged
+        );
+    }
 }
 
 fn option() {
@@ -70,13 +285,9 @@ fn option() {
 
 fn match_exp() {
     {
-        #[derive(Debug)]
-        enum UsState {
-            // today, we only care about states that start with C
-            California,
-            _Chicago,
-            _Conneticut,
-        }
+        // every state, DC and territory, with its quarter's details, lives
+        // in src/money/us_state.rs now
+        use ch6_enum_patternmatch::money::UsState;
 
         enum Coin {
             Penny,
@@ -99,7 +310,13 @@ fn match_exp() {
                     Coin::_Dime => 10,
                     Coin::Quarter(s) => {
                         // s is the state of the quarter, matched in the pattern
-                        println!("[to_cents] This quarter is from {:?}", s);
+                        println!(
+                            "[to_cents] This quarter is from {} ({}), number {} of 56, released in {}",
+                            s,
+                            s.abbreviation(),
+                            s.quarter_number(),
+                            s.quarter_year()
+                        );
                         25
                     }
                 }
@@ -114,10 +331,148 @@ fn match_exp() {
             "[match_exp] the value of a quarter is {}",
             Coin::Quarter(UsState::California).to_cents()
         );
+
+        // parsed from either the postal code or the name
+        let from_abbreviation: UsState = "CT".parse().unwrap();
+        let from_name: UsState = "connecticut".parse().unwrap();
+        assert_eq!(from_abbreviation, UsState::Connecticut);
+        assert_eq!(from_name, UsState::Connecticut);
+        assert!("Chicago".parse::<UsState>().is_err()); // a city, not a state
+        assert_eq!(UsState::Connecticut.quarter_year(), 1999);
+        assert_eq!(UsState::PuertoRico.quarter_year(), 2009);
+        assert!(!UsState::PuertoRico.is_state());
+        println!(
+            "[match_exp] the quarter after {} was {}",
+            UsState::Connecticut,
+            UsState::ALL[UsState::Connecticut.quarter_number()]
+        );
+    }
+
+    // the u8 above tops out at 255 cents, so eleven quarters won't fit.
+    // src/money.rs counts in u64 and knows every coin and bill
+    {
+        use ch6_enum_patternmatch::money::{
+            greedy_change, greedy_is_optimal, optimal_change, Bill, Coin, Purse,
+        };
+
+        let mut purse: Purse = vec![Coin::Quarter; 11].into_iter().collect();
+        purse.add(Coin::HalfDollar);
+        purse.add_many(Bill::Twenty, 2);
+        assert_eq!(purse.total(), 11 * 25 + 50 + 4000);
+        assert_eq!(purse.pieces(), 14);
+        assert!(purse.remove(Coin::Dime).is_err());
+
+        // the fewest pieces for $6.91, the way a cashier would
+        let change = Purse::change_for(691);
+        assert_eq!(
+            change.to_string(),
+            "$6.91: 1 x $5 bill, 1 x $1 bill, 1 x half dollar, 1 x quarter, 1 x dime, 1 x nickel, 1 x penny"
+        );
+
+        // greedy is only always best for some coin systems. US coins are
+        // one, but with 1, 3 and 4 it makes 6 out of three coins, not two
+        let cents: Vec<u64> = Coin::ALL.iter().map(|c| c.to_cents()).collect();
+        assert!(greedy_is_optimal(&cents));
+        assert!(!greedy_is_optimal(&[1u64, 3, 4]));
+        assert_eq!(greedy_change(6, &[1u64, 3, 4]), Some(vec![(4, 1), (1, 2)]));
+        assert_eq!(optimal_change(6, &[1u64, 3, 4]), Some(vec![(3, 2)]));
+        println!("[match_exp] {}", purse);
+    }
+
+    // and money that isn't dollars, from src/money/currency.rs. amounts
+    // know their currency, so adding euros to yen is an error, not a number
+    {
+        use ch6_enum_patternmatch::money::{Currency, ExchangeRates, Money, MoneyError, Rounding};
+        use std::fs;
+
+        let lunch = Money::new(Currency::Eur, 1250);
+        let tip = Money::new(Currency::Eur, 175);
+        let bill = lunch.add(&tip).unwrap();
+        assert_eq!(bill.to_string(), "€14.25");
+        let sushi = Money::new(Currency::Jpy, 1800);
+        assert_eq!(sushi.to_string(), "¥1800"); // yen have no cents
+        assert!(matches!(bill.add(&sushi), Err(MoneyError::Mismatch { .. })));
+
+        // rates come from a file, and every conversion says how to round
+        let path = std::env::temp_dir().join("ch6_rates.txt");
+        fs::write(&path, "USD EUR 0.92\nUSD JPY 149.5\n").unwrap();
+        let rates = ExchangeRates::load(&path).unwrap();
+        fs::remove_file(&path).unwrap();
+
+        let in_dollars =
+            |money: &Money, rounding| rates.convert(money, Currency::Usd, rounding).unwrap();
+        assert_eq!(in_dollars(&bill, Rounding::Down).to_string(), "$15.48");
+        assert_eq!(in_dollars(&bill, Rounding::Up).to_string(), "$15.49");
+        assert_eq!(in_dollars(&sushi, Rounding::HalfEven).to_string(), "$12.04");
+        assert!(rates.convert(&bill, Currency::Gbp, Rounding::Down).is_err());
+        println!(
+            "[match_exp] {} and {} come to about {} and {}",
+            bill,
+            sushi,
+            in_dollars(&bill, Rounding::HalfEven),
+            in_dollars(&sushi, Rounding::HalfEven)
+        );
+    }
+
+    // a vending machine is a match on (state, event) in src/vending.rs,
+    // cargo run --bin vending to play with one
+    {
+        use ch6_enum_patternmatch::money::Coin;
+        use ch6_enum_patternmatch::vending::{Machine, Output, State, VendError};
+
+        let mut machine = Machine::new();
+        machine.stock("A1", "crisps", 65, 2);
+        machine.load_coins(Coin::Dime, 5);
+
+        // pennies bounce straight back out
+        assert_eq!(
+            machine.insert(Coin::Penny),
+            Ok(vec![Output::Rejected(Coin::Penny)])
+        );
+        assert_eq!(machine.state(), &State::Idle);
+
+        machine.insert(Coin::Quarter).unwrap();
+        assert_eq!(
+            machine.select("A1"),
+            Err(VendError::NeedMore {
+                price: 65,
+                credit: 25
+            })
+        );
+        machine.insert(Coin::Dollar).unwrap();
+        assert_eq!(machine.credit(), 125);
+
+        // 60 cents back can't be made from five dimes and a quarter, so the
+        // sale is refused and the credit stays put
+        assert_eq!(
+            machine.select("A1"),
+            Err(VendError::ExactChangeOnly { change: 60 })
+        );
+        assert_eq!(machine.credit(), 125);
+
+        // with a nickel it can, in the fewest coins
+        machine.load_coins(Coin::Nickel, 2);
+        let outputs = machine.select("A1").unwrap();
+        assert_eq!(outputs[0], Output::Dispensed(String::from("crisps")));
+        assert_eq!(
+            outputs[1].coins(),
+            vec![
+                Coin::Quarter,
+                Coin::Dime,
+                Coin::Dime,
+                Coin::Dime,
+                Coin::Nickel
+            ]
+        );
+        assert_eq!(machine.state(), &State::Idle);
+        println!("[match_exp] vending: {}", outputs[1]);
     }
 
     // Option<T>
     {
+        // clippy would rather this be x.map(|n| n + 1), which does the same
+        // thing, but the point here is the match
+        #[allow(clippy::manual_map)]
         fn inc(x: Option<i32>) -> Option<i32> {
             match x {
                 Some(n) => Some(n + 1), // do an inc
@@ -127,6 +482,89 @@ fn match_exp() {
 
         assert_eq!(Some(6), inc(Some(5)));
         assert_eq!(None, inc(None));
+
+        // the n + 1 in there panics on i32::MAX in a debug build. src/lift.rs
+        // has inc for any integer, giving None when it would overflow, plus
+        // add, mul, sum and friends done the same way
+        use ch6_enum_patternmatch::lift::{self, Overflow};
+
+        assert_eq!(lift::inc(Some(5)), Some(6));
+        assert_eq!(lift::inc(Some(i32::MAX)), None);
+        assert_eq!(lift::inc(Some(255u8)), None);
+
+        // each one next to the way std already spells it, which is what
+        // real code should write
+        let (a, b, none) = (Some(200u8), Some(55u8), None::<u8>);
+        assert_eq!(lift::inc(a), a.and_then(|n| n.checked_add(1)));
+        assert_eq!(lift::add(a, b), Some(255));
+        assert_eq!(
+            lift::add(a, b),
+            a.and_then(|a| b.and_then(|b| a.checked_add(b)))
+        );
+        assert_eq!(lift::mul(a, b), None); // 11000 is no u8
+        assert_eq!(lift::add(a, none), None);
+        // ? does the same inside a function returning Option
+        fn add_with_question_mark(a: Option<u8>, b: Option<u8>) -> Option<u8> {
+            a?.checked_add(b?)
+        }
+        assert_eq!(lift::add(a, b), add_with_question_mark(a, b));
+        assert_eq!(lift::add(none, b), add_with_question_mark(none, b));
+
+        let readings = vec![Some(3u32), Some(4), Some(5)];
+        let folded = readings
+            .iter()
+            .try_fold(0u32, |sum, n| sum.checked_add((*n)?));
+        assert_eq!(lift::sum(readings.clone()), Some(12));
+        assert_eq!(lift::sum(readings.clone()), folded);
+        assert_eq!(lift::product(readings.clone()), Some(60));
+        assert_eq!(lift::sum(vec![Some(1u32), None]), None);
+        // std's own Sum for Option gives up on None too, but panics or wraps
+        // on overflow instead
+        assert_eq!(readings.iter().copied().sum::<Option<u32>>(), Some(12));
+
+        assert_eq!(lift::zip(a, b), a.zip(b));
+        assert_eq!(lift::unzip(lift::zip(a, b)), (a, b));
+
+        // the Result ones stop at the first error, and turn overflow into
+        // one. anything with a From<Overflow> can be the error
+        #[derive(Debug, PartialEq)]
+        enum ReadError {
+            Garbled(&'static str),
+            TooBig,
+        }
+        impl From<Overflow> for ReadError {
+            fn from(_: Overflow) -> ReadError {
+                ReadError::TooBig
+            }
+        }
+        let parse = |s: &'static str| s.parse::<i16>().map_err(|_| ReadError::Garbled(s));
+
+        let total: Result<i16, ReadError> =
+            lift::try_sum(vec!["10", "20", "30"].into_iter().map(parse));
+        assert_eq!(total, Ok(60));
+        assert_eq!(
+            lift::try_sum(vec!["10", "x", "y"].into_iter().map(parse)),
+            Err(ReadError::Garbled("x"))
+        );
+        assert_eq!(
+            lift::try_mul(parse("300"), parse("300")),
+            Err(ReadError::TooBig)
+        );
+        fn mul_with_question_mark(
+            a: Result<i16, ReadError>,
+            b: Result<i16, ReadError>,
+        ) -> Result<i16, ReadError> {
+            Ok(a?.checked_mul(b?).ok_or(Overflow)?)
+        }
+        assert_eq!(
+            lift::try_mul(parse("-12"), parse("12")),
+            mul_with_question_mark(parse("-12"), parse("12"))
+        );
+        println!(
+            "[match_exp] lifted sum {:?}, product {:?}",
+            total,
+            lift::product(readings)
+        );
     }
 
     // exhaustive patterns/ using _
@@ -151,4 +589,58 @@ fn match_exp() {
             println!("still, no one cares about non-ones");
         }
     }
+
+    // matching on a whole tree at once: src/expr.rs is a little language
+    // whose evaluator matches (operator, left value, right value), so
+    // (Div, Int(_), Int(0)) is all it takes to catch division by zero.
+    // cargo run --bin calc for a REPL
+    {
+        use ch6_enum_patternmatch::expr::{BinOp, Env, EvalError, Expr, Type, Value};
+
+        let mut env = Env::new();
+        env.set("r", Value::Int(7));
+        let expr: Expr = "if r * r > 40 && !(r % 2 == 0) then r * 3 else -1"
+            .parse()
+            .unwrap();
+        assert_eq!(expr.eval(&env), Ok(Value::Int(21)));
+
+        // the parser gets precedence right, and printing puts back only
+        // the brackets that matter
+        let sum: Expr = "1 + (2 * x)".parse().unwrap();
+        assert_eq!(
+            sum,
+            Expr::binary(
+                BinOp::Add,
+                Expr::int(1),
+                Expr::binary(BinOp::Mul, Expr::int(2), Expr::var("x"))
+            )
+        );
+        assert_eq!(sum.to_string(), "1 + 2 * x");
+
+        // errors are an enum too
+        let eval = |src: &str| src.parse::<Expr>().unwrap().eval(&env);
+        assert_eq!(eval("r / (r - 7)"), Err(EvalError::DivisionByZero));
+        assert_eq!(
+            eval("r + true"),
+            Err(EvalError::TypeMismatch {
+                op: "+",
+                expected: Type::Int,
+                found: Type::Bool
+            })
+        );
+        assert_eq!(
+            eval("q"),
+            Err(EvalError::UnboundVariable(String::from("q")))
+        );
+
+        // folding works out the constant parts ahead of time
+        let folded = "let two = 2 in two * 3 + r".parse::<Expr>().unwrap().fold();
+        assert_eq!(folded.to_string(), "6 + r");
+        println!(
+            "[match_exp] {} = {:?}, folded {}",
+            expr,
+            expr.eval(&env),
+            folded
+        );
+    }
 }
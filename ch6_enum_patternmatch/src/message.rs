@@ -0,0 +1,106 @@
+// the _Message enum from enum_basics() in main.rs, put to work as the
+// commands of a tiny protocol. each message can go over the wire two ways:
+//
+// - text, one message per line, for typing at a terminal (see message/text.rs)
+//       quit
+//       move 3 -4
+//       write hello there
+//       color 255 128 0
+// - binary, a tag byte and big endian numbers, for programs talking to each
+//   other (see message/binary.rs)
+//
+// and State is what messages get applied to, through the one match that
+// decides what each variant means
+mod binary;
+mod text;
+
+pub use self::binary::DecodeError;
+pub use self::text::ParseError;
+
+/// A command for a [`State`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Message {
+    /// Stop, nothing after this gets applied.
+    Quit,
+    /// Move by this much (not to here).
+    Move {
+        x: i32,
+        y: i32,
+    },
+    /// Add a line to the log.
+    Write(String),
+    ChangeColor(i32, i32, i32),
+}
+
+/// Which variant a message is, without its fields.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessageKind {
+    Quit,
+    Move,
+    Write,
+    ChangeColor,
+}
+
+impl Message {
+    pub fn kind(&self) -> MessageKind {
+        match self {
+            Message::Quit => MessageKind::Quit,
+            Message::Move { .. } => MessageKind::Move,
+            Message::Write(_) => MessageKind::Write,
+            Message::ChangeColor(..) => MessageKind::ChangeColor,
+        }
+    }
+}
+
+/// What messages act on.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct State {
+    pub position: (i32, i32),
+    pub log: Vec<String>,
+    pub color: (i32, i32, i32),
+    quit: bool,
+}
+
+impl State {
+    /// At the origin, black, with nothing logged.
+    pub fn new() -> State {
+        State::default()
+    }
+
+    /// Whether a [`Message::Quit`] has been applied.
+    pub fn has_quit(&self) -> bool {
+        self.quit
+    }
+
+    /// Does what `message` says. Returns false, changing nothing, once the
+    /// state has quit.
+    pub fn apply(&mut self, message: &Message) -> bool {
+        if self.quit {
+            return false;
+        }
+        match message {
+            Message::Quit => self.quit = true,
+            // saturating, so a silly move stops at the edge instead of
+            // panicking
+            Message::Move { x, y } => {
+                self.position.0 = self.position.0.saturating_add(*x);
+                self.position.1 = self.position.1.saturating_add(*y);
+            }
+            Message::Write(text) => self.log.push(text.clone()),
+            Message::ChangeColor(r, g, b) => self.color = (*r, *g, *b),
+        }
+        true
+    }
+
+    /// Applies messages in order until one is a quit, and says how many got
+    /// applied (the quit included).
+    pub fn run<'a, I>(&mut self, messages: I) -> usize
+    where
+        I: IntoIterator<Item = &'a Message>,
+    {
+        messages
+            .into_iter()
+            .take_while(|message| self.apply(message))
+            .count()
+    }
+}
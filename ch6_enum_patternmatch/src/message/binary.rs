@@ -0,0 +1,151 @@
+// the binary form. a tag byte says which variant, then its fields, numbers
+// as 4 byte big endian:
+//
+//     quit    0
+//     move    1, x, y
+//     write   2, length (a u32), that many bytes of UTF-8
+//     color   3, red, green, blue
+//
+// messages carry their own length, so they can be sent back to back and
+// pulled apart again with decode_all
+use super::Message;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str;
+
+const QUIT: u8 = 0;
+const MOVE: u8 = 1;
+const WRITE: u8 = 2;
+const COLOR: u8 = 3;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The bytes ran out part way through a message.
+    UnexpectedEnd,
+    /// A first byte that isn't one of the four tags.
+    UnknownTag(u8),
+    /// The text of a write isn't UTF-8.
+    Utf8(str::Utf8Error),
+}
+
+impl Message {
+    /// The binary form. Panics for a write over 4GiB, whose length doesn't
+    /// fit in the u32.
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        self.encode_into(&mut bytes);
+        bytes
+    }
+
+    /// [`Message::encode`], added on to the end of `bytes`.
+    pub fn encode_into(&self, bytes: &mut Vec<u8>) {
+        match self {
+            Message::Quit => bytes.push(QUIT),
+            Message::Move { x, y } => {
+                bytes.push(MOVE);
+                bytes.extend_from_slice(&x.to_be_bytes());
+                bytes.extend_from_slice(&y.to_be_bytes());
+            }
+            Message::Write(text) => {
+                let len = u32::try_from(text.len()).expect("write too long to encode");
+                bytes.push(WRITE);
+                bytes.extend_from_slice(&len.to_be_bytes());
+                bytes.extend_from_slice(text.as_bytes());
+            }
+            Message::ChangeColor(r, g, b) => {
+                bytes.push(COLOR);
+                for n in &[r, g, b] {
+                    bytes.extend_from_slice(&n.to_be_bytes());
+                }
+            }
+        }
+    }
+
+    /// The message at the start of `bytes`, and how many bytes it took up.
+    pub fn decode(bytes: &[u8]) -> Result<(Message, usize), DecodeError> {
+        let mut reader = Reader { bytes, pos: 0 };
+        let message = match reader.u8()? {
+            QUIT => Message::Quit,
+            MOVE => Message::Move {
+                x: reader.i32()?,
+                y: reader.i32()?,
+            },
+            WRITE => {
+                let len = reader.u32()? as usize;
+                let text = str::from_utf8(reader.take(len)?).map_err(DecodeError::Utf8)?;
+                Message::Write(text.to_string())
+            }
+            COLOR => Message::ChangeColor(reader.i32()?, reader.i32()?, reader.i32()?),
+            tag => return Err(DecodeError::UnknownTag(tag)),
+        };
+        Ok((message, reader.pos))
+    }
+
+    /// Every message in `bytes`, which has to end where the last one does.
+    pub fn decode_all(mut bytes: &[u8]) -> Result<Vec<Message>, DecodeError> {
+        let mut messages = Vec::new();
+        while !bytes.is_empty() {
+            let (message, used) = Message::decode(bytes)?;
+            messages.push(message);
+            bytes = &bytes[used..];
+        }
+        Ok(messages)
+    }
+}
+
+// reads from the front of a slice, keeping track of how far it's got
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], DecodeError> {
+        let end = self
+            .pos
+            .checked_add(n)
+            .filter(|&end| end <= self.bytes.len())
+            .ok_or(DecodeError::UnexpectedEnd)?;
+        let taken = &self.bytes[self.pos..end];
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array(&mut self) -> Result<[u8; 4], DecodeError> {
+        let mut array = [0; 4];
+        array.copy_from_slice(self.take(4)?);
+        Ok(array)
+    }
+
+    fn u32(&mut self) -> Result<u32, DecodeError> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn i32(&mut self) -> Result<i32, DecodeError> {
+        self.array().map(i32::from_be_bytes)
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DecodeError::UnexpectedEnd => write!(f, "the message was cut off"),
+            DecodeError::UnknownTag(tag) => write!(f, "{} isn't a message tag", tag),
+            DecodeError::Utf8(e) => write!(f, "the text isn't UTF-8: {}", e),
+        }
+    }
+}
+
+impl Error for DecodeError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DecodeError::Utf8(e) => Some(e),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+// the text form, one message per line. a command word and its arguments,
+// separated by spaces. write takes the rest of the line as is (after the one
+// space), with \n and \\ standing in for a newline and a backslash so any
+// string fits on one line
+use super::Message;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// A blank line.
+    Empty,
+    /// A first word that isn't quit, move, write or color.
+    UnknownCommand(String),
+    /// Fewer arguments than the command takes, naming the first one missing.
+    MissingArgument(&'static str),
+    /// An argument that isn't an i32.
+    Number(String),
+    /// More arguments than the command takes.
+    Trailing(String),
+    /// A backslash followed by something other than n or \.
+    Escape(String),
+}
+
+impl Message {
+    /// The command word this message starts with in the text form.
+    pub fn command(&self) -> &'static str {
+        match self {
+            Message::Quit => "quit",
+            Message::Move { .. } => "move",
+            Message::Write(_) => "write",
+            Message::ChangeColor(..) => "color",
+        }
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn unescape(text: &str) -> Result<String, ParseError> {
+    let mut unescaped = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            unescaped.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('\\') => unescaped.push('\\'),
+            Some('n') => unescaped.push('\n'),
+            Some(other) => return Err(ParseError::Escape(format!("\\{}", other))),
+            None => return Err(ParseError::Escape(String::from("\\"))),
+        }
+    }
+    Ok(unescaped)
+}
+
+// the whitespace separated numbers after a command, exactly as many as
+// there are names
+fn numbers<const N: usize>(args: &str, names: [&'static str; N]) -> Result<[i32; N], ParseError> {
+    let mut words = args.split_whitespace();
+    let mut values = [0; N];
+    for (value, name) in values.iter_mut().zip(names) {
+        let word = words.next().ok_or(ParseError::MissingArgument(name))?;
+        *value = word
+            .parse()
+            .map_err(|_| ParseError::Number(word.to_string()))?;
+    }
+    match words.next() {
+        Some(extra) => Err(ParseError::Trailing(extra.to_string())),
+        None => Ok(values),
+    }
+}
+
+/// One line of the text form, without the line ending.
+impl FromStr for Message {
+    type Err = ParseError;
+
+    fn from_str(line: &str) -> Result<Message, ParseError> {
+        let (command, args) = match line.find(' ') {
+            Some(space) => (&line[..space], &line[space + 1..]),
+            None => (line, ""),
+        };
+        match command {
+            "" if args.trim().is_empty() => Err(ParseError::Empty),
+            "quit" => numbers(args, []).map(|[]| Message::Quit),
+            "move" => numbers(args, ["x", "y"]).map(|[x, y]| Message::Move { x, y }),
+            "write" => unescape(args).map(Message::Write),
+            "color" => numbers(args, ["red", "green", "blue"])
+                .map(|[r, g, b]| Message::ChangeColor(r, g, b)),
+            _ => Err(ParseError::UnknownCommand(command.to_string())),
+        }
+    }
+}
+
+/// The text form, with no newline on the end.
+impl fmt::Display for Message {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.command())?;
+        match self {
+            Message::Quit => Ok(()),
+            Message::Move { x, y } => write!(f, " {} {}", x, y),
+            Message::Write(text) => write!(f, " {}", escape(text)),
+            Message::ChangeColor(r, g, b) => write!(f, " {} {} {}", r, g, b),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::Empty => write!(f, "empty line"),
+            ParseError::UnknownCommand(word) => {
+                write!(f, "{:?} isn't a command (quit, move, write or color)", word)
+            }
+            ParseError::MissingArgument(name) => write!(f, "missing the {} argument", name),
+            ParseError::Number(word) => write!(f, "{:?} isn't a whole number", word),
+            ParseError::Trailing(word) => write!(f, "unexpected {:?} after the arguments", word),
+            ParseError::Escape(escape) => {
+                write!(f, "{:?} isn't an escape, only \\n and \\\\ are", escape)
+            }
+        }
+    }
+}
+
+impl Error for ParseError {}
@@ -0,0 +1,249 @@
+// the Coin enum from match_exp() in main.rs, grown up. that one knows four
+// coins and counts in a u8, which runs out at $2.55. here there's every US
+// coin and bill, amounts are u64 cents (enough for 184 quadrillion dollars),
+// a Purse to keep a tally in, and two ways of making change (money/change.rs)
+//
+// UsState (money/us_state.rs) is every state quarter there's been, for the
+// Coin::Quarter(UsState) in main.rs
+//
+// all of that is dollars. money/currency.rs has the euro, pound and yen as
+// well, with a Money type that keeps track of which one an amount is in, and
+// money/exchange.rs converts between them
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+mod change;
+mod currency;
+mod exchange;
+mod purse;
+mod us_state;
+
+pub use self::change::{
+    greedy_change, greedy_is_optimal, limited_change, optimal_change, Change, MAX_AMOUNT,
+};
+pub use self::currency::{Currency, CurrencyCoin, Money, MoneyError, UnknownCurrency};
+pub use self::exchange::{
+    ConvertError, ExchangeRates, LineError, LoadError, Rate, RateError, Rounding,
+};
+pub use self::purse::{NotEnough, Purse};
+pub use self::us_state::{UnknownState, UsState};
+
+/// Anything worth a whole number of cents, or of the minor unit of some
+/// other currency. Change can be made out of any of these, and plain `u64`s
+/// stand for made up denominations.
+pub trait Denomination: Copy {
+    fn to_cents(&self) -> u64;
+}
+
+/// The coins the US mint makes, smallest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Coin {
+    Penny,
+    Nickel,
+    Dime,
+    Quarter,
+    HalfDollar,
+    /// The gold colored one, Sacagawea or a president.
+    Dollar,
+}
+
+/// From parsing something that isn't a coin's name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCoin(pub String);
+
+/// The bills still printed, smallest first.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Bill {
+    One,
+    Two,
+    Five,
+    Ten,
+    Twenty,
+    Fifty,
+    Hundred,
+}
+
+/// A coin or a bill. Sorts by value, except that the dollar coin comes
+/// before the dollar bill they're worth the same.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Piece {
+    Coin(Coin),
+    Bill(Bill),
+}
+
+impl Coin {
+    pub const ALL: [Coin; 6] = [
+        Coin::Penny,
+        Coin::Nickel,
+        Coin::Dime,
+        Coin::Quarter,
+        Coin::HalfDollar,
+        Coin::Dollar,
+    ];
+
+    pub fn to_cents(&self) -> u64 {
+        match self {
+            Coin::Penny => 1,
+            Coin::Nickel => 5,
+            Coin::Dime => 10,
+            Coin::Quarter => 25,
+            Coin::HalfDollar => 50,
+            Coin::Dollar => 100,
+        }
+    }
+}
+
+impl Bill {
+    pub const ALL: [Bill; 7] = [
+        Bill::One,
+        Bill::Two,
+        Bill::Five,
+        Bill::Ten,
+        Bill::Twenty,
+        Bill::Fifty,
+        Bill::Hundred,
+    ];
+
+    pub fn dollars(&self) -> u64 {
+        match self {
+            Bill::One => 1,
+            Bill::Two => 2,
+            Bill::Five => 5,
+            Bill::Ten => 10,
+            Bill::Twenty => 20,
+            Bill::Fifty => 50,
+            Bill::Hundred => 100,
+        }
+    }
+
+    pub fn to_cents(&self) -> u64 {
+        self.dollars() * 100
+    }
+}
+
+impl Piece {
+    /// Every coin then every bill, smallest first.
+    pub const ALL: [Piece; 13] = [
+        Piece::Coin(Coin::Penny),
+        Piece::Coin(Coin::Nickel),
+        Piece::Coin(Coin::Dime),
+        Piece::Coin(Coin::Quarter),
+        Piece::Coin(Coin::HalfDollar),
+        Piece::Coin(Coin::Dollar),
+        Piece::Bill(Bill::One),
+        Piece::Bill(Bill::Two),
+        Piece::Bill(Bill::Five),
+        Piece::Bill(Bill::Ten),
+        Piece::Bill(Bill::Twenty),
+        Piece::Bill(Bill::Fifty),
+        Piece::Bill(Bill::Hundred),
+    ];
+
+    pub fn to_cents(&self) -> u64 {
+        match self {
+            Piece::Coin(coin) => coin.to_cents(),
+            Piece::Bill(bill) => bill.to_cents(),
+        }
+    }
+}
+
+impl Denomination for Coin {
+    fn to_cents(&self) -> u64 {
+        Coin::to_cents(self)
+    }
+}
+
+impl Denomination for Bill {
+    fn to_cents(&self) -> u64 {
+        Bill::to_cents(self)
+    }
+}
+
+impl Denomination for Piece {
+    fn to_cents(&self) -> u64 {
+        Piece::to_cents(self)
+    }
+}
+
+impl Denomination for u64 {
+    fn to_cents(&self) -> u64 {
+        *self
+    }
+}
+
+impl From<Coin> for Piece {
+    fn from(coin: Coin) -> Piece {
+        Piece::Coin(coin)
+    }
+}
+
+impl From<Bill> for Piece {
+    fn from(bill: Bill) -> Piece {
+        Piece::Bill(bill)
+    }
+}
+
+/// `$12.34` for an amount in cents.
+pub fn format_cents(cents: u64) -> String {
+    format!("${}.{:02}", cents / 100, cents % 100)
+}
+
+impl fmt::Display for Coin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Coin::Penny => "penny",
+            Coin::Nickel => "nickel",
+            Coin::Dime => "dime",
+            Coin::Quarter => "quarter",
+            Coin::HalfDollar => "half dollar",
+            Coin::Dollar => "dollar coin",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// The names [`Coin`] prints as, in any case, or `half` and `dollar` for
+/// short.
+impl FromStr for Coin {
+    type Err = UnknownCoin;
+
+    fn from_str(s: &str) -> Result<Coin, UnknownCoin> {
+        let short = match s.to_ascii_lowercase().as_str() {
+            "half" => Some(Coin::HalfDollar),
+            "dollar" => Some(Coin::Dollar),
+            _ => None,
+        };
+        short
+            .or_else(|| {
+                Coin::ALL
+                    .iter()
+                    .copied()
+                    .find(|coin| coin.to_string().eq_ignore_ascii_case(s))
+            })
+            .ok_or_else(|| UnknownCoin(s.to_string()))
+    }
+}
+
+impl fmt::Display for UnknownCoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} isn't a coin", self.0)
+    }
+}
+
+impl Error for UnknownCoin {}
+
+impl fmt::Display for Bill {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "${} bill", self.dollars())
+    }
+}
+
+impl fmt::Display for Piece {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Piece::Coin(coin) => coin.fmt(f),
+            Piece::Bill(bill) => bill.fmt(f),
+        }
+    }
+}
@@ -0,0 +1,209 @@
+// making change: which denominations, and how many of each, add up to an
+// amount. greedy and optimal can use as many of each as they like,
+// limited_change only what there is
+//
+// greedy takes as many of the biggest as fit, then the next biggest, and so
+// on. it's what a cashier does, it's quick, and for US money it always
+// finds the fewest pieces. for some sets it doesn't: with 1, 3 and 4, greedy
+// makes 6 as 4 + 1 + 1 when 3 + 3 is one coin fewer
+//
+// optimal is dynamic programming over every amount up to the one asked for,
+// so it's always right but costs time and memory in proportion to the
+// amount. fine for a till, not for a bank transfer, so it stops at
+// MAX_AMOUNT
+use super::Denomination;
+use std::convert::TryFrom;
+
+/// The most [`optimal_change`] and [`limited_change`] will make change for,
+/// $10,000 in cents. They need a table entry for every amount up to the one
+/// asked for, which past this gets too big to be worth it.
+pub const MAX_AMOUNT: u64 = 1_000_000;
+
+// an amount the tables can go up to, or None if it's too big
+fn table_size(amount: u64) -> Option<usize> {
+    if amount > MAX_AMOUNT {
+        return None;
+    }
+    usize::try_from(amount).ok()
+}
+
+/// How many of each denomination, biggest first, leaving out any there are
+/// none of.
+pub type Change<D> = Vec<(D, u64)>;
+
+// biggest first, without zeros (which would never add anything) and
+// without repeats (which would only split the count in two). of two worth
+// the same, the one that came first stays
+fn sorted<D: Denomination>(denominations: &[D]) -> Vec<D> {
+    let mut sorted: Vec<D> = denominations
+        .iter()
+        .copied()
+        .filter(|d| d.to_cents() > 0)
+        .collect();
+    sorted.sort_by_key(|d| std::cmp::Reverse(d.to_cents()));
+    sorted.dedup_by_key(|d| d.to_cents());
+    sorted
+}
+
+/// The cashier's way. `None` if it gets stuck with something left over,
+/// which can happen even when some other combination would work (like 6
+/// from 4 and 3).
+pub fn greedy_change<D: Denomination>(amount: u64, denominations: &[D]) -> Option<Change<D>> {
+    let mut left = amount;
+    let mut change = Vec::new();
+    for d in sorted(denominations) {
+        let count = left / d.to_cents();
+        if count > 0 {
+            change.push((d, count));
+            left -= count * d.to_cents();
+        }
+    }
+    if left == 0 {
+        Some(change)
+    } else {
+        None
+    }
+}
+
+/// The fewest pieces there can be, or `None` if the amount can't be made at
+/// all or is over [`MAX_AMOUNT`]. Ties go to using bigger denominations.
+pub fn optimal_change<D: Denomination>(amount: u64, denominations: &[D]) -> Option<Change<D>> {
+    let denominations = sorted(denominations);
+    let amount = table_size(amount)?;
+    let table = Table::new(amount, &denominations);
+    table.fewest[amount]?;
+
+    let mut counts = vec![0; denominations.len()];
+    let mut a = amount;
+    while a > 0 {
+        counts[table.last[a]] += 1;
+        a -= denominations[table.last[a]].to_cents() as usize;
+    }
+    Some(
+        denominations
+            .into_iter()
+            .zip(counts)
+            .filter(|&(_, count)| count > 0)
+            .collect(),
+    )
+}
+
+/// Whether [`greedy_change`] always uses as few pieces as
+/// [`optimal_change`] with these denominations. If it ever doesn't, the
+/// smallest amount where it goes wrong is below the two biggest
+/// denominations added together (Kozen and Zaks, 1994), so that's as far as
+/// this has to check.
+///
+/// That only holds when there's a 1 to make up any remainder, so without
+/// one this says no, even if greedy happens to be fine. It also says no when
+/// the two biggest add up to more than [`MAX_AMOUNT`], since that's too far
+/// to check.
+pub fn greedy_is_optimal<D: Denomination>(denominations: &[D]) -> bool {
+    let sorted = sorted(denominations);
+    if sorted.last().map(|d| d.to_cents()) != Some(1) {
+        return false;
+    }
+    let limit = match sorted.as_slice() {
+        [biggest, second, ..] => biggest.to_cents().checked_add(second.to_cents()),
+        // just the 1, there's no choice to get wrong
+        _ => return true,
+    };
+    let limit = match limit.and_then(table_size) {
+        Some(limit) => limit,
+        None => return false,
+    };
+    let table = Table::new(limit, &sorted);
+    (1..limit).all(|amount| {
+        let greedy = greedy_change(amount as u64, &sorted).map(|c| c.iter().map(|(_, n)| n).sum());
+        greedy == table.fewest[amount]
+    })
+}
+
+// the dynamic programming, for every amount up to the biggest one wanted.
+// fewest[a] is the fewest pieces that make a, and last[a] which
+// denomination to take off to get to the best way of making what's left
+struct Table {
+    fewest: Vec<Option<u64>>,
+    last: Vec<usize>,
+}
+
+impl Table {
+    fn new<D: Denomination>(amount: usize, sorted: &[D]) -> Table {
+        let mut fewest: Vec<Option<u64>> = vec![None; amount + 1];
+        let mut last = vec![0; amount + 1];
+        fewest[0] = Some(0);
+        for a in 1..=amount {
+            for (i, d) in sorted.iter().enumerate() {
+                let value = d.to_cents() as usize;
+                if value > a {
+                    continue;
+                }
+                if let Some(count) = fewest[a - value] {
+                    // strictly fewer, so the first (biggest) denomination
+                    // wins a tie
+                    if fewest[a].is_none_or(|best| count + 1 < best) {
+                        fewest[a] = Some(count + 1);
+                        last[a] = i;
+                    }
+                }
+            }
+        }
+        Table { fewest, last }
+    }
+}
+
+/// The fewest pieces that make `amount` when there are only so many of
+/// each, given as (denomination, how many) pairs. `None` if what's there
+/// can't make it, or the amount is over [`MAX_AMOUNT`]. A till or a vending
+/// machine wants this one, since it can't hand out coins it doesn't have.
+pub fn limited_change<D: Denomination>(amount: u64, available: &[(D, u64)]) -> Option<Change<D>> {
+    let amount = table_size(amount)?;
+    let mut available: Vec<(D, u64)> = available
+        .iter()
+        .copied()
+        .filter(|&(d, count)| d.to_cents() > 0 && count > 0)
+        .collect();
+    available.sort_by_key(|&(d, _)| std::cmp::Reverse(d.to_cents()));
+
+    // a layer per denomination: fewest[i][a] is the fewest pieces making a
+    // out of the first i denominations, and used[i][a] how many of the i-th
+    // that took
+    let mut fewest = vec![vec![None; amount + 1]];
+    fewest[0][0] = Some(0u64);
+    let mut used = Vec::new();
+    for &(d, count) in &available {
+        let value = d.to_cents() as usize;
+        let before = fewest.last().expect("starts with a layer");
+        let mut layer = vec![None; amount + 1];
+        let mut uses = vec![0; amount + 1];
+        for a in 0..=amount {
+            // bigger denominations come first, so trying more of this one
+            // first means a tie goes to it
+            let most = count.min((a / value) as u64);
+            for n in (0..=most).rev() {
+                let rest = a - n as usize * value;
+                if let Some(pieces) = before[rest] {
+                    if layer[a].is_none_or(|best| pieces + n < best) {
+                        layer[a] = Some(pieces + n);
+                        uses[a] = n;
+                    }
+                }
+            }
+        }
+        fewest.push(layer);
+        used.push(uses);
+    }
+    fewest.last().expect("starts with a layer")[amount]?;
+
+    let mut change = Vec::new();
+    let mut a = amount;
+    for (i, &(d, _)) in available.iter().enumerate().rev() {
+        let n = used[i][a];
+        if n > 0 {
+            change.push((d, n));
+            a -= n as usize * d.to_cents() as usize;
+        }
+    }
+    change.reverse();
+    Some(change)
+}
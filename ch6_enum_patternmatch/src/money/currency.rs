@@ -0,0 +1,287 @@
+// money that isn't only US cents. every currency counts in a minor unit
+// (cents, pence, yen) and has so many of them to the major unit, written as
+// the exponent: 2 for the dollar's 100 cents, 0 for the yen, which has no
+// smaller unit anymore
+//
+// a Money is an amount of minor units in one currency. adding dollars to
+// euros is a mistake, not a number, so add and sub give back an error
+// instead of guessing. converting between them goes through ExchangeRates
+// in money/exchange.rs
+use super::{Coin, Denomination};
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Currency {
+    Usd,
+    Eur,
+    Gbp,
+    Jpy,
+}
+
+/// From parsing something that isn't one of the currency codes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownCurrency(pub String);
+
+/// A coin that one of the currencies mints. Only real ones can be made, so
+/// there's no 3 cent euro coin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CurrencyCoin {
+    currency: Currency,
+    value: u64,
+}
+
+/// An amount in one currency, counted in its minor unit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Money {
+    currency: Currency,
+    minor: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MoneyError {
+    /// Two different currencies, with the one on the left first.
+    Mismatch { left: Currency, right: Currency },
+    /// More than a u64 of minor units.
+    Overflow,
+    /// Taking away more than there is.
+    Negative,
+}
+
+// the coins in circulation, in minor units, smallest first
+const USD_COINS: [u64; 6] = [1, 5, 10, 25, 50, 100];
+const EUR_COINS: [u64; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
+const GBP_COINS: [u64; 8] = [1, 2, 5, 10, 20, 50, 100, 200];
+const JPY_COINS: [u64; 6] = [1, 5, 10, 50, 100, 500];
+
+impl Currency {
+    pub const ALL: [Currency; 4] = [Currency::Usd, Currency::Eur, Currency::Gbp, Currency::Jpy];
+
+    /// The ISO 4217 code, like `USD`.
+    pub fn code(&self) -> &'static str {
+        match self {
+            Currency::Usd => "USD",
+            Currency::Eur => "EUR",
+            Currency::Gbp => "GBP",
+            Currency::Jpy => "JPY",
+        }
+    }
+
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Currency::Usd => "$",
+            Currency::Eur => "€",
+            Currency::Gbp => "£",
+            Currency::Jpy => "¥",
+        }
+    }
+
+    /// How many decimal places the minor unit is, so a major unit is
+    /// 10 to the power of this many minor ones.
+    pub fn exponent(&self) -> u32 {
+        match self {
+            Currency::Usd | Currency::Eur | Currency::Gbp => 2,
+            Currency::Jpy => 0,
+        }
+    }
+
+    /// Minor units in one major unit, 100 cents to the dollar.
+    pub fn minor_per_major(&self) -> u64 {
+        10u64.pow(self.exponent())
+    }
+
+    fn coin_values(&self) -> &'static [u64] {
+        match self {
+            Currency::Usd => &USD_COINS,
+            Currency::Eur => &EUR_COINS,
+            Currency::Gbp => &GBP_COINS,
+            Currency::Jpy => &JPY_COINS,
+        }
+    }
+
+    /// Every coin it mints, smallest first.
+    pub fn coins(&self) -> Vec<CurrencyCoin> {
+        let currency = *self;
+        self.coin_values()
+            .iter()
+            .map(|&value| CurrencyCoin { currency, value })
+            .collect()
+    }
+}
+
+impl CurrencyCoin {
+    /// The coin of `currency` worth `value` minor units, if there is one.
+    pub fn new(currency: Currency, value: u64) -> Option<CurrencyCoin> {
+        if currency.coin_values().contains(&value) {
+            Some(CurrencyCoin { currency, value })
+        } else {
+            None
+        }
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    pub fn value(&self) -> Money {
+        Money::new(self.currency, self.value)
+    }
+}
+
+/// Minor units, which for these are whatever the currency counts in.
+impl Denomination for CurrencyCoin {
+    fn to_cents(&self) -> u64 {
+        self.value
+    }
+}
+
+impl Coin {
+    /// Always dollars, it's the US mint's.
+    pub fn currency(&self) -> Currency {
+        Currency::Usd
+    }
+}
+
+impl From<Coin> for CurrencyCoin {
+    fn from(coin: Coin) -> CurrencyCoin {
+        CurrencyCoin {
+            currency: Currency::Usd,
+            value: coin.to_cents(),
+        }
+    }
+}
+
+impl Money {
+    pub fn new(currency: Currency, minor: u64) -> Money {
+        Money { currency, minor }
+    }
+
+    pub fn zero(currency: Currency) -> Money {
+        Money::new(currency, 0)
+    }
+
+    /// Whole major units, so `Money::major(Currency::Eur, 3)` is €3.00.
+    /// None if that's too many minor units for a u64.
+    pub fn major(currency: Currency, major: u64) -> Option<Money> {
+        let minor = major.checked_mul(currency.minor_per_major())?;
+        Some(Money::new(currency, minor))
+    }
+
+    pub fn currency(&self) -> Currency {
+        self.currency
+    }
+
+    /// The amount in minor units, cents for dollars.
+    pub fn minor(&self) -> u64 {
+        self.minor
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.minor == 0
+    }
+
+    fn same_currency(&self, other: &Money) -> Result<(), MoneyError> {
+        if self.currency == other.currency {
+            Ok(())
+        } else {
+            Err(MoneyError::Mismatch {
+                left: self.currency,
+                right: other.currency,
+            })
+        }
+    }
+
+    /// The two together, as long as they're the same currency.
+    pub fn add(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let minor = self
+            .minor
+            .checked_add(other.minor)
+            .ok_or(MoneyError::Overflow)?;
+        Ok(Money::new(self.currency, minor))
+    }
+
+    /// This less `other`, as long as they're the same currency and there's
+    /// enough of this.
+    pub fn sub(&self, other: &Money) -> Result<Money, MoneyError> {
+        self.same_currency(other)?;
+        let minor = self
+            .minor
+            .checked_sub(other.minor)
+            .ok_or(MoneyError::Negative)?;
+        Ok(Money::new(self.currency, minor))
+    }
+
+    /// Adds up everything in `amounts`, which all have to be `currency`.
+    /// Nothing at all adds up to zero.
+    pub fn total<'a, I>(currency: Currency, amounts: I) -> Result<Money, MoneyError>
+    where
+        I: IntoIterator<Item = &'a Money>,
+    {
+        amounts
+            .into_iter()
+            .try_fold(Money::zero(currency), |sum, amount| sum.add(amount))
+    }
+}
+
+impl fmt::Display for Currency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+/// The code, in any case, so `usd` and `USD` both work.
+impl FromStr for Currency {
+    type Err = UnknownCurrency;
+
+    fn from_str(s: &str) -> Result<Currency, UnknownCurrency> {
+        Currency::ALL
+            .iter()
+            .copied()
+            .find(|currency| currency.code().eq_ignore_ascii_case(s))
+            .ok_or_else(|| UnknownCurrency(s.to_string()))
+    }
+}
+
+impl fmt::Display for UnknownCurrency {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} isn't USD, EUR, GBP or JPY", self.0)
+    }
+}
+
+impl Error for UnknownCurrency {}
+
+impl fmt::Display for CurrencyCoin {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} coin", self.value())
+    }
+}
+
+/// With the symbol and as many decimals as the currency has, so `$1.50`,
+/// `€0.05` or `¥500`. [`format_cents`](super::format_cents) is the dollar
+/// case of this.
+impl fmt::Display for Money {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let per = self.currency.minor_per_major();
+        write!(f, "{}{}", self.currency.symbol(), self.minor / per)?;
+        match self.currency.exponent() {
+            0 => Ok(()),
+            places => write!(f, ".{:0width$}", self.minor % per, width = places as usize),
+        }
+    }
+}
+
+impl fmt::Display for MoneyError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MoneyError::Mismatch { left, right } => {
+                write!(f, "can't mix {} and {} without converting", left, right)
+            }
+            MoneyError::Overflow => write!(f, "too much money to count"),
+            MoneyError::Negative => write!(f, "that would go below zero"),
+        }
+    }
+}
+
+impl Error for MoneyError {}
@@ -0,0 +1,420 @@
+// converting Money from one currency to another. rates are kept exactly, as
+// the fraction their decimal was written as, so 0.9234 is 9234/10000 and
+// no float ever gets near an amount. the only inexact step is the last
+// one, when the answer has to land on a whole minor unit, and how that goes
+// is always the caller's choice of Rounding
+//
+// the rate file is one pair per line, # starts a comment:
+//
+//     # 1 of the first is worth this many of the second
+//     USD EUR 0.92
+//     USD JPY 149.5
+//     GBP USD 1.27
+//
+// a pair works backwards too, so USD JPY also converts yen to dollars.
+// there's no going through a third currency, a pair that isn't in the file
+// has no rate
+use super::{Currency, Money, UnknownCurrency};
+use std::collections::HashMap;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+
+/// Which whole minor unit a conversion lands on when it falls between two.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rounding {
+    /// The smaller one, so nobody's paid a fraction too much.
+    Down,
+    /// The bigger one.
+    Up,
+    /// The nearer one, and up from exactly half.
+    HalfUp,
+    /// The nearer one, and the even one from exactly half. Banks like it
+    /// because the halves don't all drift the same way.
+    HalfEven,
+}
+
+/// How many of one currency's major unit a single major unit of another
+/// is worth, kept as an exact fraction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rate {
+    num: u128,
+    den: u128,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RateError {
+    /// Not a plain decimal like `0.92`.
+    Invalid(String),
+    /// A currency worth nothing can't be converted back.
+    Zero,
+}
+
+/// Every rate from a file, or put in by hand.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    rates: HashMap<(Currency, Currency), Rate>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConvertError {
+    /// Neither way round is in the table.
+    NoRate { from: Currency, to: Currency },
+    /// The answer doesn't fit a u64 of minor units.
+    Overflow,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LineError {
+    UnknownCurrency(UnknownCurrency),
+    /// A currency or the rate wasn't there.
+    Missing,
+    Rate(RateError),
+    /// More words after the rate.
+    Trailing(String),
+    /// Like USD USD 1.
+    SameCurrency(Currency),
+    /// The pair, either way round, was already given.
+    Duplicate {
+        from: Currency,
+        to: Currency,
+        first_line: usize,
+    },
+}
+
+#[derive(Debug)]
+pub enum LoadError {
+    Io(io::Error),
+    // line numbers start at 1, like an editor would show
+    Line { line: usize, source: LineError },
+}
+
+// more decimals than any real rate has. the more there are the sooner a
+// big amount overflows the u128 maths in convert
+const MAX_DECIMALS: usize = 18;
+
+impl Rate {
+    /// One for one.
+    pub const ONE: Rate = Rate { num: 1, den: 1 };
+
+    /// The other way round, exactly.
+    pub fn inverse(&self) -> Rate {
+        Rate {
+            num: self.den,
+            den: self.num,
+        }
+    }
+}
+
+impl Rounding {
+    /// `num / den`, rounded this way.
+    fn divide(&self, num: u128, den: u128) -> u128 {
+        let (quotient, rest) = (num / den, num % den);
+        if rest == 0 {
+            return quotient;
+        }
+        // comparing rest with what's left of den keeps 2 * rest from ever
+        // overflowing
+        let past_half = rest > den - rest;
+        let half = rest == den - rest;
+        let up = match self {
+            Rounding::Down => false,
+            Rounding::Up => true,
+            Rounding::HalfUp => past_half || half,
+            Rounding::HalfEven => past_half || (half && quotient % 2 == 1),
+        };
+        quotient + u128::from(up)
+    }
+}
+
+impl ExchangeRates {
+    pub fn new() -> ExchangeRates {
+        ExchangeRates::default()
+    }
+
+    /// How many pairs, not counting each one backwards.
+    pub fn len(&self) -> usize {
+        self.rates.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// Sets what one `from` is worth in `to`. Gives back the rate this
+    /// replaced, whichever way round it was stored.
+    pub fn insert(&mut self, from: Currency, to: Currency, rate: Rate) -> Option<Rate> {
+        let old = self.rates.remove(&(to, from)).map(|rate| rate.inverse());
+        self.rates.insert((from, to), rate).or(old)
+    }
+
+    /// What one `from` is worth in `to`. A currency is always worth one of
+    /// itself.
+    pub fn get(&self, from: Currency, to: Currency) -> Option<Rate> {
+        if from == to {
+            return Some(Rate::ONE);
+        }
+        match self.rates.get(&(from, to)) {
+            Some(rate) => Some(*rate),
+            None => self.rates.get(&(to, from)).map(|rate| rate.inverse()),
+        }
+    }
+
+    /// `money` in `to`, rounded to a whole minor unit of it.
+    pub fn convert(
+        &self,
+        money: &Money,
+        to: Currency,
+        rounding: Rounding,
+    ) -> Result<Money, ConvertError> {
+        let from = money.currency();
+        let rate = self
+            .get(from, to)
+            .ok_or(ConvertError::NoRate { from, to })?;
+
+        // minor units of from, times the rate, times how many more minor
+        // units to has to the major one than from does
+        let num = u128::from(money.minor())
+            .checked_mul(rate.num)
+            .and_then(|n| n.checked_mul(u128::from(to.minor_per_major())))
+            .ok_or(ConvertError::Overflow)?;
+        let den = rate
+            .den
+            .checked_mul(u128::from(from.minor_per_major()))
+            .ok_or(ConvertError::Overflow)?;
+        let minor = rounding.divide(num, den);
+        let minor = u64::try_from(minor).map_err(|_| ConvertError::Overflow)?;
+        Ok(Money::new(to, minor))
+    }
+
+    /// Reads a rate file in the format at the top of this file.
+    pub fn read_from<R: BufRead>(input: R) -> Result<ExchangeRates, LoadError> {
+        let mut rates = ExchangeRates::new();
+        // where each pair came from, keyed the way it was written
+        let mut lines = HashMap::new();
+
+        for (i, line) in input.lines().enumerate() {
+            let line = line?;
+            let number = i + 1;
+            let err = |source| LoadError::Line {
+                line: number,
+                source,
+            };
+
+            let (from, to, rate) = match parse_line(&line).map_err(err)? {
+                Some(parsed) => parsed,
+                None => continue,
+            };
+            let first = lines.get(&(from, to)).or_else(|| lines.get(&(to, from)));
+            if let Some(&first_line) = first {
+                return Err(err(LineError::Duplicate {
+                    from,
+                    to,
+                    first_line,
+                }));
+            }
+            lines.insert((from, to), number);
+            rates.insert(from, to, rate);
+        }
+        Ok(rates)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<ExchangeRates, LoadError> {
+        ExchangeRates::read_from(BufReader::new(File::open(path)?))
+    }
+}
+
+// None for a blank or comment line
+fn parse_line(line: &str) -> Result<Option<(Currency, Currency, Rate)>, LineError> {
+    let line = line.split('#').next().unwrap_or("");
+    let mut words = line.split_whitespace();
+    let from: Currency = match words.next() {
+        Some(word) => word.parse().map_err(LineError::UnknownCurrency)?,
+        None => return Ok(None),
+    };
+    let to: Currency = words
+        .next()
+        .ok_or(LineError::Missing)?
+        .parse()
+        .map_err(LineError::UnknownCurrency)?;
+    let rate: Rate = words
+        .next()
+        .ok_or(LineError::Missing)?
+        .parse()
+        .map_err(LineError::Rate)?;
+
+    if let Some(extra) = words.next() {
+        return Err(LineError::Trailing(extra.to_string()));
+    }
+    if from == to {
+        return Err(LineError::SameCurrency(from));
+    }
+    Ok(Some((from, to, rate)))
+}
+
+/// A plain decimal: digits, and a point with more digits after it if
+/// there's a fraction. No sign, no exponent.
+impl FromStr for Rate {
+    type Err = RateError;
+
+    fn from_str(s: &str) -> Result<Rate, RateError> {
+        let invalid = || RateError::Invalid(s.to_string());
+        let (whole, fraction) = match s.find('.') {
+            Some(point) => (&s[..point], &s[point + 1..]),
+            None => (s, ""),
+        };
+        let all_digits = |part: &str| part.bytes().all(|b| b.is_ascii_digit());
+        if whole.is_empty()
+            || !all_digits(whole)
+            || !all_digits(fraction)
+            || (s.contains('.') && fraction.is_empty())
+            || fraction.len() > MAX_DECIMALS
+        {
+            return Err(invalid());
+        }
+
+        // 149.5 is 1495 over 10
+        let digits = format!("{}{}", whole, fraction);
+        let num: u128 = digits.parse().map_err(|_| invalid())?;
+        if num == 0 {
+            return Err(RateError::Zero);
+        }
+        let den = 10u128.pow(fraction.len() as u32);
+        Ok(Rate { num, den })
+    }
+}
+
+/// As a decimal, when it is one. Inverses mostly aren't, so those print as
+/// one over a decimal, like `1/0.92`.
+impl fmt::Display for Rate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fn decimal(f: &mut fmt::Formatter, num: u128, places: u32) -> fmt::Result {
+            let den = 10u128.pow(places);
+            match places {
+                0 => write!(f, "{}", num),
+                _ => write!(
+                    f,
+                    "{}.{:0width$}",
+                    num / den,
+                    num % den,
+                    width = places as usize
+                ),
+            }
+        }
+        match (decimal_places(self.den), decimal_places(self.num)) {
+            (Some(places), _) => decimal(f, self.num, places),
+            (None, Some(places)) => {
+                write!(f, "1/")?;
+                decimal(f, self.den, places)
+            }
+            (None, None) => write!(f, "{}/{}", self.num, self.den),
+        }
+    }
+}
+
+// Some(n) when den is 10^n
+fn decimal_places(mut den: u128) -> Option<u32> {
+    let mut places = 0;
+    while den > 1 && den.is_multiple_of(10) {
+        den /= 10;
+        places += 1;
+    }
+    if den == 1 {
+        Some(places)
+    } else {
+        None
+    }
+}
+
+impl fmt::Display for Rounding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Rounding::Down => "down",
+            Rounding::Up => "up",
+            Rounding::HalfUp => "half up",
+            Rounding::HalfEven => "half even",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl fmt::Display for RateError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RateError::Invalid(s) => write!(f, "{:?} isn't a rate like 0.92", s),
+            RateError::Zero => write!(f, "a rate can't be zero"),
+        }
+    }
+}
+
+impl Error for RateError {}
+
+impl fmt::Display for ConvertError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConvertError::NoRate { from, to } => write!(f, "no rate between {} and {}", from, to),
+            ConvertError::Overflow => write!(f, "too much money to convert"),
+        }
+    }
+}
+
+impl Error for ConvertError {}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LineError::UnknownCurrency(e) => e.fmt(f),
+            LineError::Missing => write!(f, "expected two currencies and a rate"),
+            LineError::Rate(e) => e.fmt(f),
+            LineError::Trailing(word) => write!(f, "unexpected {:?} after the rate", word),
+            LineError::SameCurrency(currency) => write!(f, "{} is always 1 {}", currency, currency),
+            LineError::Duplicate {
+                from,
+                to,
+                first_line,
+            } => write!(
+                f,
+                "{} and {} already have a rate on line {}",
+                from, to, first_line
+            ),
+        }
+    }
+}
+
+impl Error for LineError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LineError::UnknownCurrency(e) => Some(e),
+            LineError::Rate(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LoadError::Io(e) => e.fmt(f),
+            LoadError::Line { line, source } => write!(f, "line {}: {}", line, source),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            LoadError::Io(e) => Some(e),
+            LoadError::Line { source, .. } => Some(source),
+        }
+    }
+}
+
+impl From<io::Error> for LoadError {
+    fn from(e: io::Error) -> LoadError {
+        LoadError::Io(e)
+    }
+}
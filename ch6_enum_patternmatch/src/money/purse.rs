@@ -0,0 +1,177 @@
+// a tally of coins and bills. it only keeps counts, since one quarter is as
+// good as another
+use super::{format_cents, greedy_change, limited_change, Coin, Piece};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::iter::FromIterator;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Purse {
+    // never holds a zero, so two purses with the same money are equal
+    counts: BTreeMap<Piece, u64>,
+}
+
+/// From taking out more of a piece than the purse has.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NotEnough {
+    pub piece: Piece,
+    pub wanted: u64,
+    pub have: u64,
+}
+
+impl Purse {
+    pub fn new() -> Purse {
+        Purse::default()
+    }
+
+    /// The fewest pieces adding up to `cents`, with a dollar bill rather
+    /// than the coin. US money never needs the smarter search, see
+    /// money/change.rs.
+    pub fn change_for(cents: u64) -> Purse {
+        let till: Vec<Piece> = Piece::ALL
+            .iter()
+            .copied()
+            .filter(|&piece| piece != Piece::Coin(Coin::Dollar))
+            .collect();
+        let change = greedy_change(cents, &till).expect("there's a penny");
+        change.into_iter().collect()
+    }
+
+    /// Takes out the fewest pieces this purse can make `cents` from, or
+    /// nothing if it can't make it exactly. It never can past
+    /// [`MAX_AMOUNT`](super::MAX_AMOUNT), see [`limited_change`].
+    pub fn take_change(&mut self, cents: u64) -> Option<Purse> {
+        // more than there is can't be made, and limited_change would build
+        // a table as big as the amount to find that out
+        if cents > self.total() {
+            return None;
+        }
+        let available: Vec<(Piece, u64)> = self.iter().collect();
+        let change: Purse = limited_change(cents, &available)?.into_iter().collect();
+        for (piece, count) in change.iter() {
+            self.remove_many(piece, count)
+                .expect("only took what was there");
+        }
+        Some(change)
+    }
+
+    pub fn add<P: Into<Piece>>(&mut self, piece: P) {
+        self.add_many(piece, 1);
+    }
+
+    pub fn add_many<P: Into<Piece>>(&mut self, piece: P, count: u64) {
+        if count > 0 {
+            *self.counts.entry(piece.into()).or_insert(0) += count;
+        }
+    }
+
+    /// Takes one out, if there is one.
+    pub fn remove<P: Into<Piece>>(&mut self, piece: P) -> Result<(), NotEnough> {
+        self.remove_many(piece, 1)
+    }
+
+    /// Takes `count` out, or nothing at all if there aren't that many.
+    pub fn remove_many<P: Into<Piece>>(&mut self, piece: P, count: u64) -> Result<(), NotEnough> {
+        let piece = piece.into();
+        let have = self.count(piece);
+        if have < count {
+            return Err(NotEnough {
+                piece,
+                wanted: count,
+                have,
+            });
+        }
+        if have == count {
+            self.counts.remove(&piece);
+        } else {
+            self.counts.insert(piece, have - count);
+        }
+        Ok(())
+    }
+
+    /// Moves everything from `other` into this purse.
+    pub fn merge(&mut self, other: Purse) {
+        for (piece, count) in other.counts {
+            self.add_many(piece, count);
+        }
+    }
+
+    pub fn count<P: Into<Piece>>(&self, piece: P) -> u64 {
+        self.counts.get(&piece.into()).copied().unwrap_or(0)
+    }
+
+    /// How many coins and bills altogether.
+    pub fn pieces(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// What it's all worth, in cents. Panics past u64::MAX cents, which is
+    /// more money than there is.
+    pub fn total(&self) -> u64 {
+        self.counts
+            .iter()
+            .map(|(piece, &count)| piece.to_cents() * count)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Each piece there's at least one of, with how many, smallest first.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Piece, u64)> + '_ {
+        self.counts.iter().map(|(&piece, &count)| (piece, count))
+    }
+}
+
+impl<P: Into<Piece>> Extend<P> for Purse {
+    fn extend<I: IntoIterator<Item = P>>(&mut self, pieces: I) {
+        for piece in pieces {
+            self.add(piece);
+        }
+    }
+}
+
+impl<P: Into<Piece>> FromIterator<P> for Purse {
+    fn from_iter<I: IntoIterator<Item = P>>(pieces: I) -> Purse {
+        let mut purse = Purse::new();
+        purse.extend(pieces);
+        purse
+    }
+}
+
+// (piece, count) pairs, which is what the change functions give back
+impl FromIterator<(Piece, u64)> for Purse {
+    fn from_iter<I: IntoIterator<Item = (Piece, u64)>>(counts: I) -> Purse {
+        let mut purse = Purse::new();
+        for (piece, count) in counts {
+            purse.add_many(piece, count);
+        }
+        purse
+    }
+}
+
+/// Like `$1.55: 1 x $1 bill, 2 x quarter, 1 x nickel`, biggest first.
+impl fmt::Display for Purse {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", format_cents(self.total()))?;
+        for (i, (piece, count)) in self.iter().rev().enumerate() {
+            let sep = if i == 0 { ": " } else { ", " };
+            write!(f, "{}{} x {}", sep, count, piece)?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for NotEnough {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "wanted {} but there's only {} ({})",
+            self.wanted, self.have, self.piece
+        )
+    }
+}
+
+impl Error for NotEnough {}
@@ -0,0 +1,261 @@
+// the UsState from match_exp() in main.rs, with all of them this time. the
+// 50 State Quarters came out five a year from 1999 to 2008, in the order
+// the states joined the union, then DC and the five territories got theirs
+// in 2009. the variants are in that same order, so sorting a collection of
+// quarters puts them in release order
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// A state, DC or a territory with its own quarter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum UsState {
+    Delaware,
+    Pennsylvania,
+    NewJersey,
+    Georgia,
+    Connecticut,
+    Massachusetts,
+    Maryland,
+    SouthCarolina,
+    NewHampshire,
+    Virginia,
+    NewYork,
+    NorthCarolina,
+    RhodeIsland,
+    Vermont,
+    Kentucky,
+    Tennessee,
+    Ohio,
+    Louisiana,
+    Indiana,
+    Mississippi,
+    Illinois,
+    Alabama,
+    Maine,
+    Missouri,
+    Arkansas,
+    Michigan,
+    Florida,
+    Texas,
+    Iowa,
+    Wisconsin,
+    California,
+    Minnesota,
+    Oregon,
+    Kansas,
+    WestVirginia,
+    Nevada,
+    Nebraska,
+    Colorado,
+    NorthDakota,
+    SouthDakota,
+    Montana,
+    Washington,
+    Idaho,
+    Wyoming,
+    Utah,
+    Oklahoma,
+    NewMexico,
+    Arizona,
+    Alaska,
+    Hawaii,
+    DistrictOfColumbia,
+    PuertoRico,
+    Guam,
+    AmericanSamoa,
+    VirginIslands,
+    NorthernMarianaIslands,
+}
+
+/// From parsing something that's neither an abbreviation nor a name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownState(pub String);
+
+// (abbreviation, name), in the same order as the variants
+const INFO: [(&str, &str); 56] = [
+    ("DE", "Delaware"),
+    ("PA", "Pennsylvania"),
+    ("NJ", "New Jersey"),
+    ("GA", "Georgia"),
+    ("CT", "Connecticut"),
+    ("MA", "Massachusetts"),
+    ("MD", "Maryland"),
+    ("SC", "South Carolina"),
+    ("NH", "New Hampshire"),
+    ("VA", "Virginia"),
+    ("NY", "New York"),
+    ("NC", "North Carolina"),
+    ("RI", "Rhode Island"),
+    ("VT", "Vermont"),
+    ("KY", "Kentucky"),
+    ("TN", "Tennessee"),
+    ("OH", "Ohio"),
+    ("LA", "Louisiana"),
+    ("IN", "Indiana"),
+    ("MS", "Mississippi"),
+    ("IL", "Illinois"),
+    ("AL", "Alabama"),
+    ("ME", "Maine"),
+    ("MO", "Missouri"),
+    ("AR", "Arkansas"),
+    ("MI", "Michigan"),
+    ("FL", "Florida"),
+    ("TX", "Texas"),
+    ("IA", "Iowa"),
+    ("WI", "Wisconsin"),
+    ("CA", "California"),
+    ("MN", "Minnesota"),
+    ("OR", "Oregon"),
+    ("KS", "Kansas"),
+    ("WV", "West Virginia"),
+    ("NV", "Nevada"),
+    ("NE", "Nebraska"),
+    ("CO", "Colorado"),
+    ("ND", "North Dakota"),
+    ("SD", "South Dakota"),
+    ("MT", "Montana"),
+    ("WA", "Washington"),
+    ("ID", "Idaho"),
+    ("WY", "Wyoming"),
+    ("UT", "Utah"),
+    ("OK", "Oklahoma"),
+    ("NM", "New Mexico"),
+    ("AZ", "Arizona"),
+    ("AK", "Alaska"),
+    ("HI", "Hawaii"),
+    ("DC", "District of Columbia"),
+    ("PR", "Puerto Rico"),
+    ("GU", "Guam"),
+    ("AS", "American Samoa"),
+    ("VI", "U.S. Virgin Islands"),
+    ("MP", "Northern Mariana Islands"),
+];
+
+impl UsState {
+    /// Every one, in the order their quarters came out.
+    pub const ALL: [UsState; 56] = [
+        UsState::Delaware,
+        UsState::Pennsylvania,
+        UsState::NewJersey,
+        UsState::Georgia,
+        UsState::Connecticut,
+        UsState::Massachusetts,
+        UsState::Maryland,
+        UsState::SouthCarolina,
+        UsState::NewHampshire,
+        UsState::Virginia,
+        UsState::NewYork,
+        UsState::NorthCarolina,
+        UsState::RhodeIsland,
+        UsState::Vermont,
+        UsState::Kentucky,
+        UsState::Tennessee,
+        UsState::Ohio,
+        UsState::Louisiana,
+        UsState::Indiana,
+        UsState::Mississippi,
+        UsState::Illinois,
+        UsState::Alabama,
+        UsState::Maine,
+        UsState::Missouri,
+        UsState::Arkansas,
+        UsState::Michigan,
+        UsState::Florida,
+        UsState::Texas,
+        UsState::Iowa,
+        UsState::Wisconsin,
+        UsState::California,
+        UsState::Minnesota,
+        UsState::Oregon,
+        UsState::Kansas,
+        UsState::WestVirginia,
+        UsState::Nevada,
+        UsState::Nebraska,
+        UsState::Colorado,
+        UsState::NorthDakota,
+        UsState::SouthDakota,
+        UsState::Montana,
+        UsState::Washington,
+        UsState::Idaho,
+        UsState::Wyoming,
+        UsState::Utah,
+        UsState::Oklahoma,
+        UsState::NewMexico,
+        UsState::Arizona,
+        UsState::Alaska,
+        UsState::Hawaii,
+        UsState::DistrictOfColumbia,
+        UsState::PuertoRico,
+        UsState::Guam,
+        UsState::AmericanSamoa,
+        UsState::VirginIslands,
+        UsState::NorthernMarianaIslands,
+    ];
+
+    fn index(&self) -> usize {
+        *self as usize
+    }
+
+    /// The two letter postal code, like `CA`.
+    pub fn abbreviation(&self) -> &'static str {
+        INFO[self.index()].0
+    }
+
+    pub fn name(&self) -> &'static str {
+        INFO[self.index()].1
+    }
+
+    /// One of the 50, not DC or a territory.
+    pub fn is_state(&self) -> bool {
+        self.index() < 50
+    }
+
+    /// The year its quarter came out.
+    pub fn quarter_year(&self) -> u16 {
+        if self.is_state() {
+            1999 + (self.index() / 5) as u16
+        } else {
+            2009
+        }
+    }
+
+    /// Where its quarter came in the whole run, from 1 (Delaware) to 56
+    /// (the Northern Mariana Islands). For the states that's also the order
+    /// they joined the union.
+    pub fn quarter_number(&self) -> usize {
+        self.index() + 1
+    }
+}
+
+/// Either the abbreviation or the full name, in any case, so `ca`,
+/// `CA` and `california` all work.
+impl FromStr for UsState {
+    type Err = UnknownState;
+
+    fn from_str(s: &str) -> Result<UsState, UnknownState> {
+        let s = s.trim();
+        UsState::ALL
+            .iter()
+            .copied()
+            .find(|state| {
+                state.abbreviation().eq_ignore_ascii_case(s) || state.name().eq_ignore_ascii_case(s)
+            })
+            .ok_or_else(|| UnknownState(s.to_string()))
+    }
+}
+
+/// The full name.
+impl fmt::Display for UsState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl fmt::Display for UnknownState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?} isn't a state, DC or a territory", self.0)
+    }
+}
+
+impl Error for UnknownState {}
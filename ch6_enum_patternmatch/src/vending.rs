@@ -0,0 +1,327 @@
+// a vending machine, as a state machine over the Coin enum in src/money.rs.
+// the machine is always in one of four states:
+//
+//     Idle --insert--> Accepting --select--> Dispensing --> ReturningChange --> Idle
+//                          |                     |                              ^
+//                          |                     +-- no change due -------------+
+//                          +--cancel--> ReturningChange
+//
+// Dispensing and ReturningChange don't wait for anyone, the machine moves on
+// from them by itself before a call returns. they're still states of their
+// own so every step is one arm of a match, and the compiler makes sure no
+// state and event pair got forgotten
+//
+// coins that go in land in the coin box, and change comes out of the same
+// box. so a sale that would need change the box can't make is refused
+// before anything drops, rather than shortchanging anyone
+//
+// src/bin/vending.rs drives one from the terminal
+use crate::money::{format_cents, Coin, Piece, Purse};
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum State {
+    /// Waiting for a coin.
+    Idle,
+    /// Got some money, waiting for more or a choice.
+    Accepting { credit: u64 },
+    /// Dropping an item, with the change already counted out.
+    Dispensing { code: String, change: Purse },
+    /// Handing back coins.
+    ReturningChange { change: Purse },
+}
+
+/// What a customer can do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Event {
+    Insert(Coin),
+    Select(String),
+    Cancel,
+}
+
+/// What comes out of the machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Output {
+    /// A coin it doesn't take, straight back out.
+    Rejected(Coin),
+    /// The name of what was bought.
+    Dispensed(String),
+    Change(Purse),
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VendError {
+    /// No slot with that code.
+    UnknownSlot(String),
+    SoldOut(String),
+    /// Not enough credit yet.
+    NeedMore {
+        price: u64,
+        credit: u64,
+    },
+    /// The coin box can't make the change, so the sale didn't happen.
+    ExactChangeOnly {
+        change: u64,
+    },
+}
+
+/// One slot of the machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub name: String,
+    pub price: u64,
+    pub count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct Machine {
+    state: State,
+    slots: BTreeMap<String, Slot>,
+    coins: Purse,
+    accepts: Vec<Coin>,
+}
+
+impl Machine {
+    /// Empty, taking nickels, dimes, quarters and dollar coins. Pennies and
+    /// half dollars get rejected, like most real machines do.
+    pub fn new() -> Machine {
+        Machine::accepting(&[Coin::Nickel, Coin::Dime, Coin::Quarter, Coin::Dollar])
+    }
+
+    /// Empty, taking only these coins.
+    pub fn accepting(coins: &[Coin]) -> Machine {
+        Machine {
+            state: State::Idle,
+            slots: BTreeMap::new(),
+            coins: Purse::new(),
+            accepts: coins.to_vec(),
+        }
+    }
+
+    /// Fills a slot, replacing whatever was in it.
+    pub fn stock(&mut self, code: &str, name: &str, price: u64, count: u32) {
+        let slot = Slot {
+            name: name.to_string(),
+            price,
+            count,
+        };
+        self.slots.insert(code.to_string(), slot);
+    }
+
+    /// Puts coins in the coin box for making change.
+    pub fn load_coins(&mut self, coin: Coin, count: u64) {
+        self.coins.add_many(coin, count);
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    /// How much has been put in and not spent.
+    pub fn credit(&self) -> u64 {
+        match self.state {
+            State::Accepting { credit } => credit,
+            State::Idle | State::Dispensing { .. } | State::ReturningChange { .. } => 0,
+        }
+    }
+
+    pub fn slots(&self) -> impl Iterator<Item = (&str, &Slot)> {
+        self.slots.iter().map(|(code, slot)| (code.as_str(), slot))
+    }
+
+    pub fn coin_box(&self) -> &Purse {
+        &self.coins
+    }
+
+    pub fn accepts(&self, coin: Coin) -> bool {
+        self.accepts.contains(&coin)
+    }
+
+    pub fn insert(&mut self, coin: Coin) -> Result<Vec<Output>, VendError> {
+        self.handle(Event::Insert(coin))
+    }
+
+    pub fn select(&mut self, code: &str) -> Result<Vec<Output>, VendError> {
+        self.handle(Event::Select(code.to_string()))
+    }
+
+    pub fn cancel(&mut self) -> Result<Vec<Output>, VendError> {
+        self.handle(Event::Cancel)
+    }
+
+    /// Reacts to `event`, then runs through Dispensing and ReturningChange,
+    /// so it's back to Idle or Accepting when this returns. Everything that
+    /// came out of the machine on the way is in the list, in order. On an
+    /// error nothing changed.
+    pub fn handle(&mut self, event: Event) -> Result<Vec<Output>, VendError> {
+        let mut outputs = Vec::new();
+        let state = self.state.clone();
+        let (next, output) = self.on_event(state, event)?;
+        self.state = next;
+        outputs.extend(output);
+        while let Some(output) = self.advance() {
+            outputs.push(output);
+        }
+        Ok(outputs)
+    }
+
+    // the states that wait on a customer, and what each event does to them
+    fn on_event(
+        &mut self,
+        state: State,
+        event: Event,
+    ) -> Result<(State, Option<Output>), VendError> {
+        match (state, event) {
+            (state, Event::Insert(coin)) if !self.accepts(coin) => {
+                Ok((state, Some(Output::Rejected(coin))))
+            }
+            (State::Idle, Event::Insert(coin)) => {
+                self.coins.add(coin);
+                let credit = coin.to_cents();
+                Ok((State::Accepting { credit }, None))
+            }
+            (State::Accepting { credit }, Event::Insert(coin)) => {
+                self.coins.add(coin);
+                let credit = credit + coin.to_cents();
+                Ok((State::Accepting { credit }, None))
+            }
+            (State::Idle, Event::Select(code)) => {
+                let slot = self.slot(&code)?;
+                Err(VendError::NeedMore {
+                    price: slot.price,
+                    credit: 0,
+                })
+            }
+            (State::Accepting { credit }, Event::Select(code)) => {
+                let slot = self.slot(&code)?;
+                if slot.price > credit {
+                    return Err(VendError::NeedMore {
+                        price: slot.price,
+                        credit,
+                    });
+                }
+                let due = credit - slot.price;
+                let change = self
+                    .coins
+                    .take_change(due)
+                    .ok_or(VendError::ExactChangeOnly { change: due })?;
+                Ok((State::Dispensing { code, change }, None))
+            }
+            (State::Idle, Event::Cancel) => Ok((State::Idle, None)),
+            (State::Accepting { credit }, Event::Cancel) => {
+                // the coins that went in are in the box, so this always works
+                let change = self
+                    .coins
+                    .take_change(credit)
+                    .expect("the credit came from coins in the box");
+                Ok((State::ReturningChange { change }, None))
+            }
+            // handle never leaves the machine in these two
+            (state @ State::Dispensing { .. }, event)
+            | (state @ State::ReturningChange { .. }, event) => {
+                unreachable!("{:?} while {:?}", event, state)
+            }
+        }
+    }
+
+    // one step on from a state that doesn't wait for anyone. None once it's
+    // in one that does
+    fn advance(&mut self) -> Option<Output> {
+        match self.state.clone() {
+            State::Idle | State::Accepting { .. } => None,
+            State::Dispensing { code, change } => {
+                let slot = self.slots.get_mut(&code).expect("checked when selected");
+                slot.count -= 1;
+                let name = slot.name.clone();
+                self.state = if change.is_empty() {
+                    State::Idle
+                } else {
+                    State::ReturningChange { change }
+                };
+                Some(Output::Dispensed(name))
+            }
+            State::ReturningChange { change } => {
+                self.state = State::Idle;
+                Some(Output::Change(change))
+            }
+        }
+    }
+
+    fn slot(&self, code: &str) -> Result<&Slot, VendError> {
+        match self.slots.get(code) {
+            None => Err(VendError::UnknownSlot(code.to_string())),
+            Some(slot) if slot.count == 0 => Err(VendError::SoldOut(code.to_string())),
+            Some(slot) => Ok(slot),
+        }
+    }
+}
+
+impl Default for Machine {
+    fn default() -> Machine {
+        Machine::new()
+    }
+}
+
+impl Output {
+    /// Every coin that came out, one entry per coin, biggest first for
+    /// change.
+    pub fn coins(&self) -> Vec<Coin> {
+        let change = match self {
+            Output::Change(change) => change,
+            Output::Rejected(coin) => return vec![*coin],
+            Output::Dispensed(_) => return Vec::new(),
+        };
+        let mut coins = Vec::new();
+        for (piece, count) in change.iter().rev() {
+            if let Piece::Coin(coin) = piece {
+                coins.extend(std::iter::repeat_n(coin, count as usize));
+            }
+        }
+        coins
+    }
+}
+
+impl fmt::Display for State {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            State::Idle => write!(f, "idle"),
+            State::Accepting { credit } => write!(f, "accepting, {} in", format_cents(*credit)),
+            State::Dispensing { code, .. } => write!(f, "dispensing {}", code),
+            State::ReturningChange { change } => write!(f, "returning {}", change),
+        }
+    }
+}
+
+impl fmt::Display for Output {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Output::Rejected(coin) => write!(f, "rejected a {}", coin),
+            Output::Dispensed(name) => write!(f, "dispensed {}", name),
+            Output::Change(change) => write!(f, "change {}", change),
+        }
+    }
+}
+
+impl fmt::Display for VendError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            VendError::UnknownSlot(code) => write!(f, "there's no slot {}", code),
+            VendError::SoldOut(code) => write!(f, "{} is sold out", code),
+            VendError::NeedMore { price, credit } => write!(
+                f,
+                "that's {}, put in {} more",
+                format_cents(*price),
+                format_cents(price - credit)
+            ),
+            VendError::ExactChangeOnly { change } => write!(
+                f,
+                "exact change only, can't give back {}",
+                format_cents(*change)
+            ),
+        }
+    }
+}
+
+impl Error for VendError {}
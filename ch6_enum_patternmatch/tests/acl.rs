@@ -0,0 +1,148 @@
+// the trie has to give the same answer as checking every rule and keeping
+// the most specific one. small tables with short prefixes, so rules nest
+// and overlap a lot. seeded, so failures repeat
+use ch6_enum_patternmatch::acl::{Acl, Action, LoadError, Rule, RuleError};
+use ch6_enum_patternmatch::ip::{Cidr, IpAddr, IpAddrKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const TABLES: usize = 200;
+
+// addresses that only differ in the first few bits, so prefixes collide
+fn random_addr(rng: &mut StdRng, kind: IpAddrKind) -> IpAddr {
+    let bits = u128::from(rng.gen::<u8>() & 0xf0) << (kind.bits() - 8);
+    IpAddr::from_bits(kind, bits | u128::from(rng.gen_bool(0.5)))
+}
+
+fn random_kind(rng: &mut StdRng) -> IpAddrKind {
+    if rng.gen_bool(0.5) {
+        IpAddrKind::V4
+    } else {
+        IpAddrKind::V6
+    }
+}
+
+fn random_rule(rng: &mut StdRng) -> Rule {
+    let kind = random_kind(rng);
+    // mostly short prefixes, now and then a whole address
+    let prefix = if rng.gen_bool(0.1) {
+        kind.bits()
+    } else {
+        rng.gen_range(0..=8)
+    };
+    let action = if rng.gen_bool(0.5) {
+        Action::Allow
+    } else {
+        Action::Deny
+    };
+    Rule {
+        action,
+        cidr: Cidr::new(random_addr(rng, kind), prefix).unwrap(),
+    }
+}
+
+#[test]
+fn matches_a_linear_scan() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..TABLES {
+        let mut acl = Acl::new(Action::Deny);
+        // later rules for the same network replace earlier ones
+        let mut rules: Vec<Rule> = Vec::new();
+        for _ in 0..rng.gen_range(0..40) {
+            let rule = random_rule(&mut rng);
+            let old = rules.iter().position(|r| r.cidr == rule.cidr);
+            let replaced = old.map(|i| std::mem::replace(&mut rules[i], rule));
+            if replaced.is_none() {
+                rules.push(rule);
+            }
+            assert_eq!(acl.insert(rule), replaced);
+        }
+        assert_eq!(acl.len(), rules.len());
+
+        for rule in &rules {
+            assert_eq!(acl.get(&rule.cidr), Some(rule));
+        }
+        for _ in 0..200 {
+            let kind = random_kind(&mut rng);
+            let addr = random_addr(&mut rng, kind);
+            let want = rules
+                .iter()
+                .filter(|r| r.cidr.contains(&addr))
+                .max_by_key(|r| r.cidr.prefix());
+            assert_eq!(acl.matching_rule(&addr), want, "{} in {:?}", addr, rules);
+        }
+    }
+}
+
+#[test]
+fn get_wants_the_exact_network() {
+    let mut acl = Acl::new(Action::Allow);
+    let rule: Rule = Rule {
+        action: Action::Deny,
+        cidr: "10.0.0.0/8".parse().unwrap(),
+    };
+    acl.insert(rule);
+    assert_eq!(acl.get(&"10.0.0.0/8".parse().unwrap()), Some(&rule));
+    assert_eq!(acl.get(&"10.0.0.0/16".parse().unwrap()), None);
+    assert_eq!(acl.get(&"0.0.0.0/0".parse().unwrap()), None);
+    // same bits, other version
+    assert_eq!(acl.get(&"a00::/8".parse().unwrap()), None);
+    assert!(!acl.is_allowed(&"10.9.9.9".parse().unwrap()));
+    assert!(acl.is_allowed(&"a00::1".parse().unwrap()));
+}
+
+#[test]
+fn rule_files() {
+    let acl = Acl::read_from(
+        "\n  # nothing but comments and blanks up here\n\ndefault allow\ndeny ::/0\n".as_bytes(),
+    )
+    .unwrap();
+    assert_eq!(acl.default_action(), Action::Allow);
+    assert!(acl.is_allowed(&"1.2.3.4".parse().unwrap()));
+    assert!(!acl.is_allowed(&"::1".parse().unwrap()));
+
+    // the default is deny when the file doesn't say
+    assert_eq!(
+        Acl::read_from("".as_bytes()).unwrap().default_action(),
+        Action::Deny
+    );
+
+    let line_error = |text: &str| match Acl::read_from(text.as_bytes()) {
+        Err(LoadError::Rule { line, source }) => (line, source),
+        other => panic!("{:?} loaded as {:?}", text, other),
+    };
+    assert_eq!(
+        line_error("allow 1.2.3.4\npermit 1.2.3.5"),
+        (2, RuleError::UnknownAction("permit".to_string()))
+    );
+    assert_eq!(line_error("deny"), (1, RuleError::MissingCidr));
+    assert_eq!(
+        line_error("deny 1.2.3.4 please"),
+        (1, RuleError::Trailing("please".to_string()))
+    );
+    assert!(matches!(
+        line_error("allow 1.2.3.4/33"),
+        (1, RuleError::Cidr(_))
+    ));
+    assert!(matches!(line_error("allow 1.2.3"), (1, RuleError::Cidr(_))));
+    assert_eq!(
+        line_error("default deny\n\ndefault allow"),
+        (3, RuleError::DuplicateDefault { first_line: 1 })
+    );
+    // 1.2.3.4 and 1.2.3.4/32 are the same network
+    assert_eq!(
+        line_error("allow 1.2.3.4\ndeny 1.2.3.4/32"),
+        (
+            2,
+            RuleError::Duplicate {
+                cidr: "1.2.3.4/32".parse().unwrap(),
+                first_line: 1
+            }
+        )
+    );
+
+    assert!(matches!(
+        Acl::load("/definitely/not/a/rules/file"),
+        Err(LoadError::Io(_))
+    ));
+}
@@ -0,0 +1,181 @@
+// threads make the interleaving random, but what each subscriber gets
+// shouldn't be: one producer's messages in the order it sent them, and every
+// subscriber agreeing on the order of everything they both get
+use ch6_enum_patternmatch::bus::{Bus, Closed, Filter};
+use ch6_enum_patternmatch::message::{Message, MessageKind, State};
+use std::thread;
+
+const PRODUCERS: i32 = 4;
+const PER_PRODUCER: i32 = 1_000;
+
+// producer p sends Move { x: p, y: 0 }, Move { x: p, y: 1 }, ... with a
+// Write and a ChangeColor mixed in, so the filters have something to do
+fn produce(bus: Bus, producer: i32) {
+    for n in 0..PER_PRODUCER {
+        let message = match n % 3 {
+            0 => Message::Move { x: producer, y: n },
+            1 => Message::Write(format!("{} {}", producer, n)),
+            _ => Message::ChangeColor(producer, n, 0),
+        };
+        bus.publish(message).unwrap();
+    }
+}
+
+// producer and sequence number, from any of the three kinds produce sends
+fn origin(message: &Message) -> (i32, i32) {
+    match message {
+        Message::Move { x, y } => (*x, *y),
+        Message::Write(text) => {
+            let mut parts = text.split(' ').map(|p| p.parse().unwrap());
+            (parts.next().unwrap(), parts.next().unwrap())
+        }
+        Message::ChangeColor(p, n, _) => (*p, *n),
+        Message::Quit => panic!("a subscription handed out a Quit"),
+    }
+}
+
+#[test]
+fn delivery_order() {
+    let bus = Bus::new();
+    let filters = [
+        Filter::ALL,
+        Filter::ALL,
+        Filter::only(MessageKind::Move),
+        Filter::only(MessageKind::Move).and(MessageKind::Write),
+    ];
+    let workers: Vec<_> = filters
+        .iter()
+        .map(|&filter| {
+            let subscription = bus.subscribe(filter);
+            thread::spawn(move || subscription.collect::<Vec<Message>>())
+        })
+        .collect();
+
+    let producers: Vec<_> = (0..PRODUCERS)
+        .map(|p| {
+            let bus = bus.clone();
+            thread::spawn(move || produce(bus, p))
+        })
+        .collect();
+    for producer in producers {
+        producer.join().unwrap();
+    }
+    bus.publish(Message::Quit).unwrap();
+
+    let received: Vec<Vec<Message>> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+
+    for (filter, messages) in filters.iter().zip(&received) {
+        assert!(messages.iter().all(|m| filter.matches(m)));
+        // every producer's messages, in the order it sent them
+        for p in 0..PRODUCERS {
+            let sent: Vec<i32> = (0..PER_PRODUCER)
+                .filter(|n| {
+                    let kind = [
+                        MessageKind::Move,
+                        MessageKind::Write,
+                        MessageKind::ChangeColor,
+                    ];
+                    filter.allows(kind[*n as usize % 3])
+                })
+                .collect();
+            let got: Vec<i32> = messages
+                .iter()
+                .map(origin)
+                .filter(|&(from, _)| from == p)
+                .map(|(_, n)| n)
+                .collect();
+            assert_eq!(got, sent, "producer {} through {:?}", p, filter);
+        }
+    }
+
+    // the two that wanted everything got it in the very same order, and the
+    // others got that order with their kinds picked out
+    assert_eq!(received[0], received[1]);
+    for (filter, messages) in filters.iter().zip(&received).skip(2) {
+        let picked: Vec<Message> = received[0]
+            .iter()
+            .filter(|m| filter.matches(m))
+            .cloned()
+            .collect();
+        assert_eq!(messages, &picked);
+    }
+}
+
+#[test]
+fn quit_stops_every_worker() {
+    let bus = Bus::new();
+    // workers that apply what they get to their own State, and one that
+    // only asked for Writes. the Quit reaches all of them anyway
+    let workers: Vec<_> = (0..3)
+        .map(|i| {
+            let filter = if i == 0 {
+                Filter::only(MessageKind::Write)
+            } else {
+                Filter::ALL
+            };
+            let subscription = bus.subscribe(filter);
+            thread::spawn(move || {
+                let mut state = State::new();
+                state.run(&subscription.collect::<Vec<_>>());
+                state
+            })
+        })
+        .collect();
+
+    bus.publish(Message::Move { x: 2, y: 3 }).unwrap();
+    bus.publish(Message::Write(String::from("hi"))).unwrap();
+    bus.publish(Message::Quit).unwrap();
+    assert!(bus.is_closed());
+    assert_eq!(bus.subscribers(), 0);
+    assert_eq!(
+        bus.publish(Message::Move { x: 1, y: 1 }),
+        Err(Closed(Message::Move { x: 1, y: 1 }))
+    );
+
+    let states: Vec<State> = workers.into_iter().map(|w| w.join().unwrap()).collect();
+    assert_eq!(states[0].position, (0, 0));
+    assert_eq!(states[0].log, ["hi"]);
+    for state in &states[1..] {
+        assert_eq!(state.position, (2, 3));
+        assert_eq!(state.log, ["hi"]);
+    }
+
+    // too late to hear anything
+    let mut late = bus.subscribe(Filter::ALL);
+    assert!(late.is_done());
+    assert_eq!(late.recv(), None);
+}
+
+#[test]
+fn dropped_ends() {
+    let bus = Bus::new();
+    let kept = bus.subscribe(Filter::only(MessageKind::Move));
+    drop(bus.subscribe(Filter::ALL));
+    assert_eq!(bus.subscribers(), 2);
+    // the dropped subscription is forgotten the first time it's sent to
+    bus.publish(Message::Move { x: 1, y: 0 }).unwrap();
+    assert_eq!(bus.subscribers(), 1);
+
+    // with every handle gone the subscription runs out without a Quit
+    let worker = thread::spawn(move || kept.count());
+    bus.publish(Message::Write(String::from("not for kept")))
+        .unwrap();
+    drop(bus);
+    assert_eq!(worker.join().unwrap(), 1);
+}
+
+#[test]
+fn filters() {
+    let moves = Filter::from(MessageKind::Move);
+    assert!(moves.matches(&Message::Move { x: 0, y: 0 }));
+    assert!(!moves.matches(&Message::Quit));
+    assert!(!Filter::NONE.allows(MessageKind::Quit));
+    let kinds = [
+        MessageKind::Quit,
+        MessageKind::Move,
+        MessageKind::Write,
+        MessageKind::ChangeColor,
+    ];
+    let all = kinds.iter().fold(Filter::NONE, |f, &k| f.and(k));
+    assert_eq!(all, Filter::ALL);
+}
@@ -0,0 +1,232 @@
+// Money across currencies: amounts that won't mix, rates read from a file,
+// and every rounding mode on the cases where they disagree
+use ch6_enum_patternmatch::money::{
+    greedy_change, Coin, ConvertError, Currency, CurrencyCoin, ExchangeRates, LineError, LoadError,
+    Money, MoneyError, Rate, RateError, Rounding,
+};
+
+fn rates(file: &str) -> ExchangeRates {
+    ExchangeRates::read_from(file.as_bytes()).unwrap()
+}
+
+#[test]
+fn money_keeps_to_its_currency() {
+    let dollars = Money::new(Currency::Usd, 150);
+    let euros = Money::major(Currency::Eur, 2).unwrap();
+    assert_eq!(euros.minor(), 200);
+    assert_eq!(Money::major(Currency::Jpy, 2).unwrap().minor(), 2);
+    assert_eq!(Money::major(Currency::Usd, u64::MAX), None);
+
+    assert_eq!(
+        dollars.add(&euros),
+        Err(MoneyError::Mismatch {
+            left: Currency::Usd,
+            right: Currency::Eur
+        })
+    );
+    assert_eq!(
+        dollars.add(&Money::new(Currency::Usd, 25)),
+        Ok(Money::new(Currency::Usd, 175))
+    );
+    assert_eq!(
+        dollars.sub(&Money::new(Currency::Usd, 151)),
+        Err(MoneyError::Negative)
+    );
+    assert_eq!(
+        Money::new(Currency::Gbp, u64::MAX).add(&Money::new(Currency::Gbp, 1)),
+        Err(MoneyError::Overflow)
+    );
+
+    let amounts = [dollars, Money::new(Currency::Usd, 5), euros];
+    assert_eq!(
+        Money::total(Currency::Usd, &amounts[..2]),
+        Ok(Money::new(Currency::Usd, 155))
+    );
+    assert!(Money::total(Currency::Usd, &amounts).is_err());
+    assert_eq!(
+        Money::total(Currency::Jpy, &[]),
+        Ok(Money::zero(Currency::Jpy))
+    );
+
+    assert_eq!(dollars.to_string(), "$1.50");
+    assert_eq!(Money::new(Currency::Eur, 5).to_string(), "€0.05");
+    assert_eq!(Money::new(Currency::Gbp, 1999).to_string(), "£19.99");
+    assert_eq!(Money::new(Currency::Jpy, 500).to_string(), "¥500");
+}
+
+#[test]
+fn coins_belong_to_a_currency() {
+    for currency in &Currency::ALL {
+        let coins = currency.coins();
+        assert!(coins.iter().all(|coin| coin.currency() == *currency));
+        // every one of them can make any amount, there's a 1 in each
+        let change = greedy_change(388, &coins).unwrap();
+        let total: u64 = change
+            .iter()
+            .map(|(coin, n)| coin.value().minor() * n)
+            .sum();
+        assert_eq!(total, 388);
+        assert_eq!(currency.to_string().parse::<Currency>(), Ok(*currency));
+    }
+    assert_eq!("jpy".parse::<Currency>(), Ok(Currency::Jpy));
+    assert!("XYZ".parse::<Currency>().is_err());
+
+    assert!(CurrencyCoin::new(Currency::Eur, 3).is_none());
+    assert!(CurrencyCoin::new(Currency::Usd, 200).is_none());
+    let two_euro = CurrencyCoin::new(Currency::Eur, 200).unwrap();
+    assert_eq!(two_euro.to_string(), "€2.00 coin");
+    assert_eq!(
+        CurrencyCoin::from(Coin::Quarter),
+        CurrencyCoin::new(Currency::Usd, 25).unwrap()
+    );
+    assert_eq!(Coin::Dime.currency(), Currency::Usd);
+}
+
+#[test]
+fn rounding_modes() {
+    // 1 to 0.5 makes every odd amount land on a half
+    let half = rates("USD EUR 0.5");
+    let convert = |minor, rounding| {
+        half.convert(&Money::new(Currency::Usd, minor), Currency::Eur, rounding)
+            .unwrap()
+            .minor()
+    };
+    let cases = [
+        // cents, down, up, half up, half even
+        (4, 2, 2, 2, 2),
+        (5, 2, 3, 3, 2),
+        (7, 3, 4, 4, 4),
+        (9, 4, 5, 5, 4),
+    ];
+    for &(cents, down, up, half_up, half_even) in &cases {
+        assert_eq!(convert(cents, Rounding::Down), down, "{} down", cents);
+        assert_eq!(convert(cents, Rounding::Up), up, "{} up", cents);
+        assert_eq!(
+            convert(cents, Rounding::HalfUp),
+            half_up,
+            "{} half up",
+            cents
+        );
+        assert_eq!(
+            convert(cents, Rounding::HalfEven),
+            half_even,
+            "{} half even",
+            cents
+        );
+    }
+
+    // and off a half, the two half modes agree on the nearer one
+    let third = rates("GBP EUR 0.3");
+    let convert = |rounding| {
+        third
+            .convert(&Money::new(Currency::Gbp, 1), Currency::Eur, rounding)
+            .unwrap()
+            .minor()
+    };
+    assert_eq!(convert(Rounding::HalfUp), 0);
+    assert_eq!(convert(Rounding::HalfEven), 0);
+    assert_eq!(convert(Rounding::Up), 1);
+}
+
+#[test]
+fn conversions_use_the_exponents() {
+    let table = rates(
+        "# a day's rates\n\
+         USD EUR 0.92\n\
+         \n\
+         USD JPY 149.5   # yen has no cents\n\
+         GBP USD 1.27\n",
+    );
+    assert_eq!(table.len(), 3);
+
+    let convert = |minor, from, to| {
+        table
+            .convert(&Money::new(from, minor), to, Rounding::HalfEven)
+            .map(|money| money.to_string())
+    };
+    assert_eq!(
+        convert(1000, Currency::Usd, Currency::Eur).unwrap(),
+        "€9.20"
+    );
+    assert_eq!(
+        convert(1000, Currency::Usd, Currency::Jpy).unwrap(),
+        "¥1495"
+    );
+    // backwards through the same pair, 1495 / 149.5 is exactly 10
+    assert_eq!(
+        convert(1495, Currency::Jpy, Currency::Usd).unwrap(),
+        "$10.00"
+    );
+    assert_eq!(convert(100, Currency::Usd, Currency::Gbp).unwrap(), "£0.79");
+    assert_eq!(convert(123, Currency::Eur, Currency::Eur).unwrap(), "€1.23");
+    assert_eq!(
+        convert(100, Currency::Eur, Currency::Jpy),
+        Err(ConvertError::NoRate {
+            from: Currency::Eur,
+            to: Currency::Jpy
+        })
+    );
+    assert_eq!(
+        convert(u64::MAX, Currency::Usd, Currency::Jpy),
+        Err(ConvertError::Overflow)
+    );
+
+    assert_eq!(
+        table.get(Currency::Usd, Currency::Eur).unwrap().to_string(),
+        "0.92"
+    );
+    assert_eq!(
+        table.get(Currency::Eur, Currency::Usd).unwrap().to_string(),
+        "1/0.92"
+    );
+}
+
+#[test]
+fn bad_rate_files_say_where() {
+    let line_error = |file: &str| match ExchangeRates::read_from(file.as_bytes()) {
+        Err(LoadError::Line { line, source }) => (line, source),
+        other => panic!("expected a line error, got {:?}", other),
+    };
+
+    assert!(matches!(
+        line_error("USD EUR 0.92\nUSD XYZ 1"),
+        (2, LineError::UnknownCurrency(_))
+    ));
+    assert_eq!(line_error("USD EUR"), (1, LineError::Missing));
+    assert_eq!(
+        line_error("USD EUR 0"),
+        (1, LineError::Rate(RateError::Zero))
+    );
+    for bad in &["-1", "1.", ".5", "1e3", "0.1.2"] {
+        assert_eq!(
+            line_error(&format!("USD EUR {}", bad)),
+            (1, LineError::Rate(RateError::Invalid(bad.to_string())))
+        );
+    }
+    assert_eq!(
+        line_error("USD EUR 0.92 today"),
+        (1, LineError::Trailing(String::from("today")))
+    );
+    assert_eq!(
+        line_error("USD USD 1"),
+        (1, LineError::SameCurrency(Currency::Usd))
+    );
+    // the same pair backwards is still the same pair
+    assert_eq!(
+        line_error("USD EUR 0.92\n# later\nEUR USD 1.09"),
+        (
+            3,
+            LineError::Duplicate {
+                from: Currency::Eur,
+                to: Currency::Usd,
+                first_line: 1
+            }
+        )
+    );
+
+    assert!(matches!(
+        ExchangeRates::load("/no/such/rates/file"),
+        Err(LoadError::Io(_))
+    ));
+    assert_eq!("149.5".parse::<Rate>().unwrap().to_string(), "149.5");
+}
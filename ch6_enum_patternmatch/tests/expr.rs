@@ -0,0 +1,436 @@
+// the expression language: the evaluator on every variant of Expr and every
+// error, the parser on precedence and its mistakes, and two properties over
+// random trees from a seeded rng: printing parses back to the same tree,
+// and folding never changes what an expression evaluates to
+use ch6_enum_patternmatch::expr::{
+    BinOp, Env, EvalError, Expr, ParseError, Statement, Type, UnOp, Value, MAX_DEPTH,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn eval(src: &str) -> Result<Value, EvalError> {
+    let mut env = Env::new();
+    env.set("x", Value::Int(10));
+    env.set("yes", Value::Bool(true));
+    src.parse::<Expr>()
+        .unwrap_or_else(|e| panic!("{:?}: {}", src, e))
+        .eval(&env)
+}
+
+fn int(src: &str) -> i64 {
+    match eval(src) {
+        Ok(Value::Int(n)) => n,
+        other => panic!("{:?} gave {:?}", src, other),
+    }
+}
+
+fn boolean(src: &str) -> bool {
+    match eval(src) {
+        Ok(Value::Bool(b)) => b,
+        other => panic!("{:?} gave {:?}", src, other),
+    }
+}
+
+fn mismatch(op: &'static str, expected: Type, found: Type) -> Result<Value, EvalError> {
+    Err(EvalError::TypeMismatch {
+        op,
+        expected,
+        found,
+    })
+}
+
+#[test]
+fn literals_and_variables() {
+    assert_eq!(Expr::int(3).eval(&Env::new()), Ok(Value::Int(3)));
+    assert_eq!(Expr::bool(false).eval(&Env::new()), Ok(Value::Bool(false)));
+    assert_eq!(int("-9223372036854775808"), i64::MIN);
+
+    assert_eq!(int("x"), 10);
+    assert!(boolean("yes"));
+    assert_eq!(
+        eval("nope"),
+        Err(EvalError::UnboundVariable(String::from("nope")))
+    );
+}
+
+#[test]
+fn unary_operators() {
+    assert_eq!(int("-x"), -10);
+    assert_eq!(int("--x"), 10);
+    assert!(!boolean("!yes"));
+    assert!(boolean("!!yes"));
+    assert_eq!(eval("-yes"), mismatch("-", Type::Int, Type::Bool));
+    assert_eq!(eval("!x"), mismatch("!", Type::Bool, Type::Int));
+    assert_eq!(eval("-(-9223372036854775808)"), Err(EvalError::Overflow));
+}
+
+#[test]
+fn arithmetic() {
+    assert_eq!(int("1 + 2 * 3"), 7);
+    assert_eq!(int("(1 + 2) * 3"), 9);
+    assert_eq!(int("10 - 3 - 2"), 5); // left to right
+    assert_eq!(int("-7 / 2"), -3); // towards zero
+    assert_eq!(int("-7 % 2"), -1);
+    assert_eq!(int("x * x % 7"), 2);
+
+    assert_eq!(eval("1 / 0"), Err(EvalError::DivisionByZero));
+    assert_eq!(eval("x % (x - 10)"), Err(EvalError::DivisionByZero));
+    assert_eq!(eval("9223372036854775807 + 1"), Err(EvalError::Overflow));
+    assert_eq!(eval("-9223372036854775808 - 1"), Err(EvalError::Overflow));
+    assert_eq!(eval("4294967296 * 4294967296"), Err(EvalError::Overflow));
+    assert_eq!(eval("-9223372036854775808 / -1"), Err(EvalError::Overflow));
+    assert_eq!(eval("-9223372036854775808 % -1"), Err(EvalError::Overflow));
+
+    assert_eq!(eval("1 + yes"), mismatch("+", Type::Int, Type::Bool));
+    assert_eq!(eval("yes * 2"), mismatch("*", Type::Int, Type::Bool));
+    // the left side goes first, so its error wins
+    assert_eq!(
+        eval("nope + 1 / 0"),
+        Err(EvalError::UnboundVariable(String::from("nope")))
+    );
+}
+
+#[test]
+fn comparisons() {
+    assert!(boolean("1 < 2"));
+    assert!(boolean("2 <= 2"));
+    assert!(!boolean("1 > 2"));
+    assert!(boolean("x >= 10"));
+    assert!(boolean("x == 10"));
+    assert!(boolean("x != 11"));
+    assert!(boolean("yes == true"));
+    assert!(boolean("yes != false"));
+
+    assert_eq!(eval("1 == true"), mismatch("==", Type::Int, Type::Bool));
+    assert_eq!(eval("false != 0"), mismatch("!=", Type::Bool, Type::Int));
+    assert_eq!(eval("yes < 1"), mismatch("<", Type::Int, Type::Bool));
+}
+
+#[test]
+fn logic_short_circuits() {
+    assert!(boolean("yes && 1 < 2"));
+    assert!(!boolean("yes && false"));
+    assert!(boolean("false || yes"));
+    assert!(!boolean("false || false"));
+    // && binds tighter than ||
+    assert!(boolean("true || false && false"));
+
+    // the right side is never looked at, so it can't fail
+    assert!(!boolean("false && 1 / 0 == 1"));
+    assert!(boolean("true || nope"));
+    assert!(!boolean("false && 5"));
+
+    assert_eq!(eval("1 && yes"), mismatch("&&", Type::Bool, Type::Int));
+    assert_eq!(eval("yes && 1"), mismatch("&&", Type::Bool, Type::Int));
+    assert_eq!(eval("false || x"), mismatch("||", Type::Bool, Type::Int));
+}
+
+#[test]
+fn if_only_evaluates_one_branch() {
+    assert_eq!(int("if yes then 1 else 2"), 1);
+    assert_eq!(int("if x > 100 then 1 else 2"), 2);
+    assert_eq!(int("if true then x else 1 / 0"), 10);
+    assert_eq!(int("if false then nope else 3"), 3);
+    // if reaches as far right as it can, even after an operator
+    assert_eq!(int("1 + if yes then 2 else 3 * 10"), 3);
+    assert_eq!(int("if yes then if false then 1 else 2 else 3"), 2);
+    assert_eq!(
+        eval("if 1 then 2 else 3"),
+        mismatch("if", Type::Bool, Type::Int)
+    );
+    // and it's an expression, so the branches can differ in type
+    assert!(boolean("if false then 1 else true"));
+}
+
+#[test]
+fn let_binds_in_its_body_only() {
+    assert_eq!(int("let y = 3 in y * y"), 9);
+    assert_eq!(int("let x = 1 in x"), 1); // shadows the env's x
+    assert_eq!(int("let y = x in let x = 2 in x + y"), 12);
+    // the value is worked out before the name's bound, so this is the
+    // outer x
+    assert_eq!(int("let x = x + 1 in x"), 11);
+    assert_eq!(int("(let y = 1 in y) + x"), 11);
+    assert_eq!(
+        eval("(let y = 1 in y) + y"),
+        Err(EvalError::UnboundVariable(String::from("y")))
+    );
+    assert_eq!(eval("let y = 1 / 0 in 5"), Err(EvalError::DivisionByZero));
+}
+
+#[test]
+fn parse_errors() {
+    let err = |src: &str| src.parse::<Expr>().unwrap_err();
+    assert_eq!(err("   "), ParseError::Empty);
+    assert_eq!(err("1 $ 2"), ParseError::UnexpectedChar { pos: 2, ch: '$' });
+    assert_eq!(
+        err("1 +"),
+        ParseError::UnexpectedEnd {
+            expected: "a value"
+        }
+    );
+    assert_eq!(err("(1 + 2"), ParseError::UnexpectedEnd { expected: ")" });
+    assert_eq!(
+        err("1 2"),
+        ParseError::Unexpected {
+            pos: 2,
+            found: String::from("2"),
+            expected: "an operator or the end"
+        }
+    );
+    assert_eq!(
+        err("if yes then 1"),
+        ParseError::UnexpectedEnd { expected: "else" }
+    );
+    assert_eq!(
+        err("let 5 = 1 in 2"),
+        ParseError::Unexpected {
+            pos: 4,
+            found: String::from("5"),
+            expected: "a name"
+        }
+    );
+    assert_eq!(
+        err("9223372036854775808"),
+        ParseError::NumberTooBig { pos: 0 }
+    );
+    assert_eq!(err("1 < 2 < 3"), ParseError::ChainedComparison { pos: 6 });
+    assert_eq!(
+        err("1 + 1 == 2 == true"),
+        ParseError::ChainedComparison { pos: 11 }
+    );
+}
+
+#[test]
+fn nesting_has_a_limit() {
+    let brackets = |n: usize| format!("{}1{}", "(".repeat(n), ")".repeat(n));
+    let chain = |n: usize| format!("1{}", " + 1".repeat(n));
+    let minuses = |n: usize| format!("{}x", "- ".repeat(n));
+    let ifs = |n: usize| format!("{}1{}", "if yes then ".repeat(n), " else 0".repeat(n));
+
+    // right up to the limit parses, and everything that walks the tree
+    // copes with it. the whole line counts as one level of brackets
+    assert_eq!(int(&brackets(MAX_DEPTH - 1)), 1);
+    assert_eq!(int(&chain(MAX_DEPTH - 1)), MAX_DEPTH as i64);
+    assert_eq!(int(&minuses(MAX_DEPTH - 1)), -10);
+    assert_eq!(int(&ifs(MAX_DEPTH - 1)), 1);
+    for src in &[
+        chain(MAX_DEPTH - 1),
+        minuses(MAX_DEPTH - 1),
+        ifs(MAX_DEPTH - 1),
+    ] {
+        let expr = src.parse::<Expr>().unwrap();
+        assert_eq!(expr.to_string().parse(), Ok(expr.clone()));
+        let folded = expr.fold();
+        let mut env = Env::new();
+        env.set("x", Value::Int(10));
+        env.set("yes", Value::Bool(true));
+        assert_eq!(folded.eval(&env), expr.eval(&env));
+    }
+
+    // one more is an error, at the token that went over
+    let err = |src: &str| src.parse::<Expr>().unwrap_err();
+    let pos = MAX_DEPTH;
+    assert_eq!(err(&brackets(MAX_DEPTH)), ParseError::TooDeep { pos });
+    assert_eq!(
+        err(&chain(MAX_DEPTH)),
+        ParseError::TooDeep {
+            pos: 2 + 4 * (MAX_DEPTH - 1)
+        }
+    );
+    assert_eq!(
+        err(&minuses(MAX_DEPTH)),
+        ParseError::TooDeep { pos: 2 * MAX_DEPTH }
+    );
+    assert!(matches!(err(&ifs(MAX_DEPTH)), ParseError::TooDeep { .. }));
+    assert_eq!(
+        err(&brackets(MAX_DEPTH)).to_string(),
+        format!("at {}: nested more than {} deep", MAX_DEPTH, MAX_DEPTH)
+    );
+
+    // and far past it is the same error, not a stack overflow
+    assert!(matches!(err(&brackets(5_000)), ParseError::TooDeep { .. }));
+    assert!(matches!(err(&chain(200_000)), ParseError::TooDeep { .. }));
+    assert!(matches!(
+        err(&"-".repeat(300_000)),
+        ParseError::TooDeep { .. }
+    ));
+    assert!(matches!(
+        err(&format!("{}true", "!".repeat(300_000))),
+        ParseError::TooDeep { .. }
+    ));
+    assert!(matches!(
+        format!("let y = {}", chain(10_000)).parse::<Statement>(),
+        Err(ParseError::TooDeep { .. })
+    ));
+}
+
+#[test]
+fn statements() {
+    assert_eq!(
+        "let y = 1 + 2".parse(),
+        Ok(Statement::Let(
+            String::from("y"),
+            Expr::binary(BinOp::Add, Expr::int(1), Expr::int(2))
+        ))
+    );
+    assert_eq!(
+        "let y = 1 in y".parse(),
+        Ok(Statement::Expr(Expr::let_(
+            "y",
+            Expr::int(1),
+            Expr::var("y")
+        )))
+    );
+    assert_eq!("x".parse(), Ok(Statement::Expr(Expr::var("x"))));
+    assert!("let y =".parse::<Statement>().is_err());
+}
+
+#[test]
+fn folding() {
+    let fold = |src: &str| src.parse::<Expr>().unwrap().fold().to_string();
+    assert_eq!(fold("2 * 3 + x"), "6 + x");
+    assert_eq!(fold("x + 2 * 3"), "x + 6");
+    assert_eq!(fold("-(2 + 3)"), "-5");
+    assert_eq!(fold("!(1 < 2) || yes"), "false || yes");
+    assert_eq!(fold("if 1 < 2 then x else nope"), "x");
+    assert_eq!(fold("if yes then 1 + 1 else 2 + 2"), "if yes then 2 else 4");
+    assert_eq!(fold("false && nope"), "false");
+    assert_eq!(fold("true || 1 / 0"), "true");
+    assert_eq!(fold("let a = 5 in let b = a * 2 in b + a + x"), "15 + x");
+    // a let of the same name inside hides the outer one, but not in its
+    // own value
+    assert_eq!(fold("let a = 1 in let a = a + 1 in a * x"), "2 * x");
+    assert_eq!(fold("let a = x in a + 1"), "let a = x in a + 1");
+
+    // anything that would fail is left to fail when it runs
+    assert_eq!(fold("1 / 0 + x"), "1 / 0 + x");
+    assert_eq!(fold("-(-9223372036854775808)"), "- -9223372036854775808");
+    assert_eq!(fold("if 3 then 1 else 2"), "if 3 then 1 else 2");
+    assert_eq!(fold("true && 5"), "true && 5");
+    // x * 1 is only x when x is an int
+    assert_eq!(fold("x * 1"), "x * 1");
+}
+
+#[test]
+fn display_adds_only_the_brackets_it_needs() {
+    let show = |src: &str| src.parse::<Expr>().unwrap().to_string();
+    assert_eq!(show("((1 + 2)) * (3)"), "(1 + 2) * 3");
+    assert_eq!(show("1 + (2 * 3)"), "1 + 2 * 3");
+    assert_eq!(show("(1 - 2) - 3"), "1 - 2 - 3");
+    assert_eq!(show("1 - (2 - 3)"), "1 - (2 - 3)");
+    assert_eq!(show("(1 < 2) == true"), "(1 < 2) == true");
+    assert_eq!(show("-(x)"), "-x");
+    assert_eq!(show("-(5)"), "-(5)");
+    assert_eq!(show("- -5"), "- -5");
+    assert_eq!(
+        show("(if yes then 1 else 2) + 1"),
+        "(if yes then 1 else 2) + 1"
+    );
+    assert_eq!(show("let a=1 in(a)"), "let a = 1 in a");
+    assert_eq!(
+        Expr::unary(
+            UnOp::Not,
+            Expr::binary(BinOp::And, Expr::bool(true), Expr::var("b"))
+        )
+        .to_string(),
+        "!(true && b)"
+    );
+}
+
+const NAMES: [&str; 3] = ["a", "b", "c"];
+
+// a random tree, mostly well typed but not always, so errors come up too
+fn random_expr(rng: &mut StdRng, depth: u32) -> Expr {
+    if depth == 0 || rng.gen_range(0..4) == 0 {
+        return match rng.gen_range(0..6) {
+            0 => Expr::bool(rng.gen()),
+            1 | 2 => Expr::var(NAMES[rng.gen_range(0..NAMES.len())]),
+            3 => Expr::int([i64::MIN, i64::MAX, -1][rng.gen_range(0..3)]),
+            _ => Expr::int(rng.gen_range(-5..=5)),
+        };
+    }
+    match rng.gen_range(0..10) {
+        0 => {
+            let op = if rng.gen() { UnOp::Neg } else { UnOp::Not };
+            Expr::unary(op, random_expr(rng, depth - 1))
+        }
+        1 => Expr::if_(
+            random_expr(rng, depth - 1),
+            random_expr(rng, depth - 1),
+            random_expr(rng, depth - 1),
+        ),
+        2 => {
+            let name = NAMES[rng.gen_range(0..NAMES.len())];
+            Expr::let_(
+                name,
+                random_expr(rng, depth - 1),
+                random_expr(rng, depth - 1),
+            )
+        }
+        _ => {
+            let ops = [
+                BinOp::Add,
+                BinOp::Sub,
+                BinOp::Mul,
+                BinOp::Div,
+                BinOp::Rem,
+                BinOp::Eq,
+                BinOp::Ne,
+                BinOp::Lt,
+                BinOp::Le,
+                BinOp::Gt,
+                BinOp::Ge,
+                BinOp::And,
+                BinOp::Or,
+            ];
+            let op = ops[rng.gen_range(0..ops.len())];
+            Expr::binary(op, random_expr(rng, depth - 1), random_expr(rng, depth - 1))
+        }
+    }
+}
+
+#[test]
+fn printing_parses_back() {
+    let mut rng = StdRng::seed_from_u64(50);
+    for _ in 0..2000 {
+        let expr = random_expr(&mut rng, 5);
+        let printed = expr.to_string();
+        assert_eq!(printed.parse::<Expr>(), Ok(expr), "{}", printed);
+    }
+}
+
+#[test]
+fn folding_changes_nothing_but_the_tree() {
+    let mut rng = StdRng::seed_from_u64(5050);
+    let mut envs = vec![Env::new()];
+    for &(a, b) in &[(0, true), (3, false), (-7, true)] {
+        let mut env = Env::new();
+        env.set("a", Value::Int(a));
+        env.set("b", Value::Bool(b));
+        env.set("c", Value::Int(i64::MAX));
+        envs.push(env);
+    }
+
+    let mut folded_to_literal = 0;
+    for _ in 0..3000 {
+        let expr = random_expr(&mut rng, 5);
+        let folded = expr.fold();
+        for env in &envs {
+            assert_eq!(
+                folded.eval(env),
+                expr.eval(env),
+                "{} folded to {}",
+                expr,
+                folded
+            );
+        }
+        // folding twice finds nothing more
+        assert_eq!(folded.fold(), folded, "{}", expr);
+        if let Expr::Lit(_) = folded {
+            folded_to_literal += 1;
+        }
+    }
+    // and it does actually fold things
+    assert!(folded_to_literal > 300, "only {}", folded_to_literal);
+}
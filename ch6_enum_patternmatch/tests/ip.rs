@@ -0,0 +1,303 @@
+// the ip module is meant to agree with std::net on everything they both do:
+// which strings parse, what they parse to, how they print and how they're
+// classified. random inputs from a seeded rng, so failures repeat
+use ch6_enum_patternmatch::ip::{AddrParseError, Cidr, CidrError, IpAddr, IpAddrKind};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::net;
+
+const CASES: usize = 50_000;
+
+// v6 addresses with lots of zero runs, since that's where printing gets
+// interesting, and some v4 mapped ones
+fn random_addr(rng: &mut StdRng) -> IpAddr {
+    match rng.gen_range(0..4) {
+        0 => IpAddr::V4(rng.gen()),
+        1 => {
+            let [a, b, c, d]: [u8; 4] = rng.gen();
+            IpAddr::V6([
+                0,
+                0,
+                0,
+                0,
+                0,
+                0xffff,
+                u16::from_be_bytes([a, b]),
+                u16::from_be_bytes([c, d]),
+            ])
+        }
+        _ => {
+            let mut segments = [0u16; 8];
+            for s in segments.iter_mut() {
+                if rng.gen_bool(0.5) {
+                    *s = rng.gen();
+                }
+            }
+            IpAddr::V6(segments)
+        }
+    }
+}
+
+fn std_parse(s: &str) -> Option<IpAddr> {
+    s.parse::<net::IpAddr>().ok().map(IpAddr::from)
+}
+
+#[test]
+fn display_matches_std() {
+    let mut rng = StdRng::seed_from_u64(1);
+    for _ in 0..CASES {
+        let addr = random_addr(&mut rng);
+        let ours = addr.to_string();
+        assert_eq!(ours, net::IpAddr::from(addr).to_string());
+        assert_eq!(ours.parse(), Ok(addr));
+    }
+}
+
+#[test]
+fn classification_matches_std() {
+    let mut rng = StdRng::seed_from_u64(2);
+    let special = [
+        "0.0.0.0",
+        "127.0.0.1",
+        "127.255.255.255",
+        "10.0.0.0",
+        "172.15.255.255",
+        "172.16.0.0",
+        "172.31.255.255",
+        "172.32.0.0",
+        "192.168.0.1",
+        "192.169.0.1",
+        "169.254.1.1",
+        "224.0.0.1",
+        "239.255.255.255",
+        "240.0.0.0",
+        "::",
+        "::1",
+        "::2",
+        "fc00::",
+        "fdff::1",
+        "fe00::",
+        "fe80::1",
+        "febf::1",
+        "fec0::1",
+        "ff02::1",
+    ];
+    let addrs = special
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .chain((0..CASES).map(|_| random_addr(&mut rng)));
+
+    for addr in addrs {
+        let desc = addr.to_string();
+        assert_eq!(
+            addr.is_unspecified(),
+            net::IpAddr::from(addr).is_unspecified(),
+            "{}",
+            desc
+        );
+        assert_eq!(
+            addr.is_loopback(),
+            net::IpAddr::from(addr).is_loopback(),
+            "{}",
+            desc
+        );
+        assert_eq!(
+            addr.is_multicast(),
+            net::IpAddr::from(addr).is_multicast(),
+            "{}",
+            desc
+        );
+        match net::IpAddr::from(addr) {
+            net::IpAddr::V4(v4) => {
+                assert_eq!(addr.is_private(), v4.is_private(), "{}", desc);
+                assert_eq!(addr.is_link_local(), v4.is_link_local(), "{}", desc);
+            }
+            net::IpAddr::V6(v6) => {
+                assert_eq!(addr.is_private(), v6.is_unique_local(), "{}", desc);
+                assert_eq!(addr.is_link_local(), v6.is_unicast_link_local(), "{}", desc);
+            }
+        }
+    }
+}
+
+#[test]
+fn parsing_matches_std() {
+    let cases = [
+        "1.2.3.4",
+        "255.255.255.255",
+        "256.0.0.0",
+        "1.2.3",
+        "1.2.3.4.5",
+        "01.2.3.4",
+        "0.0.0.0",
+        "+1.2.3.4",
+        "1..3.4",
+        " 1.2.3.4",
+        "1.2.3.4 ",
+        "::",
+        "::1",
+        "1::",
+        "1:2:3:4:5:6:7:8",
+        "1:2:3:4:5:6:7:8:9",
+        "1:2:3:4:5:6:7::",
+        "1:2:3:4::5:6:7:8",
+        "::1:2:3:4:5:6:7",
+        "1::2::3",
+        ":::",
+        ":1::",
+        "1:",
+        ":1",
+        "12345::",
+        "g::",
+        "::ffff:1.2.3.4",
+        "::1.2.3.4",
+        "1.2.3.4::",
+        "::ffff:1.2.3",
+        "1:2:3:4:5:6:1.2.3.4",
+        "1:2:3:4:5:6:7:1.2.3.4",
+        "FFFF::",
+        "0000:0000::",
+        "::+1",
+        "",
+        ".",
+        ":",
+    ];
+    for case in &cases {
+        assert_eq!(case.parse().ok(), std_parse(case), "{:?}", case);
+    }
+
+    // and a pile of random junk made of the characters addresses use
+    let mut rng = StdRng::seed_from_u64(3);
+    let alphabet = b"0123456789abcdefABCDEF:.:.:.";
+    for _ in 0..CASES {
+        let len = rng.gen_range(0..20);
+        let s: String = (0..len)
+            .map(|_| alphabet[rng.gen_range(0..alphabet.len())] as char)
+            .collect();
+        assert_eq!(s.parse().ok(), std_parse(&s), "{:?}", s);
+    }
+}
+
+#[test]
+fn parse_errors() {
+    let err = |s: &str| s.parse::<IpAddr>().unwrap_err();
+    assert_eq!(err(""), AddrParseError::Empty);
+    assert_eq!(err("1.2.3"), AddrParseError::OctetCount(3));
+    assert_eq!(err("1.2.3.04"), AddrParseError::Octet("04".to_string()));
+    assert_eq!(err("1:2:3"), AddrParseError::SegmentCount(3));
+    assert_eq!(err("1:2:3:4::5:6:7:8"), AddrParseError::NothingToCompress);
+    assert_eq!(err("1::2::3"), AddrParseError::DoubleColons);
+    assert_eq!(err("12345::"), AddrParseError::Segment("12345".to_string()));
+    assert_eq!(err("::1.2.3.999"), AddrParseError::Octet("999".to_string()));
+}
+
+#[test]
+fn bits_round_trip() {
+    let mut rng = StdRng::seed_from_u64(4);
+    for _ in 0..CASES {
+        let addr = random_addr(&mut rng);
+        assert_eq!(IpAddr::from_bits(addr.kind(), addr.to_bits()), addr);
+        let std_bits = match net::IpAddr::from(addr) {
+            net::IpAddr::V4(v4) => u128::from(u32::from(v4)),
+            net::IpAddr::V6(v6) => u128::from(v6),
+        };
+        assert_eq!(addr.to_bits(), std_bits);
+    }
+}
+
+#[test]
+fn cidr_contains() {
+    let net: Cidr = "10.1.2.3/8".parse().unwrap();
+    assert_eq!(net.to_string(), "10.0.0.0/8", "host bits get cleared");
+    assert!(net.contains(&"10.255.0.1".parse().unwrap()));
+    assert!(!net.contains(&"11.0.0.0".parse().unwrap()));
+    assert!(!net.contains(&"::a00:1".parse().unwrap()));
+    assert!(net.contains_cidr(&"10.20.0.0/16".parse().unwrap()));
+    assert!(!net.contains_cidr(&"10.0.0.0/7".parse().unwrap()));
+
+    // against the slow way, on random networks and addresses
+    let mut rng = StdRng::seed_from_u64(5);
+    for _ in 0..CASES {
+        let addr = random_addr(&mut rng);
+        let prefix = rng.gen_range(0..=addr.kind().bits());
+        let cidr = Cidr::new(random_addr(&mut rng), prefix).unwrap_or_else(|_| Cidr::host(addr));
+        let shift = u32::from(cidr.kind().bits() - cidr.prefix());
+        let same_network = addr.kind() == cidr.kind()
+            && addr.to_bits().checked_shr(shift).unwrap_or(0)
+                == cidr.network().to_bits().checked_shr(shift).unwrap_or(0);
+        assert_eq!(cidr.contains(&addr), same_network, "{} {}", cidr, addr);
+    }
+}
+
+#[test]
+fn cidr_ranges() {
+    let net: Cidr = "192.168.0.0/28".parse().unwrap();
+    let addrs: Vec<IpAddr> = net.addrs().collect();
+    assert_eq!(addrs.len(), 16);
+    assert_eq!(addrs[0], net.network());
+    assert_eq!(addrs[15], net.last());
+    assert_eq!(net.netmask().to_string(), "255.255.255.240");
+    assert!(addrs.iter().all(|a| net.contains(a)));
+
+    let all_v4: Cidr = "0.0.0.0/0".parse().unwrap();
+    assert_eq!(all_v4.size(), Some(1 << 32));
+    assert_eq!(all_v4.last().to_string(), "255.255.255.255");
+    let halves: Vec<String> = all_v4.subnets(1).unwrap().map(|c| c.to_string()).collect();
+    assert_eq!(halves, ["0.0.0.0/1", "128.0.0.0/1"]);
+
+    // everything, which is one more address than a u128 can count
+    let all_v6: Cidr = "::/0".parse().unwrap();
+    assert_eq!(all_v6.size(), None);
+    assert_eq!(
+        all_v6.last().to_string(),
+        "ffff:ffff:ffff:ffff:ffff:ffff:ffff:ffff"
+    );
+    assert_eq!(all_v6.subnets(1).unwrap().count(), 2);
+    let top: Cidr = "ffff:ffff:ffff:ffff:ffff:ffff:ffff:fffe/127"
+        .parse()
+        .unwrap();
+    assert_eq!(
+        top.addrs().count(),
+        2,
+        "stops at the last address without overflowing"
+    );
+    assert_eq!(top.subnets(128).unwrap().count(), 2);
+
+    assert_eq!(net.subnets(30).unwrap().count(), 4);
+    assert_eq!(net.subnets(8).unwrap().collect::<Vec<_>>(), [net]);
+    assert_eq!(
+        net.subnets(33).unwrap_err(),
+        CidrError::PrefixTooLong {
+            prefix: 33,
+            max: 32
+        }
+    );
+    assert_eq!(Cidr::host("::1".parse().unwrap()).size(), Some(1));
+    assert_eq!(Cidr::host("::1".parse().unwrap()).kind(), IpAddrKind::V6);
+}
+
+#[test]
+fn cidr_errors() {
+    let err = |s: &str| s.parse::<Cidr>().unwrap_err();
+    assert_eq!(err("10.0.0.0"), CidrError::Prefix(String::new()));
+    assert_eq!(err("10.0.0.0/x"), CidrError::Prefix("x".to_string()));
+    assert_eq!(err("10.0.0.0/+8"), CidrError::Prefix("+8".to_string()));
+    assert_eq!(
+        err("10.0.0.0/33"),
+        CidrError::PrefixTooLong {
+            prefix: 33,
+            max: 32
+        }
+    );
+    assert_eq!(
+        err("10.0.0/8"),
+        CidrError::Addr(AddrParseError::OctetCount(3))
+    );
+    assert_eq!(
+        err("::/129"),
+        CidrError::PrefixTooLong {
+            prefix: 129,
+            max: 128
+        }
+    );
+}
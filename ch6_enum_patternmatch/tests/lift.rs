@@ -0,0 +1,170 @@
+// every helper in src/lift.rs against the std one liner its doc says it's
+// the same as. i8 and u8 so overflow comes up all the time, from a seeded
+// rng so failures repeat
+use ch6_enum_patternmatch::lift::{self, Overflow};
+use rand::distributions::{Distribution, Standard};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const ROUNDS: usize = 2000;
+
+// None about a quarter of the time
+fn maybe<T>(rng: &mut StdRng) -> Option<T>
+where
+    Standard: Distribution<T>,
+{
+    if rng.gen_range(0..4) == 0 {
+        None
+    } else {
+        Some(rng.gen())
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Error {
+    Bad(u8),
+    Overflow,
+}
+
+impl From<Overflow> for Error {
+    fn from(_: Overflow) -> Error {
+        Error::Overflow
+    }
+}
+
+// an error about a sixth of the time
+fn result<T>(rng: &mut StdRng) -> Result<T, Error>
+where
+    Standard: Distribution<T>,
+{
+    match rng.gen_range(0..6) {
+        0 => Err(Error::Bad(rng.gen::<u8>())),
+        _ => Ok(rng.gen()),
+    }
+}
+
+#[test]
+fn option_arithmetic() {
+    let mut rng = StdRng::seed_from_u64(49);
+    for _ in 0..ROUNDS {
+        let a = maybe::<i8>(&mut rng);
+        let b = maybe::<i8>(&mut rng);
+        assert_eq!(lift::inc(a), a.and_then(|n| n.checked_add(1)));
+        let add = || a?.checked_add(b?);
+        assert_eq!(lift::add(a, b), add(), "{:?} + {:?}", a, b);
+        let mul = || a?.checked_mul(b?);
+        assert_eq!(lift::mul(a, b), mul(), "{:?} * {:?}", a, b);
+    }
+    assert_eq!(lift::inc(Some(u128::MAX)), None);
+    assert_eq!(lift::inc(Some(-1i64)), Some(0));
+    assert_eq!(lift::mul(Some(-128i8), Some(-1)), None);
+}
+
+#[test]
+fn option_sum_and_product() {
+    let mut rng = StdRng::seed_from_u64(4949);
+    for _ in 0..ROUNDS {
+        let len = rng.gen_range(0..6);
+        let values: Vec<Option<u8>> = (0..len)
+            .map(|_| {
+                let n = rng.gen_range(0..8);
+                // None rarely, so whole lists get through sometimes
+                if rng.gen_range(0..10) == 0 {
+                    None
+                } else {
+                    Some(n)
+                }
+            })
+            .collect();
+        let sum = values.iter().try_fold(0u8, |sum, n| sum.checked_add((*n)?));
+        let product = values
+            .iter()
+            .try_fold(1u8, |product, n| product.checked_mul((*n)?));
+        assert_eq!(lift::sum(values.clone()), sum, "{:?}", values);
+        assert_eq!(lift::product(values.clone()), product, "{:?}", values);
+    }
+    assert_eq!(lift::sum(Vec::<Option<u8>>::new()), Some(0));
+    assert_eq!(lift::product(Vec::<Option<u8>>::new()), Some(1));
+    assert_eq!(lift::sum(vec![Some(200u8), Some(100)]), None);
+    // an overflow before a None is still just None
+    assert_eq!(lift::product(vec![Some(16u8), Some(16), None]), None);
+}
+
+#[test]
+fn option_zip_and_unzip() {
+    let mut rng = StdRng::seed_from_u64(94);
+    for _ in 0..ROUNDS {
+        let a = maybe::<u8>(&mut rng);
+        let b = maybe::<bool>(&mut rng);
+        let pair = lift::zip(a, b);
+        assert_eq!(pair, a.zip(b));
+        assert_eq!(lift::unzip(pair), pair.unzip());
+        if pair.is_some() {
+            assert_eq!(lift::unzip(pair), (a, b));
+        }
+    }
+}
+
+#[test]
+fn result_arithmetic() {
+    let mut rng = StdRng::seed_from_u64(490);
+    for _ in 0..ROUNDS {
+        let a = result::<i8>(&mut rng);
+        let b = result::<i8>(&mut rng);
+        let add =
+            || -> Result<i8, Error> { Ok(a.clone()?.checked_add(b.clone()?).ok_or(Overflow)?) };
+        let mul =
+            || -> Result<i8, Error> { Ok(a.clone()?.checked_mul(b.clone()?).ok_or(Overflow)?) };
+        assert_eq!(lift::try_add(a.clone(), b.clone()), add());
+        assert_eq!(lift::try_mul(a.clone(), b.clone()), mul());
+
+        let pair = lift::try_zip(a.clone(), b.clone());
+        let zipped = || -> Result<(i8, i8), Error> { Ok((a.clone()?, b.clone()?)) };
+        assert_eq!(pair, zipped());
+        let (first, second) = lift::try_unzip(pair.clone());
+        assert_eq!(first, pair.clone().map(|(a, _)| a));
+        assert_eq!(second, pair.map(|(_, b)| b));
+    }
+    // the first error wins, even over a second one
+    assert_eq!(
+        lift::try_add::<u8, _>(Err(Error::Bad(1)), Err(Error::Bad(2))),
+        Err(Error::Bad(1))
+    );
+    assert_eq!(
+        lift::try_add(Ok::<u8, Error>(255), Ok(1)),
+        Err(Error::Overflow)
+    );
+}
+
+#[test]
+fn result_sum_and_product() {
+    let mut rng = StdRng::seed_from_u64(9);
+    for _ in 0..ROUNDS {
+        let len = rng.gen_range(0..6);
+        let values: Vec<Result<u8, Error>> = (0..len)
+            .map(|_| {
+                let n = rng.gen_range(0..8);
+                if rng.gen_range(0..10) == 0 {
+                    Err(Error::Bad(n))
+                } else {
+                    Ok(n)
+                }
+            })
+            .collect();
+        let sum = values.iter().try_fold(0u8, |sum, n| {
+            let n = n.clone()?;
+            Ok(sum.checked_add(n).ok_or(Overflow)?)
+        });
+        let product = values.iter().try_fold(1u8, |product, n| {
+            let n = n.clone()?;
+            Ok(product.checked_mul(n).ok_or(Overflow)?)
+        });
+        assert_eq!(lift::try_sum(values.clone()), sum, "{:?}", values);
+        assert_eq!(lift::try_product(values.clone()), product, "{:?}", values);
+    }
+    // stops at the overflow, so the later error is never seen
+    assert_eq!(
+        lift::try_product(vec![Ok(16u8), Ok(16), Err(Error::Bad(0))]),
+        Err(Error::Overflow)
+    );
+}
@@ -0,0 +1,173 @@
+// both encodings have to give back exactly the message that went in, for
+// every variant. a hand picked case for each one, then random ones from a
+// seeded rng, so failures repeat
+use ch6_enum_patternmatch::message::{DecodeError, Message, ParseError, State};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const CASES: usize = 10_000;
+
+// the awkward bits for each variant: extremes, spaces, escapes, non-ASCII
+fn edge_cases() -> Vec<Message> {
+    vec![
+        Message::Quit,
+        Message::Move { x: 0, y: 0 },
+        Message::Move {
+            x: i32::MIN,
+            y: i32::MAX,
+        },
+        Message::Write(String::new()),
+        Message::Write(String::from("  leading and trailing  ")),
+        Message::Write(String::from("back\\slash \\n not a newline\nbut this is")),
+        Message::Write(String::from("# quit\nmove 1 2")),
+        Message::Write(String::from("héllo wörld ✓")),
+        Message::ChangeColor(0, 0, 0),
+        Message::ChangeColor(-1, 256, i32::MAX),
+    ]
+}
+
+fn random_message(rng: &mut StdRng) -> Message {
+    match rng.gen_range(0..4) {
+        0 => Message::Quit,
+        1 => Message::Move {
+            x: rng.gen(),
+            y: rng.gen(),
+        },
+        2 => {
+            let len = rng.gen_range(0..20);
+            // mostly printable, with the characters that need escaping
+            let text = (0..len)
+                .map(|_| match rng.gen_range(0..10) {
+                    0 => '\\',
+                    1 => '\n',
+                    2 => ' ',
+                    3 => rng.gen::<char>(),
+                    _ => rng.gen_range('a'..='z'),
+                })
+                .collect();
+            Message::Write(text)
+        }
+        _ => Message::ChangeColor(rng.gen(), rng.gen(), rng.gen()),
+    }
+}
+
+fn check_round_trip(message: &Message) {
+    let line = message.to_string();
+    assert!(!line.contains('\n'), "{:?} printed as {:?}", message, line);
+    assert_eq!(line.parse::<Message>().as_ref(), Ok(message), "{:?}", line);
+
+    let bytes = message.encode();
+    assert_eq!(Message::decode(&bytes), Ok((message.clone(), bytes.len())));
+    // every shorter prefix is cut off, not some other message
+    for end in 0..bytes.len() {
+        assert_eq!(
+            Message::decode(&bytes[..end]),
+            Err(DecodeError::UnexpectedEnd),
+            "{:?} cut at {}",
+            message,
+            end
+        );
+    }
+}
+
+#[test]
+fn every_variant_round_trips() {
+    for message in edge_cases() {
+        check_round_trip(&message);
+    }
+}
+
+#[test]
+fn random_messages_round_trip() {
+    let mut rng = StdRng::seed_from_u64(42);
+    let messages: Vec<Message> = (0..CASES).map(|_| random_message(&mut rng)).collect();
+    for message in &messages {
+        check_round_trip(message);
+    }
+
+    // all in one stream too
+    let mut bytes = Vec::new();
+    for message in &messages {
+        message.encode_into(&mut bytes);
+    }
+    assert_eq!(Message::decode_all(&bytes), Ok(messages));
+}
+
+#[test]
+fn bad_text() {
+    let parse = |line: &str| line.parse::<Message>();
+    assert_eq!(parse(""), Err(ParseError::Empty));
+    assert_eq!(
+        parse("Quit"),
+        Err(ParseError::UnknownCommand(String::from("Quit")))
+    );
+    assert_eq!(
+        parse("quit now"),
+        Err(ParseError::Trailing(String::from("now")))
+    );
+    assert_eq!(parse("move"), Err(ParseError::MissingArgument("x")));
+    assert_eq!(parse("color 1 2"), Err(ParseError::MissingArgument("blue")));
+    assert_eq!(
+        parse("move 1 two"),
+        Err(ParseError::Number(String::from("two")))
+    );
+    assert_eq!(
+        parse("move 1 2147483648"),
+        Err(ParseError::Number(String::from("2147483648")))
+    );
+    assert_eq!(
+        parse("write tab\\t"),
+        Err(ParseError::Escape(String::from("\\t")))
+    );
+    assert_eq!(
+        parse("write dangling\\"),
+        Err(ParseError::Escape(String::from("\\")))
+    );
+    // extra spaces between numbers are fine, write keeps its spaces
+    assert_eq!(parse("move  1   2 "), Ok(Message::Move { x: 1, y: 2 }));
+    assert_eq!(parse("write"), Ok(Message::Write(String::new())));
+}
+
+#[test]
+fn bad_bytes() {
+    assert_eq!(Message::decode(&[]), Err(DecodeError::UnexpectedEnd));
+    assert_eq!(Message::decode(&[4]), Err(DecodeError::UnknownTag(4)));
+    assert!(matches!(
+        Message::decode(&[2, 0, 0, 0, 2, 0xc3, 0x28]),
+        Err(DecodeError::Utf8(_))
+    ));
+    // a length far past the end is just cut off
+    assert_eq!(
+        Message::decode(&[2, 255, 255, 255, 255, b'a']),
+        Err(DecodeError::UnexpectedEnd)
+    );
+    // decode only takes the first message, decode_all wants them all whole
+    let mut bytes = Message::Quit.encode();
+    bytes.push(1);
+    assert_eq!(Message::decode(&bytes), Ok((Message::Quit, 1)));
+    assert_eq!(Message::decode_all(&bytes), Err(DecodeError::UnexpectedEnd));
+}
+
+#[test]
+fn dispatching() {
+    let mut state = State::new();
+    assert!(state.apply(&Message::Move { x: i32::MAX, y: -5 }));
+    assert!(state.apply(&Message::Move { x: 10, y: 5 }));
+    assert_eq!(state.position, (i32::MAX, 0)); // stuck at the edge
+    assert!(state.apply(&Message::Write(String::from("one"))));
+    assert!(state.apply(&Message::ChangeColor(1, 2, 3)));
+    assert!(state.apply(&Message::Quit));
+
+    let before = state.clone();
+    assert!(!state.apply(&Message::Write(String::from("two"))));
+    assert!(!state.apply(&Message::Quit));
+    assert_eq!(state, before);
+    assert_eq!(state.log, ["one"]);
+    assert_eq!(state.color, (1, 2, 3));
+
+    // run without a quit applies everything
+    let mut state = State::new();
+    let messages = edge_cases();
+    assert_eq!(state.run(&messages[1..]), messages.len() - 1);
+    assert!(!state.has_quit());
+}
@@ -0,0 +1,281 @@
+// change making checked against itself: whatever comes back has to add up,
+// optimal can never use more pieces than greedy, and greedy_is_optimal has
+// to agree with actually trying. random denominations from a seeded rng, so
+// failures repeat
+use ch6_enum_patternmatch::money::{
+    greedy_change, greedy_is_optimal, limited_change, optimal_change, Bill, Change, Coin,
+    Denomination, NotEnough, Piece, Purse, MAX_AMOUNT,
+};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+const SYSTEMS: usize = 300;
+
+fn total<D: Denomination>(change: &Change<D>) -> u64 {
+    change.iter().map(|(d, n)| d.to_cents() * n).sum()
+}
+
+fn pieces<D>(change: &Change<D>) -> u64 {
+    change.iter().map(|(_, n)| n).sum()
+}
+
+// biggest first, no zero counts
+fn well_formed<D: Denomination>(change: &Change<D>) -> bool {
+    change.iter().all(|&(_, n)| n > 0)
+        && change
+            .windows(2)
+            .all(|pair| pair[0].0.to_cents() > pair[1].0.to_cents())
+}
+
+#[test]
+fn random_systems() {
+    let mut rng = StdRng::seed_from_u64(42);
+    for _ in 0..SYSTEMS {
+        let mut system: Vec<u64> = (0..rng.gen_range(1..6))
+            .map(|_| rng.gen_range(1..40))
+            .collect();
+        if rng.gen_bool(0.7) {
+            system.push(1);
+        }
+
+        let mut greedy_was_worse = false;
+        for amount in 0..200 {
+            let greedy = greedy_change(amount, &system);
+            let optimal = optimal_change(amount, &system);
+            if let Some(greedy) = &greedy {
+                assert!(well_formed(greedy));
+                assert_eq!(total(greedy), amount);
+            }
+            match (&greedy, &optimal) {
+                (_, Some(optimal)) => {
+                    assert!(well_formed(optimal));
+                    assert_eq!(total(optimal), amount);
+                    if let Some(greedy) = &greedy {
+                        assert!(pieces(optimal) <= pieces(greedy));
+                        greedy_was_worse |= pieces(optimal) < pieces(greedy);
+                    }
+                }
+                // greedy can't find what optimal says isn't there
+                (Some(_), None) => panic!("greedy made {} from {:?}", amount, system),
+                (None, None) => {}
+            }
+            if system.contains(&1) {
+                assert!(optimal.is_some());
+            }
+        }
+        // it checks fewer amounts than this loop did, but shouldn't miss
+        // anything the loop found
+        if system.contains(&1) {
+            assert_eq!(
+                greedy_is_optimal(&system),
+                !greedy_was_worse,
+                "{:?}",
+                system
+            );
+        }
+    }
+}
+
+#[test]
+fn us_money() {
+    assert!(greedy_is_optimal(&Coin::ALL));
+    assert!(!greedy_is_optimal(&Bill::ALL)); // no 1 cent bill
+    assert!(greedy_is_optimal(&Piece::ALL));
+    for amount in 0..1_000 {
+        assert_eq!(
+            greedy_change(amount, &Piece::ALL),
+            optimal_change(amount, &Piece::ALL)
+        );
+    }
+    assert_eq!(
+        greedy_change(99, &Coin::ALL),
+        Some(vec![
+            (Coin::HalfDollar, 1),
+            (Coin::Quarter, 1),
+            (Coin::Dime, 2),
+            (Coin::Penny, 4)
+        ])
+    );
+    assert_eq!(greedy_change(150, &Bill::ALL), None);
+    // big amounts are fine for greedy, it doesn't count up to them
+    let rich = Purse::change_for(u64::MAX / 2);
+    assert_eq!(rich.total(), u64::MAX / 2);
+}
+
+#[test]
+fn odd_denominations() {
+    // no 1, so some amounts just can't be made
+    assert_eq!(optimal_change(7, &[5u64, 3]), None);
+    // and greedy gets stuck on some that can
+    assert_eq!(greedy_change(9, &[5u64, 3]), None);
+    assert_eq!(optimal_change(9, &[5u64, 3]), Some(vec![(3, 3)]));
+    // zeros and repeats are ignored
+    assert_eq!(optimal_change(10, &[0u64, 5, 5, 1]), Some(vec![(5, 2)]));
+    assert_eq!(greedy_change(3, &[0u64]), None);
+    assert_eq!(greedy_change(0, &[0u64]), Some(vec![]));
+}
+
+#[test]
+fn limited() {
+    // 3 dimes beats a quarter and 5 pennies, as long as there are 3 dimes
+    let till = [(25u64, 1), (10, 3), (5, 0), (1, 5)];
+    assert_eq!(limited_change(30, &till), Some(vec![(10, 3)]));
+    assert_eq!(
+        limited_change(30, &[(25u64, 1), (10, 2), (1, 5)]),
+        Some(vec![(25, 1), (1, 5)])
+    );
+    assert_eq!(limited_change(0, &till), Some(vec![]));
+    // everything there is, and then one more
+    assert_eq!(
+        limited_change(60, &till),
+        Some(vec![(25, 1), (10, 3), (1, 5)])
+    );
+    assert_eq!(limited_change(61, &till), None);
+    // 3 + 3 needs two 3s, and 4 + 1 + 1 two 1s
+    assert_eq!(
+        limited_change(6, &[(4u64, 1), (3, 2), (1, 1)]),
+        Some(vec![(3, 2)])
+    );
+    assert_eq!(limited_change(6, &[(4u64, 1), (3, 1), (1, 1)]), None);
+    // zero values and zero counts are ignored
+    assert_eq!(
+        limited_change(5, &[(0u64, 9), (5, 0), (1, 5)]),
+        Some(vec![(1, 5)])
+    );
+}
+
+#[test]
+fn limited_matches_optimal() {
+    let mut rng = StdRng::seed_from_u64(45);
+    for _ in 0..SYSTEMS {
+        let mut system: Vec<u64> = (0..rng.gen_range(1..5))
+            .map(|_| rng.gen_range(1..30))
+            .collect();
+        // one count for each, like a purse
+        system.sort_unstable();
+        system.dedup();
+        let counts: Vec<u64> = system.iter().map(|_| rng.gen_range(0..6)).collect();
+        let till: Vec<(u64, u64)> = system.iter().copied().zip(counts).collect();
+        // with plenty of everything it's the same as having no limit
+        let plenty: Vec<(u64, u64)> = system.iter().map(|&d| (d, 100)).collect();
+
+        for amount in 0..100 {
+            let optimal = optimal_change(amount, &system);
+            assert_eq!(
+                limited_change(amount, &plenty).map(|c| pieces(&c)),
+                optimal.as_ref().map(pieces),
+                "{} from {:?}",
+                amount,
+                system
+            );
+            if let Some(change) = limited_change(amount, &till) {
+                assert!(well_formed(&change));
+                assert_eq!(total(&change), amount);
+                for (d, n) in &change {
+                    let have: u64 = till.iter().filter(|(t, _)| t == d).map(|(_, c)| c).sum();
+                    assert!(*n <= have, "{} x {} from {:?}", n, d, till);
+                }
+                // never fewer than with no limit
+                assert!(pieces(&change) >= pieces(optimal.as_ref().unwrap()));
+            }
+        }
+    }
+}
+
+#[test]
+fn amounts_too_big_for_the_tables() {
+    assert_eq!(optimal_change(MAX_AMOUNT + 1, &[1u64]), None);
+    assert_eq!(optimal_change(u64::MAX, &[1u64]), None);
+    assert_eq!(limited_change(MAX_AMOUNT + 1, &[(1u64, u64::MAX)]), None);
+    assert_eq!(limited_change(u64::MAX, &[(1u64, u64::MAX)]), None);
+    // greedy doesn't need a table
+    assert!(greedy_change(u64::MAX, &[1u64]).is_some());
+    // right at the limit is fine
+    assert_eq!(
+        optimal_change(MAX_AMOUNT, &[1u64, 10_000]),
+        Some(vec![(10_000, 100)])
+    );
+
+    // too far apart to check, let alone add up
+    assert!(!greedy_is_optimal(&[u64::MAX, u64::MAX - 1, 1]));
+    assert!(!greedy_is_optimal(&[MAX_AMOUNT, 2, 1]));
+    assert!(greedy_is_optimal(&[MAX_AMOUNT - 2, 2, 1]));
+}
+
+#[test]
+fn taking_change_from_a_purse() {
+    let mut purse = Purse::new();
+    purse.add(Coin::Quarter);
+    purse.add_many(Coin::Dime, 3);
+    purse.add_many(Coin::Penny, 5);
+    let before = purse.clone();
+
+    let change = purse.take_change(30).unwrap();
+    assert_eq!(change.count(Coin::Dime), 3);
+    assert_eq!(change.pieces(), 3);
+    assert_eq!(purse.total(), 30);
+    assert_eq!(purse.count(Coin::Dime), 0);
+
+    // can't be made exactly, or more than there is: nothing comes out
+    let mut purse = before.clone();
+    assert_eq!(purse.take_change(9), None);
+    assert_eq!(purse.take_change(61), None);
+    // far too much to build a table for, it doesn't try
+    assert_eq!(purse.take_change(100_000_000_000), None);
+    assert_eq!(purse.take_change(u64::MAX), None);
+    assert_eq!(purse, before);
+
+    // all of it
+    assert_eq!(purse.take_change(60), Some(before));
+    assert!(purse.is_empty());
+    assert_eq!(purse.take_change(0), Some(Purse::new()));
+}
+
+#[test]
+fn purses() {
+    let mut purse = Purse::new();
+    assert!(purse.is_empty());
+    assert_eq!(purse.total(), 0);
+    purse.add(Coin::Penny);
+    purse.add(Piece::Bill(Bill::Hundred));
+    purse.add_many(Coin::Dollar, 3);
+    purse.add_many(Bill::One, 0); // adds nothing, leaves no trace
+    assert_eq!(purse.total(), 1 + 10_000 + 300);
+    assert_eq!(purse.pieces(), 5);
+    assert_eq!(purse.count(Coin::Dollar), 3);
+    assert_eq!(purse.count(Bill::One), 0);
+    assert_eq!(
+        purse.iter().collect::<Vec<_>>(),
+        [
+            (Piece::Coin(Coin::Penny), 1),
+            (Piece::Coin(Coin::Dollar), 3),
+            (Piece::Bill(Bill::Hundred), 1)
+        ]
+    );
+
+    assert_eq!(
+        purse.remove_many(Coin::Dollar, 4),
+        Err(NotEnough {
+            piece: Piece::Coin(Coin::Dollar),
+            wanted: 4,
+            have: 3
+        })
+    );
+    assert_eq!(purse.count(Coin::Dollar), 3); // untouched
+    purse.remove_many(Coin::Dollar, 3).unwrap();
+    purse.remove(Coin::Penny).unwrap();
+
+    // equal however the purse got that way
+    let mut other: Purse = vec![Bill::Fifty, Bill::Fifty].into_iter().collect();
+    other.remove_many(Bill::Fifty, 2).unwrap();
+    other.add(Bill::Hundred);
+    assert_eq!(purse, other);
+
+    purse.merge(Purse::change_for(141));
+    assert_eq!(purse.total(), 10_141);
+    assert_eq!(
+        Purse::change_for(141).to_string(),
+        "$1.41: 1 x $1 bill, 1 x quarter, 1 x dime, 1 x nickel, 1 x penny"
+    );
+    assert_eq!(Purse::new().to_string(), "$0.00");
+}
@@ -0,0 +1,64 @@
+// every state has to come back from its own abbreviation and name, and the
+// table behind them has to line up with the variants
+use ch6_enum_patternmatch::money::{UnknownState, UsState};
+use std::collections::HashSet;
+
+#[test]
+fn abbreviations_and_names_round_trip() {
+    for &state in UsState::ALL.iter() {
+        let abbreviation = state.abbreviation();
+        assert_eq!(abbreviation.len(), 2);
+        assert!(abbreviation.bytes().all(|b| b.is_ascii_uppercase()));
+        assert_eq!(abbreviation.parse(), Ok(state));
+        assert_eq!(abbreviation.to_lowercase().parse(), Ok(state));
+        assert_eq!(state.name().parse(), Ok(state));
+        assert_eq!(state.name().to_uppercase().parse(), Ok(state));
+        assert_eq!(state.to_string().parse(), Ok(state));
+    }
+    let abbreviations: HashSet<_> = UsState::ALL.iter().map(|s| s.abbreviation()).collect();
+    let names: HashSet<_> = UsState::ALL.iter().map(|s| s.name()).collect();
+    assert_eq!(abbreviations.len(), 56);
+    assert_eq!(names.len(), 56);
+}
+
+#[test]
+fn release_order() {
+    // ALL is in variant order, which is release order
+    for (i, state) in UsState::ALL.iter().enumerate() {
+        assert_eq!(state.quarter_number(), i + 1);
+    }
+    assert!(UsState::ALL.windows(2).all(|pair| pair[0] < pair[1]));
+
+    // five states a year, 1999 to 2008
+    let states: Vec<UsState> = UsState::ALL
+        .iter()
+        .copied()
+        .filter(|s| s.is_state())
+        .collect();
+    assert_eq!(states.len(), 50);
+    for year in 1999..=2008 {
+        assert_eq!(
+            states.iter().filter(|s| s.quarter_year() == year).count(),
+            5
+        );
+    }
+    // then DC and the territories
+    for state in UsState::ALL.iter().filter(|s| !s.is_state()) {
+        assert_eq!(state.quarter_year(), 2009);
+    }
+
+    assert_eq!(UsState::Delaware.quarter_year(), 1999);
+    assert_eq!(UsState::California.quarter_year(), 2005);
+    assert_eq!(UsState::Hawaii.quarter_number(), 50);
+    assert_eq!(UsState::Hawaii.quarter_year(), 2008);
+    assert_eq!(UsState::DistrictOfColumbia.abbreviation(), "DC");
+}
+
+#[test]
+fn unknown() {
+    for bad in &["", "XX", "Chicago", "Conneticut", "C A", "New  York"] {
+        assert_eq!(bad.parse::<UsState>(), Err(UnknownState(bad.to_string())));
+    }
+    // surrounding spaces are fine
+    assert_eq!(" ny ".parse(), Ok(UsState::NewYork));
+}
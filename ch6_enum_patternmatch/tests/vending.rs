@@ -0,0 +1,172 @@
+// the vending machine walked through every state, plus the ways a sale can
+// go wrong. the change it gives back is checked against optimal_change over
+// random coin boxes, from a seeded rng so failures repeat
+use ch6_enum_patternmatch::money::{optimal_change, Coin, Purse};
+use ch6_enum_patternmatch::vending::{Event, Machine, Output, State, VendError};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+fn machine() -> Machine {
+    let mut machine = Machine::new();
+    machine.stock("A1", "crisps", 125, 2);
+    machine.stock("B2", "gum", 35, 0);
+    for coin in &[Coin::Nickel, Coin::Dime, Coin::Quarter] {
+        machine.load_coins(*coin, 4);
+    }
+    machine
+}
+
+fn purse(coins: &[Coin]) -> Purse {
+    coins.iter().copied().collect()
+}
+
+#[test]
+fn a_sale_goes_through_every_state() {
+    let mut m = machine();
+    assert_eq!(m.state(), &State::Idle);
+
+    // Idle -> Accepting, and more coins stay in Accepting
+    assert_eq!(m.insert(Coin::Dollar), Ok(vec![]));
+    assert_eq!(m.state(), &State::Accepting { credit: 100 });
+    assert_eq!(
+        m.handle(Event::Insert(Coin::HalfDollar)),
+        Ok(vec![Output::Rejected(Coin::HalfDollar)])
+    );
+    m.insert(Coin::Quarter).unwrap();
+    m.insert(Coin::Quarter).unwrap();
+    assert_eq!(m.credit(), 150);
+
+    // Accepting -> Dispensing -> ReturningChange -> Idle in one call
+    let outputs = m.select("A1").unwrap();
+    assert_eq!(
+        outputs,
+        vec![
+            Output::Dispensed(String::from("crisps")),
+            Output::Change(purse(&[Coin::Quarter])),
+        ]
+    );
+    assert_eq!(m.state(), &State::Idle);
+    assert_eq!(
+        m.slots().find(|(code, _)| *code == "A1").unwrap().1.count,
+        1
+    );
+
+    // exact money skips ReturningChange
+    for _ in 0..5 {
+        m.insert(Coin::Quarter).unwrap();
+    }
+    assert_eq!(
+        m.select("A1"),
+        Ok(vec![Output::Dispensed(String::from("crisps"))])
+    );
+    assert_eq!(m.state(), &State::Idle);
+    assert_eq!(m.select("A1"), Err(VendError::SoldOut(String::from("A1"))));
+}
+
+#[test]
+fn bad_coins_and_bad_choices_change_nothing() {
+    let mut m = machine();
+    assert_eq!(
+        m.insert(Coin::Penny),
+        Ok(vec![Output::Rejected(Coin::Penny)])
+    );
+    assert_eq!(m.state(), &State::Idle);
+    assert_eq!(m.coin_box().count(Coin::Penny), 0);
+    assert_eq!(
+        m.select("A1"),
+        Err(VendError::NeedMore {
+            price: 125,
+            credit: 0
+        })
+    );
+    assert_eq!(m.cancel(), Ok(vec![]));
+
+    m.insert(Coin::Dime).unwrap();
+    let before = m.clone();
+    assert_eq!(
+        m.select("Z9"),
+        Err(VendError::UnknownSlot(String::from("Z9")))
+    );
+    assert_eq!(m.select("B2"), Err(VendError::SoldOut(String::from("B2"))));
+    assert_eq!(
+        m.select("A1"),
+        Err(VendError::NeedMore {
+            price: 125,
+            credit: 10
+        })
+    );
+    assert_eq!(m.state(), before.state());
+    assert_eq!(m.coin_box(), before.coin_box());
+
+    // a machine that only takes quarters bounces dimes even mid sale
+    let mut picky = Machine::accepting(&[Coin::Quarter]);
+    picky.insert(Coin::Quarter).unwrap();
+    assert_eq!(
+        picky.insert(Coin::Dime),
+        Ok(vec![Output::Rejected(Coin::Dime)])
+    );
+    assert_eq!(picky.state(), &State::Accepting { credit: 25 });
+}
+
+#[test]
+fn no_sale_without_the_change_for_it() {
+    let mut m = Machine::new();
+    m.stock("A1", "crisps", 65, 1);
+    m.insert(Coin::Dollar).unwrap();
+    assert_eq!(
+        m.select("A1"),
+        Err(VendError::ExactChangeOnly { change: 35 })
+    );
+    assert_eq!(m.state(), &State::Accepting { credit: 100 });
+    assert_eq!(m.slots().next().unwrap().1.count, 1);
+
+    // the customer can still back out, and gets their own coin back
+    assert_eq!(m.cancel(), Ok(vec![Output::Change(purse(&[Coin::Dollar]))]));
+    assert_eq!(m.state(), &State::Idle);
+    assert!(m.coin_box().is_empty());
+}
+
+#[test]
+fn cancel_returns_the_credit() {
+    let mut m = machine();
+    m.insert(Coin::Dime).unwrap();
+    m.insert(Coin::Dime).unwrap();
+    m.insert(Coin::Nickel).unwrap();
+    // 25 cents comes back as the one quarter, not the coins that went in
+    let outputs = m.cancel().unwrap();
+    assert_eq!(outputs, vec![Output::Change(purse(&[Coin::Quarter]))]);
+    assert_eq!(outputs[0].coins(), vec![Coin::Quarter]);
+    assert_eq!(m.state(), &State::Idle);
+    // what went in stays in the box, so it holds the same amount as before
+    assert_eq!(m.coin_box().total(), 4 * (5 + 10 + 25));
+    assert_eq!(m.coin_box().count(Coin::Dime), 6);
+}
+
+#[test]
+fn change_is_the_fewest_coins_the_box_can_manage() {
+    let mut rng = StdRng::seed_from_u64(47);
+    let coins = [Coin::Nickel, Coin::Dime, Coin::Quarter, Coin::Dollar];
+    for _ in 0..200 {
+        let mut m = Machine::new();
+        // plenty of every coin, so the box never runs short and the answer
+        // has to match the unlimited optimum
+        for coin in &coins {
+            m.load_coins(*coin, 20);
+        }
+        let price = rng.gen_range(1..=40) * 5;
+        m.stock("A1", "thing", price, 1);
+        while m.credit() < price {
+            m.insert(coins[rng.gen_range(0..coins.len())]).unwrap();
+        }
+        let due = m.credit() - price;
+        let outputs = m.select("A1").unwrap();
+        let given: Vec<Coin> = outputs.iter().flat_map(|o| o.coins()).collect();
+        assert_eq!(given.iter().map(|c| c.to_cents()).sum::<u64>(), due);
+        let best: u64 = optimal_change(due, &coins)
+            .unwrap()
+            .iter()
+            .map(|(_, n)| n)
+            .sum();
+        assert_eq!(given.len() as u64, best, "{} cents back", due);
+    }
+}